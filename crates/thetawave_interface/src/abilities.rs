@@ -1,6 +1,6 @@
 use bevy_ecs::{bundle::Bundle, component::Component, event::Event, system::Resource};
 use bevy_time::{Timer, TimerMode};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     audio::SoundEffectType, player::PlayerIDComponent, spawnable::ProjectileType,
@@ -57,13 +57,13 @@ pub struct AbilitiesResource {
 
 /// Identifier for ability slots
 /// Used for ability entities that are spawned as children of the player
-#[derive(Component, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[derive(Component, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
 pub enum AbilitySlotIDComponent {
     One,
     Two,
 }
 /// Component for tracking ability cooldowns
-#[derive(Component, Deserialize, Clone)]
+#[derive(Component, Serialize, Deserialize, Clone)]
 pub struct AbilityCooldownComponent {
     /// Stored seperately so that it can used with the player's cooldown multiplier
     /// to set the duration of the cooldown timer
@@ -1,11 +1,11 @@
 use std::time::Duration;
 
 use bevy_ecs::prelude::Event;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use strum_macros::Display;
 
 /// Background music types
-#[derive(Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
+#[derive(Serialize, Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
 pub enum BGMusicType {
     Game,
     Boss,
@@ -1,5 +1,6 @@
 use bevy_math::Vec2;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
 
 use crate::{
     abilities::{SlotOneAbilityType, SlotTwoAbilityType},
@@ -8,7 +9,7 @@ use crate::{
 };
 
 /// The playable character types. To a player, these will have different appearances and abilities.
-#[derive(Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, EnumString, Display, Clone, Debug, Hash, PartialEq, Eq)]
 pub enum CharacterType {
     Captain,
     Juggernaut,
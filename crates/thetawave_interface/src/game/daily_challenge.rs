@@ -0,0 +1,56 @@
+//! Results of daily challenge runs. The game records a result when a daily challenge run ends, and
+//! a storage backend persists them between sessions.
+use bevy_ecs::event::Event;
+use bevy_ecs_macros::Resource;
+use strum_macros::{Display, EnumString};
+
+use crate::character::CharacterType;
+
+/// How a daily challenge run ended
+#[derive(EnumString, Display, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DailyOutcome {
+    Victory,
+    Defeat,
+}
+
+/// The result of one daily challenge run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyResult {
+    /// Local date of the challenge, formatted as YYYY-MM-DD
+    pub date: String,
+    pub score: usize,
+    /// How long the run was played for, in seconds
+    pub time_secs: u64,
+    pub outcome: DailyOutcome,
+    /// Character of the first player
+    pub character: CharacterType,
+}
+
+/// Results of every daily challenge run that was played, for all days
+#[derive(
+    Debug, Default, Clone, PartialEq, Eq, Resource, derive_more::Deref, derive_more::DerefMut,
+)]
+pub struct DailyResultsResource(pub Vec<DailyResult>);
+
+impl DailyResultsResource {
+    /// Get the results of a day, from the highest score to the lowest. Results with the same
+    /// score are ranked by the shortest time.
+    pub fn get_leaderboard(&self, date: &str) -> Vec<&DailyResult> {
+        let mut leaderboard: Vec<&DailyResult> =
+            self.iter().filter(|result| result.date == date).collect();
+        leaderboard.sort_by(|a, b| b.score.cmp(&a.score).then(a.time_secs.cmp(&b.time_secs)));
+        leaderboard
+    }
+
+    /// Get the dates that have results, from the most recent to the oldest
+    pub fn get_dates(&self) -> Vec<&str> {
+        let mut dates: Vec<&str> = self.iter().map(|result| result.date.as_str()).collect();
+        dates.sort_unstable_by(|a, b| b.cmp(a));
+        dates.dedup();
+        dates
+    }
+}
+
+/// Sent after a result is added to `DailyResultsResource`, so that it can be persisted
+#[derive(Event, Debug, Clone)]
+pub struct DailyResultRecordedEvent(pub DailyResult);
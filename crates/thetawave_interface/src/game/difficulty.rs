@@ -0,0 +1,46 @@
+use bevy_ecs::system::Resource;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter, EnumString};
+
+/// How challenging a run is. Each level maps to a set of multipliers in the difficulties data
+/// file.
+#[derive(
+    Serialize,
+    Deserialize,
+    EnumString,
+    EnumIter,
+    Display,
+    Debug,
+    Default,
+    Hash,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+)]
+#[strum(ascii_case_insensitive)]
+pub enum DifficultyLevel {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+impl DifficultyLevel {
+    /// The next harder difficulty, wrapping around to the easiest
+    pub fn next(self) -> Self {
+        match self {
+            DifficultyLevel::Easy => DifficultyLevel::Normal,
+            DifficultyLevel::Normal => DifficultyLevel::Hard,
+            DifficultyLevel::Hard => DifficultyLevel::Nightmare,
+            DifficultyLevel::Nightmare => DifficultyLevel::Easy,
+        }
+    }
+}
+
+/// The difficulty chosen for the next or current run
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SelectedDifficultyResource {
+    pub level: DifficultyLevel,
+}
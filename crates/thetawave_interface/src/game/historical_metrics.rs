@@ -1,5 +1,6 @@
 //! Stats for games _before_ the currently running game. A value of 0 typically means that the
 //! corresponding systems are not 'online' to mutate the resources.
+use crate::game::difficulty::DifficultyLevel;
 use crate::spawnable::EnemyMobType;
 use bevy_ecs_macros::Resource;
 use std::collections::HashMap;
@@ -14,6 +15,10 @@ pub struct UserStat {
 pub type UserStatsByPlayerCacheT = HashMap<usize, UserStat>;
 pub type MobsKilledBy1PlayerCacheT = HashMap<EnemyMobType, usize>;
 pub type MobsKilledByPlayerCacheT = HashMap<usize, MobsKilledBy1PlayerCacheT>;
+pub type RunsByDifficulty1PlayerCacheT = HashMap<DifficultyLevel, usize>;
+pub type RunsByDifficultyByPlayerCacheT = HashMap<usize, RunsByDifficulty1PlayerCacheT>;
+pub type RunsByModifier1PlayerCacheT = HashMap<String, usize>;
+pub type RunsByModifierByPlayerCacheT = HashMap<usize, RunsByModifier1PlayerCacheT>;
 /// An in-memory cache of stats for games that have been completed. Keys are "user ids"
 #[derive(Debug, Default, Eq, PartialEq, Resource, derive_more::Deref, derive_more::DerefMut)]
 pub struct UserStatsByPlayerForCompletedGamesCache(pub UserStatsByPlayerCacheT);
@@ -41,5 +46,33 @@ pub struct MobKillsByPlayerForCompletedGames {
 #[derive(Debug, Default, Eq, PartialEq, Resource, derive_more::Deref, derive_more::DerefMut)]
 pub struct MobKillsByPlayerForCurrentGame(pub MobsKilledByPlayerCacheT);
 
+/// An in-memory cache of the number of completed games played on each difficulty. Keys are "user
+/// ids"
+#[derive(
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Resource,
+    derive_more::Deref,
+    derive_more::DerefMut,
+    derive_more::From,
+)]
+pub struct RunsByDifficultyForCompletedGames(pub RunsByDifficultyByPlayerCacheT);
+
+/// An in-memory cache of the number of completed games played with each run modifier, by the key
+/// of the modifier. Keys are "user ids"
+#[derive(
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Resource,
+    derive_more::Deref,
+    derive_more::DerefMut,
+    derive_more::From,
+)]
+pub struct RunsByModifierForCompletedGames(pub RunsByModifierByPlayerCacheT);
+
 /// The user id of the anonymous/"main" player. IOW "player 1".
 pub const DEFAULT_USER_ID: usize = 0;
@@ -1,7 +1,13 @@
+/// Results of daily challenge runs, for the daily leaderboards.
+pub mod daily_challenge;
 /// Expose counts about the progress/metrics for the currently running game, along with a plugin
 /// for all of the systems that mutate these counts. These counts start at
 /// 0 for each new game.
-
+pub mod difficulty;
 /// Resources/singletons with the within-game/run counts/metrics.
 pub mod historical_metrics;
 pub mod options;
+/// Run modifiers toggled before a run, which change how it plays.
+pub mod run_modifiers;
+/// Runs in progress that were saved, so that they can be resumed later.
+pub mod saved_runs;
@@ -0,0 +1,24 @@
+//! Run modifiers that players toggle before a run. What each modifier does is defined in the run
+//! modifiers data file, so they are referred to by their keys in that file.
+use bevy_ecs::system::Resource;
+
+/// Keys of the run modifiers toggled on for the next or current run
+#[derive(Resource, Debug, Default, Clone, PartialEq, Eq)]
+pub struct SelectedRunModifiersResource {
+    pub modifiers: Vec<String>,
+}
+
+impl SelectedRunModifiersResource {
+    pub fn is_active(&self, key: &str) -> bool {
+        self.modifiers.iter().any(|modifier| modifier == key)
+    }
+
+    /// Turn a modifier on if it is off and off if it is on
+    pub fn toggle(&mut self, key: &str) {
+        if self.is_active(key) {
+            self.modifiers.retain(|modifier| modifier != key);
+        } else {
+            self.modifiers.push(key.to_string());
+        }
+    }
+}
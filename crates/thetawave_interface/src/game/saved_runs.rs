@@ -0,0 +1,21 @@
+//! Save slots for runs in progress. The game serializes runs into these slots, and a storage
+//! backend persists them between sessions.
+use bevy_ecs::event::Event;
+use bevy_ecs_macros::Resource;
+use std::collections::HashMap;
+
+/// The save slot that runs are saved to and resumed from
+pub const DEFAULT_SAVE_SLOT: usize = 0;
+
+/// Serialized runs keyed by save slot. A slot without a saved run has no entry.
+#[derive(
+    Debug, Default, Clone, Eq, PartialEq, Resource, derive_more::Deref, derive_more::DerefMut,
+)]
+pub struct SavedRunsResource(pub HashMap<usize, String>);
+
+/// Sent after a slot in `SavedRunsResource` is written or cleared, so that the change can be
+/// persisted
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SaveSlotChangedEvent {
+    pub slot: usize,
+}
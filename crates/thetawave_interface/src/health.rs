@@ -3,6 +3,7 @@ use std::time::Duration;
 use bevy_ecs::prelude::{Component, Entity};
 use bevy_ecs_macros::Event;
 use bevy_time::{Timer, TimerMode};
+use serde::{Deserialize, Serialize};
 
 #[derive(Event)]
 pub struct DamageDealtEvent {
@@ -11,7 +12,7 @@ pub struct DamageDealtEvent {
 }
 
 /// Tracks health for an entity
-#[derive(Component, Default)]
+#[derive(Component, Serialize, Deserialize, Clone, Default)]
 pub struct HealthComponent {
     /// Current health value
     health: usize,
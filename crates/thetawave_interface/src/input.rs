@@ -21,6 +21,9 @@ pub enum MenuAction {
     ExitPauseMenu,
     PauseGame,
     ToggleTutorial,
+    ChangeDifficulty,
+    SelectRunModifier,
+    ToggleRunModifier,
     NavigateUp,
     NavigateDown,
 }
@@ -35,6 +38,8 @@ pub enum PlayerAction {
     MoveRight,
     SlotOneAbility,
     SlotTwoAbility,
+    ShopSelect,
+    ShopBuy,
 }
 
 /// The parsed input/key bindings used for the life of the  entire game. This is read from files/
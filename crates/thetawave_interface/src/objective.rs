@@ -1,33 +1,126 @@
 use bevy_ecs::prelude::Event;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::spawnable::{MobSegmentType, MobType};
+use crate::{
+    run::RunDefeatType,
+    spawnable::{AllyMobType, MobSegmentType, MobType},
+};
 
-// Event for sending damage dealt from mob reaching bottom of arena
+// Event for a mob or mob segment reaching the bottom of the arena, sent for every mob so that
+// escorted mobs without a defense interaction are delivered
 #[derive(Event)]
 pub struct MobReachedBottomGateEvent {
     pub mob_type: Option<MobType>,
     pub mob_segment_type: Option<MobSegmentType>,
-    pub defense_interaction: DefenseInteraction,
+    pub defense_interaction: Option<DefenseInteraction>,
 }
 
-/// Additional fail condition for a level
-#[derive(Deserialize, Clone, Debug)]
+/// Additional fail or completion condition for a level
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum Objective {
     /// Objective representing defense of a planet, structure, etc
     Defense(DefenseData),
+    /// Objective to get haulers safely to the bottom of the arena
+    Escort(EscortData),
+    /// Objective to stay alive for an amount of time
+    Survive(SurviveData),
+    /// Objective to destroy a number of a type of mob
+    Destroy(DestroyData),
+    /// Objective to collect an amount of money
+    Collect(CollectData),
 }
 
 impl Objective {
     pub fn get_name(self) -> String {
         match self {
             Objective::Defense(_) => "Defense".to_string(),
+            Objective::Escort(_) => "Escort".to_string(),
+            Objective::Survive(_) => "Survive".to_string(),
+            Objective::Destroy(destroy_data) => {
+                format!("Destroy {}", destroy_data.mob_type.get_name())
+            }
+            Objective::Collect(_) => "Collect".to_string(),
         }
     }
+
+    /// Returns the reason for the run being lost if the objective is failed
+    pub fn get_defeat_type(&self) -> Option<RunDefeatType> {
+        match self {
+            Objective::Defense(defense_data) => defense_data
+                .is_failed()
+                .then_some(RunDefeatType::DefenseDestroyed),
+            Objective::Escort(escort_data) => escort_data
+                .is_failed()
+                .then_some(RunDefeatType::EscortDestroyed),
+            Objective::Survive(_) => None,
+            Objective::Destroy(destroy_data) => destroy_data
+                .time_limit
+                .is_expired()
+                .then_some(RunDefeatType::ObjectiveTimeExpired),
+            Objective::Collect(collect_data) => collect_data
+                .time_limit
+                .is_expired()
+                .then_some(RunDefeatType::ObjectiveTimeExpired),
+        }
+    }
+
+    /// Returns if the objective is completed, which completes the level
+    pub fn is_completed(&self) -> bool {
+        match self {
+            Objective::Defense(_) => false,
+            Objective::Escort(escort_data) => escort_data.is_completed(),
+            Objective::Survive(survive_data) => survive_data.is_completed(),
+            Objective::Destroy(destroy_data) => destroy_data.is_completed(),
+            Objective::Collect(collect_data) => collect_data.is_completed(),
+        }
+    }
+
+    /// Progress of the objective from 0.0 to 1.0, for displaying in the level ui
+    pub fn get_percentage(&self) -> f32 {
+        match self {
+            Objective::Defense(defense_data) => defense_data.get_percentage(),
+            Objective::Escort(escort_data) => escort_data.get_percentage(),
+            Objective::Survive(survive_data) => survive_data.get_percentage(),
+            Objective::Destroy(destroy_data) => destroy_data.get_percentage(),
+            Objective::Collect(collect_data) => collect_data.get_percentage(),
+        }
+    }
+
+    /// Short description of the progress of the objective, for displaying in the level ui
+    pub fn get_progress_text(&self) -> String {
+        match self {
+            Objective::Defense(defense_data) => {
+                format!("{}/{}", defense_data.defense, defense_data.max_defense)
+            }
+            Objective::Escort(escort_data) => format!(
+                "{}/{} delivered",
+                escort_data.delivered, escort_data.deliveries
+            ),
+            Objective::Survive(survive_data) => format!(
+                "{:.0}s left",
+                (survive_data.time - survive_data.elapsed).max(0.0).ceil()
+            ),
+            Objective::Destroy(destroy_data) => {
+                format!("{}/{}", destroy_data.destroyed, destroy_data.count)
+            }
+            Objective::Collect(collect_data) => {
+                format!("{}/{}", collect_data.collected, collect_data.money)
+            }
+        }
+    }
+}
+
+/// Ratio of a current value to a target value, clamped to 1.0
+fn get_ratio(value: usize, target: usize) -> f32 {
+    if target > 0 {
+        (value as f32 / target as f32).min(1.0)
+    } else {
+        1.0
+    }
 }
 
 /// Tracks data for the defense objective
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct DefenseData {
     /// Current defense
     pub defense: usize,
@@ -61,7 +154,143 @@ impl DefenseData {
     }
 }
 
-#[derive(Deserialize, Clone)]
+/// Tracks data for the escort objective
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EscortData {
+    /// Types of haulers that need to be escorted
+    pub haulers: Vec<AllyMobType>,
+    /// Number of haulers that need to reach the bottom of the arena to complete the objective
+    pub deliveries: usize,
+    /// Number of haulers that can be destroyed before the objective is failed
+    pub max_losses: usize,
+    /// Haulers that have reached the bottom of the arena
+    #[serde(default)]
+    pub delivered: usize,
+    /// Haulers that have been destroyed
+    #[serde(default)]
+    pub lost: usize,
+}
+
+impl EscortData {
+    /// Returns if the mob is one of the haulers being escorted
+    pub fn is_escorted(&self, mob_type: &MobType) -> bool {
+        matches!(mob_type, MobType::Ally(ally_type) if self.haulers.contains(ally_type))
+    }
+
+    /// Returns if more haulers were destroyed than allowed
+    pub fn is_failed(&self) -> bool {
+        self.lost > self.max_losses
+    }
+
+    /// Returns if enough haulers reached the bottom of the arena
+    pub fn is_completed(&self) -> bool {
+        self.delivered >= self.deliveries
+    }
+
+    /// Percentage of the deliveries completed
+    pub fn get_percentage(&self) -> f32 {
+        get_ratio(self.delivered, self.deliveries)
+    }
+}
+
+/// Tracks data for the survive objective
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SurviveData {
+    /// Seconds to survive
+    pub time: f32,
+    /// Seconds survived so far
+    #[serde(default)]
+    pub elapsed: f32,
+}
+
+impl SurviveData {
+    /// Returns if the players have survived long enough
+    pub fn is_completed(&self) -> bool {
+        self.elapsed >= self.time
+    }
+
+    /// Percentage of the time survived
+    pub fn get_percentage(&self) -> f32 {
+        if self.time > 0.0 {
+            (self.elapsed / self.time).min(1.0)
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Optional time limit of an objective
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ObjectiveTimeLimit {
+    /// Seconds before the objective is failed, no limit if `None`
+    pub time: Option<f32>,
+    /// Seconds spent on the objective so far
+    #[serde(default)]
+    pub elapsed: f32,
+}
+
+impl ObjectiveTimeLimit {
+    /// Returns if the time limit has been reached
+    pub fn is_expired(&self) -> bool {
+        self.time.is_some_and(|time| self.elapsed >= time)
+    }
+}
+
+/// Tracks data for the destroy objective
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DestroyData {
+    /// Type of mob that needs to be destroyed
+    pub mob_type: MobType,
+    /// Number of mobs that need to be destroyed
+    pub count: usize,
+    /// Mobs destroyed so far
+    #[serde(default)]
+    pub destroyed: usize,
+    #[serde(default)]
+    pub time_limit: ObjectiveTimeLimit,
+}
+
+impl DestroyData {
+    /// Returns if enough mobs have been destroyed
+    pub fn is_completed(&self) -> bool {
+        self.destroyed >= self.count
+    }
+
+    /// Percentage of the mobs destroyed
+    pub fn get_percentage(&self) -> f32 {
+        get_ratio(self.destroyed, self.count)
+    }
+}
+
+/// Tracks data for the collect objective
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CollectData {
+    /// Money the players need to be holding to complete the objective
+    pub money: usize,
+    /// Money the players have gained since the level started
+    #[serde(default)]
+    pub collected: usize,
+    /// Money the players were holding when the objective was last updated, so that only money
+    /// gained during the level is collected
+    #[serde(default)]
+    pub held_money: Option<usize>,
+    #[serde(default)]
+    pub time_limit: ObjectiveTimeLimit,
+}
+
+impl CollectData {
+    /// Returns if the players have collected enough money
+    pub fn is_completed(&self) -> bool {
+        self.collected >= self.money
+    }
+
+    /// Percentage of the money collected
+    pub fn get_percentage(&self) -> f32 {
+        get_ratio(self.collected, self.money)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub enum DefenseInteraction {
     Heal(usize),
     Damage(usize),
@@ -4,6 +4,7 @@ use bevy_ecs::system::Resource;
 use bevy_ecs::{bundle::Bundle, prelude::Component};
 use bevy_math::Vec2;
 use derive_more::{Deref, DerefMut};
+use serde::{Deserialize, Serialize};
 
 /// Parameters for how to spawn new players. By default, the player can do anything.
 #[derive(Resource, Debug, Default, Deref, DerefMut)]
@@ -17,14 +18,14 @@ pub struct InputRestrictions {
 }
 
 /// Stores all available player slots
-#[derive(Resource, Debug)]
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
 pub struct PlayersResource {
     /// Vec of Optional players, an index is Some if a player has joined for that slot
     pub player_data: Vec<Option<PlayerData>>,
 }
 
 /// Information about a player slot
-#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PlayerData {
     /// The character that a joined player has chosen
     pub character: CharacterType,
@@ -34,7 +35,7 @@ pub struct PlayerData {
 
 /// Input method for a player
 /// Gamepad has a usize identifier
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub enum PlayerInput {
     Keyboard,
     Gamepad(usize),
@@ -92,7 +93,7 @@ impl PlayerBundle {
 }
 
 /// Identity of a player component, used for syncing UI
-#[derive(Component, Clone, Copy, PartialEq, Debug)]
+#[derive(Component, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
 pub enum PlayerIDComponent {
     One,
     Two,
@@ -179,7 +180,7 @@ impl Default for PlayerIncomingDamageComponent {
 
 /// Tracks what the player current has in inventory
 /// TODO: track stats of how many of each consumable has been picked up for the run
-#[derive(Component)]
+#[derive(Component, Serialize, Deserialize, Clone)]
 pub struct PlayerInventoryComponent {
     pub money: usize,
 }
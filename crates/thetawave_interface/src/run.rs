@@ -8,6 +8,8 @@ pub enum RunOutcomeType {
 pub enum RunDefeatType {
     PlayersDestroyed,
     DefenseDestroyed,
+    EscortDestroyed,
+    ObjectiveTimeExpired,
 }
 
 #[derive(Event)]
@@ -17,3 +19,9 @@ pub struct RunEndEvent {
 
 #[derive(Event)]
 pub struct CyclePhaseEvent;
+
+/// Event for showing text in the center of the screen in the middle of a phase
+#[derive(Event)]
+pub struct IntroTextEvent {
+    pub text: String,
+}
@@ -3,11 +3,11 @@ use std::default::Default;
 use bevy_ecs::{entity::Entity, event::Event};
 use bevy_ecs_macros::Component;
 use bevy_math::{Quat, Vec2};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumString};
 
 /// Type that encompasses all spawnable enemy mobs
-#[derive(Deserialize, EnumString, Display, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[derive(Serialize, Deserialize, EnumString, Display, Debug, Hash, PartialEq, Eq, Clone, Copy)]
 pub enum EnemyMobType {
     Pawn,
     Drone,
@@ -22,7 +22,7 @@ pub enum EnemyMobType {
 }
 
 /// Type that encompasses all spawnable entities
-#[derive(Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
+#[derive(Serialize, Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
 pub enum SpawnableType {
     Projectile(ProjectileType),
     Consumable(ConsumableType),
@@ -40,7 +40,7 @@ impl Default for SpawnableType {
 }
 
 /// Type that encompasses all weapon projectiles
-#[derive(Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display, Copy)]
+#[derive(Serialize, Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display, Copy)]
 pub enum ProjectileType {
     Blast(Faction),
     Bullet(Faction),
@@ -56,7 +56,7 @@ impl ProjectileType {
 }
 
 /// Factions
-#[derive(Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display, Copy)]
+#[derive(Serialize, Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display, Copy)]
 pub enum Faction {
     Ally,
     Enemy,
@@ -64,7 +64,7 @@ pub enum Faction {
 }
 
 /// Type that encompasses all spawnable mobs
-#[derive(Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
+#[derive(Serialize, Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
 pub enum MobType {
     Enemy(EnemyMobType),
     Ally(AllyMobType),
@@ -98,14 +98,14 @@ impl MobType {
     }
 }
 
-#[derive(Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
+#[derive(Serialize, Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
 pub enum MobSegmentType {
     Neutral(NeutralMobSegmentType),
     Enemy(EnemyMobSegmentType),
 }
 
 /// Type that encompasses all spawnable ally mobs
-#[derive(Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
+#[derive(Serialize, Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
 pub enum AllyMobType {
     Hauler2,
     Hauler3,
@@ -113,14 +113,14 @@ pub enum AllyMobType {
 }
 
 /// Type that encompasses all spawnable ally mob segments
-#[derive(Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
+#[derive(Serialize, Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
 pub enum NeutralMobSegmentType {
     HaulerBack,
     HaulerMiddle,
     TutorialHaulerBack,
 }
 
-#[derive(Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
+#[derive(Serialize, Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
 pub enum EnemyMobSegmentType {
     CrustlingTentacle1,
     CrustlingTentacle2,
@@ -135,14 +135,14 @@ pub enum EnemyMobSegmentType {
 }
 
 /// Type that encompasses all spawnable neutral mobs
-#[derive(Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
+#[derive(Serialize, Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
 pub enum NeutralMobType {
     MoneyAsteroid,
     TutorialDrone,
 }
 
 /// Type that encompasses all spawnable consumables
-#[derive(Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
+#[derive(Serialize, Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
 pub enum ConsumableType {
     Money1,
     Money3,
@@ -152,7 +152,7 @@ pub enum ConsumableType {
 }
 
 /// Type that encompasses all spawnable items
-#[derive(Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
+#[derive(Serialize, Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
 pub enum ItemType {
     EnhancedPlating,
     /*
@@ -174,7 +174,7 @@ pub enum ItemType {
 }
 
 /// Type that encompasses all spawnable effects
-#[derive(Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display, Default)]
+#[derive(Serialize, Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display, Default)]
 pub enum EffectType {
     AllyBlastExplosion,
     AllyBlastDespawn,
@@ -192,7 +192,7 @@ pub enum EffectType {
 }
 
 /// Subtype of effect for text effects
-#[derive(Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
+#[derive(Serialize, Deserialize, Debug, Hash, PartialEq, Eq, Clone, Display)]
 pub enum TextEffectType {
     DamageDealt,
     ConsumableCollected(ConsumableType),
@@ -224,6 +224,17 @@ pub struct SpawnMobEvent {
     pub rotation: Quat,
 
     pub boss: bool,
+    /// Entity that spawned the mob, if it was spawned by another mob or mob segment
+    pub parent: Option<Entity>,
+}
+
+/// Event for spawning mob segments on their own, without jointing them to a mob
+#[derive(Event)]
+pub struct SpawnMobSegmentEvent {
+    /// Type of mob segment to spawn
+    pub mob_segment_type: MobSegmentType,
+    /// Position to spawn mob segment
+    pub position: Vec2,
 }
 
 #[derive(Component)]
@@ -241,7 +252,20 @@ pub struct SpawnItemEvent {
 #[derive(Component)]
 pub struct AttractToClosestPlayerComponent;
 
-#[derive(Deserialize, Clone, Debug)]
+/// How a spawnable picks what to aim at, for rotating toward its target and firing aimed weapons
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub enum TargetingStrategy {
+    #[default]
+    ClosestPlayer,
+    LowestHealthPlayer,
+    /// A random player, kept as the target until it is gone
+    RandomPlayer,
+    /// The closest mob on the side of the players, such as haulers
+    ClosestAllyMob,
+    FixedPoint(Vec2),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum SpawnPosition {
     Global(Vec2),
     Local(Vec2),
@@ -6,6 +6,7 @@ pub enum AppStates {
     #[default]
     LoadingAssets,
     MainMenu,
+    DailyLeaderboard,
     Instructions,
     CharacterSelection,
     InitializeRun,
@@ -39,5 +40,8 @@ pub struct PauseCleanup;
 #[derive(Component)]
 pub struct InstructionsCleanup;
 
+#[derive(Component)]
+pub struct DailyLeaderboardCleanup;
+
 #[derive(Component)]
 pub struct CharacterSelectionCleanup;
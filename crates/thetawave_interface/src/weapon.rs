@@ -65,6 +65,9 @@ pub struct WeaponProjectileData {
     pub speed: f32,
     /// Angle in radians of spawned projectiles
     pub direction: f32,
+    /// Fire toward the target of the spawnable instead of in `direction`, if it has a target
+    #[serde(default)]
+    pub aimed: bool,
     /// Time before spawned projectiles despawn
     pub despawn_time: f32,
     /// Number of projectiles spawned at once
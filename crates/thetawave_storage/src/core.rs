@@ -11,6 +11,10 @@ const THETAWAVE_DB_FILE: &'static str = "thetawave.sqlite";
 pub(super) const USERSTAT: &'static str = "UserStat";
 pub(super) const ENEMY_KILL_HISTORY_TABLE_NAME: &'static str = "EnemiesKilled";
 pub(super) const OPTIONS_TABLE_NAME: &'static str = "Options";
+pub(super) const RUNS_BY_DIFFICULTY_TABLE_NAME: &'static str = "RunsByDifficulty";
+pub(super) const RUNS_BY_MODIFIER_TABLE_NAME: &'static str = "RunsByModifier";
+pub(super) const SAVED_RUNS_TABLE_NAME: &'static str = "SavedRuns";
+pub(super) const DAILY_RESULTS_TABLE_NAME: &'static str = "DailyResults";
 
 #[derive(Error, Debug, derive_more::From)]
 pub(super) enum OurDBError {
@@ -58,6 +62,42 @@ pub(super) fn setup_db(conn: Connection) -> rusqlite::Result<()> {
     )"
     );
 
+    let create_runs_by_difficulty_table_sql = format!(
+        "CREATE TABLE IF NOT EXISTS {RUNS_BY_DIFFICULTY_TABLE_NAME} (
+        userId INTEGER NOT NULL,
+        difficulty VARCHAR(255) NOT NULL,
+        nRuns INTEGER NOT NULL DEFAULT 0,
+        PRIMARY KEY (userId, difficulty)
+    )"
+    );
+
+    let create_runs_by_modifier_table_sql = format!(
+        "CREATE TABLE IF NOT EXISTS {RUNS_BY_MODIFIER_TABLE_NAME} (
+        userId INTEGER NOT NULL,
+        modifier VARCHAR(255) NOT NULL,
+        nRuns INTEGER NOT NULL DEFAULT 0,
+        PRIMARY KEY (userId, modifier)
+    )"
+    );
+
+    let create_saved_runs_table_sql = format!(
+        "CREATE TABLE IF NOT EXISTS {SAVED_RUNS_TABLE_NAME} (
+        saveSlot INTEGER PRIMARY KEY NOT NULL,
+        runData TEXT NOT NULL
+    )"
+    );
+
+    let create_daily_results_table_sql = format!(
+        "CREATE TABLE IF NOT EXISTS {DAILY_RESULTS_TABLE_NAME} (
+        resultId INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+        challengeDate VARCHAR(10) NOT NULL,
+        score INTEGER NOT NULL,
+        timeSecs INTEGER NOT NULL,
+        outcome VARCHAR(255) NOT NULL,
+        character VARCHAR(255) NOT NULL
+    )"
+    );
+
     let create_options_table_sql = format!(
         "CREATE TABLE IF NOT EXISTS {OPTIONS_TABLE_NAME} (
         optionsProfileId INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
@@ -69,6 +109,13 @@ pub(super) fn setup_db(conn: Connection) -> rusqlite::Result<()> {
     conn.execute(&create_user_stats_sql, []).map(|_| ())?;
     conn.execute(&create_enemies_killed_table_sql, [])
         .map(|_| ())?;
+    conn.execute(&create_runs_by_difficulty_table_sql, [])
+        .map(|_| ())?;
+    conn.execute(&create_runs_by_modifier_table_sql, [])
+        .map(|_| ())?;
+    conn.execute(&create_saved_runs_table_sql, []).map(|_| ())?;
+    conn.execute(&create_daily_results_table_sql, [])
+        .map(|_| ())?;
     conn.execute(&create_options_table_sql, []).map(|_| ())?;
 
     // insert a default options row if it is not in the db
@@ -0,0 +1,69 @@
+use crate::core::{get_db, OurDBError, DAILY_RESULTS_TABLE_NAME};
+use bevy::log::error;
+use rusqlite::{params, Result};
+use thetawave_interface::character::CharacterType;
+use thetawave_interface::game::daily_challenge::{DailyOutcome, DailyResult};
+
+pub(super) fn add_daily_result(result: &DailyResult) -> Result<(), OurDBError> {
+    let stmt_raw = format!(
+        "
+    INSERT INTO {DAILY_RESULTS_TABLE_NAME} (challengeDate, score, timeSecs, outcome, character)
+    VALUES (?1,  ?2, ?3, ?4, ?5)"
+    );
+    let conn = get_db()?;
+    conn.prepare(&stmt_raw)?.execute(params![
+        result.date,
+        result.score,
+        result.time_secs,
+        result.outcome.to_string(),
+        result.character.to_string(),
+    ])?;
+    Ok(())
+}
+
+fn _get_daily_results() -> Result<Vec<DailyResult>, OurDBError> {
+    let stmt_raw = format!(
+        "
+    SELECT challengeDate, score, timeSecs, outcome, character FROM {DAILY_RESULTS_TABLE_NAME}
+    ORDER BY challengeDate DESC, score DESC"
+    );
+    let conn = get_db()?;
+    let mut stmt = conn.prepare(&stmt_raw)?;
+    let rows = stmt.query([])?;
+    rows.mapped(|r| {
+        Ok((
+            r.get::<usize, String>(0)?,
+            r.get::<usize, usize>(1)?,
+            r.get::<usize, u64>(2)?,
+            r.get::<usize, String>(3)?,
+            r.get::<usize, String>(4)?,
+        ))
+    })
+    .collect::<Result<Vec<(String, usize, u64, String, String)>, rusqlite::Error>>()?
+    .into_iter()
+    .map(|(date, score, time_secs, outcome, character)| {
+        Ok(DailyResult {
+            date,
+            score,
+            time_secs,
+            outcome: outcome.parse::<DailyOutcome>().map_err(|e| {
+                OurDBError::InternalError(format!("Failed to read daily outcome from db {}", e))
+            })?,
+            character: character.parse::<CharacterType>().map_err(|e| {
+                OurDBError::InternalError(format!("Failed to read character from db {}", e))
+            })?,
+        })
+    })
+    .collect()
+}
+
+/// Returns the results of every daily challenge run that was played.
+pub fn get_daily_results() -> Vec<DailyResult> {
+    _get_daily_results().unwrap_or_else(|e| {
+        error!(
+            "Failed to get daily results from db. Empty result fallback. {}",
+            e
+        );
+        Default::default()
+    })
+}
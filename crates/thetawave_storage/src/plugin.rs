@@ -2,14 +2,22 @@
 use bevy::prelude::*;
 use thetawave_interface::game::options::{GameOptions, DEFAULT_OPTIONS_PROFILE_ID};
 
+use crate::daily_results::{add_daily_result, get_daily_results};
 use crate::options::get_game_options;
+use crate::saved_runs::{delete_saved_run, get_saved_runs, set_saved_run};
 use crate::user_stats::{
-    get_mob_killed_counts_for_user, get_user_stats, set_user_stats_for_user_id,
+    get_mob_killed_counts_for_user, get_runs_by_difficulty_for_user, get_runs_by_modifier_for_user,
+    get_user_stats, set_runs_count_for_difficulty_for_user, set_runs_count_for_modifier_for_user,
+    set_user_stats_for_user_id,
 };
+use thetawave_interface::game::daily_challenge::{DailyResultRecordedEvent, DailyResultsResource};
 use thetawave_interface::game::historical_metrics::{
-    MobKillsByPlayerForCompletedGames, MobsKilledByPlayerCacheT, UserStatsByPlayerCacheT,
+    MobKillsByPlayerForCompletedGames, MobsKilledByPlayerCacheT, RunsByDifficultyByPlayerCacheT,
+    RunsByDifficultyForCompletedGames, RunsByModifierByPlayerCacheT,
+    RunsByModifierForCompletedGames, UserStatsByPlayerCacheT,
     UserStatsByPlayerForCompletedGamesCache, DEFAULT_USER_ID,
 };
+use thetawave_interface::game::saved_runs::{SaveSlotChangedEvent, SavedRunsResource};
 use thetawave_interface::states;
 
 use super::core::{get_db, setup_db};
@@ -44,6 +52,49 @@ fn flush_mobs_killed_for_completed_games_counters_to_db(
         }
     }
 }
+fn flush_runs_by_difficulty_for_completed_games_to_db(
+    runs_by_difficulty: Res<RunsByDifficultyForCompletedGames>,
+) {
+    if let Some(runs) = (**runs_by_difficulty).get(&DEFAULT_USER_ID) {
+        for (difficulty, n_runs) in runs {
+            set_runs_count_for_difficulty_for_user(DEFAULT_USER_ID, difficulty, *n_runs)
+                .unwrap_or_else(|e| error!("Error setting runs count for difficulty: {e}"));
+        }
+    }
+}
+fn flush_runs_by_modifier_for_completed_games_to_db(
+    runs_by_modifier: Res<RunsByModifierForCompletedGames>,
+) {
+    if let Some(runs) = (**runs_by_modifier).get(&DEFAULT_USER_ID) {
+        for (modifier, n_runs) in runs {
+            set_runs_count_for_modifier_for_user(DEFAULT_USER_ID, modifier, *n_runs)
+                .unwrap_or_else(|e| error!("Error setting runs count for modifier: {e}"));
+        }
+    }
+}
+/// Write the save slots that changed, deleting the ones that were cleared. Runs last so that runs
+/// saved while the game is closing are written.
+fn flush_changed_save_slots_to_db(
+    mut save_slot_changed_event_reader: EventReader<SaveSlotChangedEvent>,
+    saved_runs: Res<SavedRunsResource>,
+) {
+    for event in save_slot_changed_event_reader.read() {
+        match saved_runs.get(&event.slot) {
+            Some(run_data) => set_saved_run(event.slot, run_data),
+            None => delete_saved_run(event.slot),
+        }
+        .unwrap_or_else(|e| error!("Error flushing save slot {}: {e}", event.slot));
+    }
+}
+/// Write the results of daily challenge runs as they are recorded
+fn flush_daily_results_to_db(
+    mut daily_result_recorded_event_reader: EventReader<DailyResultRecordedEvent>,
+) {
+    for event in daily_result_recorded_event_reader.read() {
+        add_daily_result(&event.0)
+            .unwrap_or_else(|e| error!("Error adding daily result to db: {e}"));
+    }
+}
 impl Plugin for DBPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(states::AppStates::LoadingAssets), db_setup_system);
@@ -52,14 +103,33 @@ impl Plugin for DBPlugin {
             (
                 load_user_stats_cache_from_db,
                 load_mob_kills_cache_from_db,
+                load_runs_by_difficulty_cache_from_db,
+                load_runs_by_modifier_cache_from_db,
+                load_saved_runs_from_db,
+                load_daily_results_from_db,
                 load_game_options_from_db,
             ),
         );
         app.add_systems(
-            OnExit(states::AppStates::GameOver),
+            Last,
+            (flush_changed_save_slots_to_db, flush_daily_results_to_db),
+        );
+        // lost runs are counted when leaving the game over screen for the main menu, rather than
+        // to continue the run
+        app.add_systems(
+            OnEnter(states::AppStates::MainMenu),
             (
                 flush_user_stats_for_completed_games_to_db,
                 flush_mobs_killed_for_completed_games_counters_to_db,
+                flush_runs_by_difficulty_for_completed_games_to_db,
+                flush_runs_by_modifier_for_completed_games_to_db,
+            ),
+        );
+        app.add_systems(
+            OnExit(states::AppStates::Victory),
+            (
+                flush_runs_by_difficulty_for_completed_games_to_db,
+                flush_runs_by_modifier_for_completed_games_to_db,
             ),
         );
     }
@@ -93,6 +163,28 @@ fn load_mob_kills_cache_from_db(mut mob_kills_cache: ResMut<MobKillsByPlayerForC
     (**mob_kills_cache) =
         MobsKilledByPlayerCacheT::from([(0, get_mob_killed_counts_for_user(DEFAULT_USER_ID))]);
 }
+fn load_runs_by_difficulty_cache_from_db(
+    mut runs_by_difficulty_cache: ResMut<RunsByDifficultyForCompletedGames>,
+) {
+    (**runs_by_difficulty_cache) = RunsByDifficultyByPlayerCacheT::from([(
+        DEFAULT_USER_ID,
+        get_runs_by_difficulty_for_user(DEFAULT_USER_ID),
+    )]);
+}
+fn load_runs_by_modifier_cache_from_db(
+    mut runs_by_modifier_cache: ResMut<RunsByModifierForCompletedGames>,
+) {
+    (**runs_by_modifier_cache) = RunsByModifierByPlayerCacheT::from([(
+        DEFAULT_USER_ID,
+        get_runs_by_modifier_for_user(DEFAULT_USER_ID),
+    )]);
+}
+fn load_saved_runs_from_db(mut saved_runs: ResMut<SavedRunsResource>) {
+    **saved_runs = get_saved_runs();
+}
+fn load_daily_results_from_db(mut daily_results: ResMut<DailyResultsResource>) {
+    **daily_results = get_daily_results();
+}
 fn db_setup_system() {
     match get_db() {
         Ok(conn) => setup_db(conn).unwrap_or_else(|e| {
@@ -107,18 +199,34 @@ fn db_setup_system() {
 #[cfg(test)]
 mod test {
     use crate::core::THETAWAVE_DB_PATH_ENVVAR;
+    use crate::daily_results::get_daily_results;
     use crate::plugin::DBPlugin;
-    use crate::user_stats::{get_mob_killed_counts_for_user, get_user_stats};
+    use crate::saved_runs::get_saved_runs;
+    use crate::user_stats::{
+        get_mob_killed_counts_for_user, get_runs_by_difficulty_for_user,
+        get_runs_by_modifier_for_user, get_user_stats,
+    };
     use bevy::log::{Level, LogPlugin};
-    use bevy::prelude::{default, App, NextState, OnEnter, ResMut};
+    use bevy::prelude::{default, App, EventWriter, NextState, OnEnter, ResMut};
     use bevy::MinimalPlugins;
+    use std::collections::HashMap;
     use std::ffi::{OsStr, OsString};
     use tempdir;
+    use thetawave_interface::character::CharacterType;
+    use thetawave_interface::game::daily_challenge::{
+        DailyOutcome, DailyResult, DailyResultRecordedEvent, DailyResultsResource,
+    };
+    use thetawave_interface::game::difficulty::DifficultyLevel;
     use thetawave_interface::game::historical_metrics::{
         MobKillsByPlayerForCompletedGames, MobsKilledBy1PlayerCacheT, MobsKilledByPlayerCacheT,
-        UserStat, UserStatsByPlayerForCompletedGamesCache, DEFAULT_USER_ID,
+        RunsByDifficulty1PlayerCacheT, RunsByDifficultyForCompletedGames,
+        RunsByModifier1PlayerCacheT, RunsByModifierForCompletedGames, UserStat,
+        UserStatsByPlayerForCompletedGamesCache, DEFAULT_USER_ID,
     };
     use thetawave_interface::game::options::GameOptions;
+    use thetawave_interface::game::saved_runs::{
+        SaveSlotChangedEvent, SavedRunsResource, DEFAULT_SAVE_SLOT,
+    };
     use thetawave_interface::spawnable::EnemyMobType;
     use thetawave_interface::states::AppStates;
 
@@ -170,8 +278,8 @@ mod test {
         (*s).set(AppStates::GameOver);
     }
 
-    fn set_dummy_terminal_game_state(mut s: ResMut<NextState<AppStates>>) {
-        (*s).set(AppStates::Instructions);
+    fn set_main_menu_state(mut s: ResMut<NextState<AppStates>>) {
+        (*s).set(AppStates::MainMenu);
     }
 
     fn clear_completed_games_metrics(
@@ -190,6 +298,47 @@ mod test {
             MobsKilledBy1PlayerCacheT::from([(EnemyMobType::Drone, N_DRONES)]),
         );
     }
+    fn set_n_hard_runs_for_p1_in_completed_games_cache<const N_RUNS: usize>(
+        mut runs_by_difficulty: ResMut<RunsByDifficultyForCompletedGames>,
+    ) {
+        (**runs_by_difficulty).insert(
+            DEFAULT_USER_ID,
+            RunsByDifficulty1PlayerCacheT::from([(DifficultyLevel::Hard, N_RUNS)]),
+        );
+    }
+    fn set_n_modifier_runs_for_p1_in_completed_games_cache<const N_RUNS: usize>(
+        mut runs_by_modifier: ResMut<RunsByModifierForCompletedGames>,
+    ) {
+        (**runs_by_modifier).insert(
+            DEFAULT_USER_ID,
+            RunsByModifier1PlayerCacheT::from([("NoHealthDrops".to_string(), N_RUNS)]),
+        );
+    }
+    fn set_saved_run_in_default_slot(
+        mut saved_runs: ResMut<SavedRunsResource>,
+        mut save_slot_changed: EventWriter<SaveSlotChangedEvent>,
+    ) {
+        (**saved_runs).insert(DEFAULT_SAVE_SLOT, "(saved: true)".to_string());
+        save_slot_changed.send(SaveSlotChangedEvent {
+            slot: DEFAULT_SAVE_SLOT,
+        });
+    }
+    fn dummy_daily_result() -> DailyResult {
+        DailyResult {
+            date: "2024-05-01".to_string(),
+            score: 1200,
+            time_secs: 300,
+            outcome: DailyOutcome::Defeat,
+            character: CharacterType::Juggernaut,
+        }
+    }
+    fn record_daily_result(
+        mut daily_results: ResMut<DailyResultsResource>,
+        mut daily_result_recorded: EventWriter<DailyResultRecordedEvent>,
+    ) {
+        (**daily_results).push(dummy_daily_result());
+        daily_result_recorded.send(DailyResultRecordedEvent(dummy_daily_result()));
+    }
     fn set_user_stats_for_completed_games<
         const N_GAMES_LOST: usize,
         const TOTAL_SHOTS_HIT: usize,
@@ -218,6 +367,12 @@ mod test {
             })
             .insert_resource(MobKillsByPlayerForCompletedGames::default())
             .insert_resource(UserStatsByPlayerForCompletedGamesCache::default())
+            .insert_resource(RunsByDifficultyForCompletedGames::default())
+            .insert_resource(RunsByModifierForCompletedGames::default())
+            .insert_resource(SavedRunsResource::default())
+            .add_event::<SaveSlotChangedEvent>()
+            .insert_resource(DailyResultsResource::default())
+            .add_event::<DailyResultRecordedEvent>()
             .insert_resource(GameOptions::default());
         app
     }
@@ -226,6 +381,8 @@ mod test {
         const N_GAMES_PLAYED: usize = 2;
         const TOTAL_SHOTS_HIT: usize = 10;
         const TOTAL_SHOTS_FIRED: usize = 15;
+        const N_HARD_RUNS: usize = 3;
+        const N_MODIFIER_RUNS: usize = 4;
 
         let mob_kills_after_1_game =
             MobKillsByPlayerForCompletedGames::from(MobsKilledByPlayerCacheT::from([(
@@ -238,6 +395,10 @@ mod test {
                 OnEnter(AppStates::Game),
                 (
                     set_n_drones_killed_for_p1_in_completed_games_cache::<N_DRONES_KILLED>,
+                    set_n_hard_runs_for_p1_in_completed_games_cache::<N_HARD_RUNS>,
+                    set_n_modifier_runs_for_p1_in_completed_games_cache::<N_MODIFIER_RUNS>,
+                    set_saved_run_in_default_slot,
+                    record_daily_result,
                     set_user_stats_for_completed_games::<
                         N_GAMES_PLAYED,
                         TOTAL_SHOTS_HIT,
@@ -246,7 +407,7 @@ mod test {
                 ),
             )
             .add_systems(OnEnter(AppStates::Game), set_game_over_state)
-            .add_systems(OnEnter(AppStates::GameOver), set_dummy_terminal_game_state);
+            .add_systems(OnEnter(AppStates::GameOver), set_main_menu_state);
 
         app.update();
         app.update();
@@ -275,5 +436,18 @@ mod test {
                 .get(&DEFAULT_USER_ID)
                 .unwrap()
         );
+        assert_eq!(
+            get_runs_by_difficulty_for_user(DEFAULT_USER_ID),
+            RunsByDifficulty1PlayerCacheT::from([(DifficultyLevel::Hard, N_HARD_RUNS)])
+        );
+        assert_eq!(
+            get_runs_by_modifier_for_user(DEFAULT_USER_ID),
+            RunsByModifier1PlayerCacheT::from([("NoHealthDrops".to_string(), N_MODIFIER_RUNS)])
+        );
+        assert_eq!(
+            get_saved_runs(),
+            HashMap::from([(DEFAULT_SAVE_SLOT, "(saved: true)".to_string())])
+        );
+        assert_eq!(get_daily_results(), vec![dummy_daily_result()]);
     }
 }
@@ -0,0 +1,52 @@
+use crate::core::{get_db, OurDBError, SAVED_RUNS_TABLE_NAME};
+use bevy::log::error;
+use rusqlite::{params, Result};
+use std::collections::HashMap;
+
+pub(super) fn set_saved_run(slot: usize, run_data: &str) -> Result<(), OurDBError> {
+    let stmt_raw = format!(
+        "
+    INSERT OR REPLACE INTO {SAVED_RUNS_TABLE_NAME} (saveSlot, runData)
+    VALUES (?1,  ?2)
+    ON CONFLICT DO UPDATE SET runData=?2"
+    );
+    let conn = get_db()?;
+    conn.prepare(&stmt_raw)?.execute(params![slot, run_data])?;
+    Ok(())
+}
+
+pub(super) fn delete_saved_run(slot: usize) -> Result<(), OurDBError> {
+    let stmt_raw = format!(
+        "
+    DELETE FROM {SAVED_RUNS_TABLE_NAME}
+    WHERE saveSlot=?1"
+    );
+    let conn = get_db()?;
+    conn.prepare(&stmt_raw)?.execute([slot])?;
+    Ok(())
+}
+
+fn _get_saved_runs() -> Result<HashMap<usize, String>, OurDBError> {
+    let stmt_raw = format!(
+        "
+    SELECT saveSlot, runData FROM {SAVED_RUNS_TABLE_NAME}"
+    );
+    let conn = get_db()?;
+    let mut stmt = conn.prepare(&stmt_raw)?;
+    let rows = stmt.query([])?;
+    // The DB primary key guarantees that translating from a vec -> hashmap doesnt lose elements
+    Ok(rows
+        .mapped(|r| Ok((r.get::<usize, usize>(0)?, r.get::<usize, String>(1)?)))
+        .collect::<Result<HashMap<usize, String>, rusqlite::Error>>()?)
+}
+
+/// Returns the serialized runs in each save slot.
+pub fn get_saved_runs() -> HashMap<usize, String> {
+    _get_saved_runs().unwrap_or_else(|e| {
+        error!(
+            "Failed to get saved runs from db. Empty result fallback. {}",
+            e
+        );
+        Default::default()
+    })
+}
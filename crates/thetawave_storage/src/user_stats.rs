@@ -1,9 +1,15 @@
-use crate::core::{get_db, OurDBError, ENEMY_KILL_HISTORY_TABLE_NAME, USERSTAT};
+use crate::core::{
+    get_db, OurDBError, ENEMY_KILL_HISTORY_TABLE_NAME, RUNS_BY_DIFFICULTY_TABLE_NAME,
+    RUNS_BY_MODIFIER_TABLE_NAME, USERSTAT,
+};
 use bevy::log::{error, info};
 use rusqlite::{params, Result};
+use thetawave_interface::game::difficulty::DifficultyLevel;
 use thetawave_interface::spawnable::EnemyMobType;
 
-use thetawave_interface::game::historical_metrics::{MobsKilledBy1PlayerCacheT, UserStat};
+use thetawave_interface::game::historical_metrics::{
+    MobsKilledBy1PlayerCacheT, RunsByDifficulty1PlayerCacheT, RunsByModifier1PlayerCacheT, UserStat,
+};
 
 pub(super) fn set_user_stats_for_user_id(
     user_id: usize,
@@ -124,3 +130,111 @@ pub fn get_mob_killed_counts_for_user(user_id: usize) -> MobsKilledBy1PlayerCach
         Default::default()
     })
 }
+
+pub(super) fn set_runs_count_for_difficulty_for_user(
+    user_id: usize,
+    difficulty: &DifficultyLevel,
+    amount: usize,
+) -> Result<(), OurDBError> {
+    let stmt_raw = format!(
+        "
+    INSERT OR REPLACE INTO {RUNS_BY_DIFFICULTY_TABLE_NAME} (userId, difficulty, nRuns)
+    VALUES (?1,  ?2, ?3)
+    ON CONFLICT DO UPDATE SET nRuns=?3"
+    );
+    let conn = get_db()?;
+    conn.prepare(&stmt_raw)?
+        .execute(params![user_id, difficulty.to_string(), amount])?;
+    Ok(())
+}
+
+fn _get_runs_by_difficulty_for_user(
+    user_id: usize,
+) -> Result<RunsByDifficulty1PlayerCacheT, OurDBError> {
+    let stmt_raw = format!(
+        "
+    SELECT difficulty, nRuns FROM {RUNS_BY_DIFFICULTY_TABLE_NAME}
+    WHERE userId=?1"
+    );
+    let conn = get_db()?;
+    let mut stmt = conn.prepare(&stmt_raw)?;
+    let rows = stmt.query([user_id])?;
+    rows.mapped(|r| {
+        let difficulty = r.get::<usize, String>(0)?;
+        let n_runs = r.get::<usize, usize>(1)?;
+        Ok((difficulty, n_runs))
+    })
+    .collect::<Result<Vec<(String, usize)>, rusqlite::Error>>()?
+    .into_iter()
+    .map(|(difficulty, n_runs)| {
+        Ok((
+            difficulty.parse::<DifficultyLevel>().map_err(|e| {
+                OurDBError::InternalError(format!("Failed to read difficulty from db {}", e))
+            })?,
+            n_runs,
+        ))
+    })
+    // The DB primary key guarantees that translating from a vec -> hashmap doesnt lose elements
+    .collect()
+}
+
+/// Returns the number of completed games played on each difficulty.
+pub fn get_runs_by_difficulty_for_user(user_id: usize) -> RunsByDifficulty1PlayerCacheT {
+    _get_runs_by_difficulty_for_user(user_id).unwrap_or_else(|e| {
+        error!(
+            "Failed to get runs by difficulty from db. Empty result fallback. {}",
+            e
+        );
+        Default::default()
+    })
+}
+
+pub(super) fn set_runs_count_for_modifier_for_user(
+    user_id: usize,
+    modifier: &str,
+    amount: usize,
+) -> Result<(), OurDBError> {
+    let stmt_raw = format!(
+        "
+    INSERT OR REPLACE INTO {RUNS_BY_MODIFIER_TABLE_NAME} (userId, modifier, nRuns)
+    VALUES (?1,  ?2, ?3)
+    ON CONFLICT DO UPDATE SET nRuns=?3"
+    );
+    let conn = get_db()?;
+    conn.prepare(&stmt_raw)?
+        .execute(params![user_id, modifier, amount])?;
+    Ok(())
+}
+
+fn _get_runs_by_modifier_for_user(
+    user_id: usize,
+) -> Result<RunsByModifier1PlayerCacheT, OurDBError> {
+    let stmt_raw = format!(
+        "
+    SELECT modifier, nRuns FROM {RUNS_BY_MODIFIER_TABLE_NAME}
+    WHERE userId=?1"
+    );
+    let conn = get_db()?;
+    let mut stmt = conn.prepare(&stmt_raw)?;
+    let rows = stmt.query([user_id])?;
+    // The DB primary key guarantees that translating from a vec -> hashmap doesnt lose elements
+    let runs_by_modifier = rows
+        .mapped(|r| {
+            let modifier = r.get::<usize, String>(0)?;
+            let n_runs = r.get::<usize, usize>(1)?;
+            Ok((modifier, n_runs))
+        })
+        .collect::<Result<RunsByModifier1PlayerCacheT, rusqlite::Error>>()?;
+    Ok(runs_by_modifier)
+}
+
+/// Returns the number of completed games played with each run modifier.
+pub fn get_runs_by_modifier_for_user(user_id: usize) -> RunsByModifier1PlayerCacheT {
+    _get_runs_by_modifier_for_user(user_id).unwrap_or_else(|e| {
+        error!(
+            "Failed to get runs by modifier from db. Empty result fallback. {}",
+            e
+        );
+        Default::default()
+    })
+}
@@ -1,4 +1,6 @@
-use crate::spawnable::{MobComponent, MobSegmentComponent, SpawnableComponent};
+use crate::spawnable::{
+    MobComponent, MobSegmentComponent, RecycleOrDespawnExt, SpawnableComponent,
+};
 use bevy::prelude::*;
 use bevy_rapier2d::{prelude::*, rapier::prelude::CollisionEventFlags};
 use thetawave_interface::{objective::MobReachedBottomGateEvent, states::GameCleanup};
@@ -59,39 +61,82 @@ pub(super) fn despawn_gates_system(
 
                 // verify the other entity is a spawnable
                 if spawnable_query.contains(*other_entity) {
-                    // despawn the spawnable entity
-                    commands.entity(*other_entity).despawn_recursive();
+                    // despawn the spawnable entity, or recycle it if it is pooled
+                    commands.recycle_or_despawn(*other_entity);
 
                     // check if the other entity is a mob
                     if let Ok((_, mob_component)) = mob_query.get(*other_entity) {
                         // send event for mob reaching bottom of arena
-                        if let Some(defense_interaction) = mob_component.defense_interaction.clone()
-                        {
-                            enemy_bottom_event.send(MobReachedBottomGateEvent {
-                                mob_type: Some(mob_component.mob_type.clone()),
-                                mob_segment_type: None,
-                                defense_interaction,
-                            });
-                        }
+                        enemy_bottom_event.send(MobReachedBottomGateEvent {
+                            mob_type: Some(mob_component.mob_type.clone()),
+                            mob_segment_type: None,
+                            defense_interaction: mob_component.defense_interaction.clone(),
+                        });
                     }
 
                     // check if the other entity is a mob segment
                     if let Ok((_, mob_segment_component)) = mob_segment_query.get(*other_entity) {
                         // send event for mob segment reaching bottom of arena
-                        if let Some(defense_interaction) =
-                            mob_segment_component.defense_interaction.clone()
-                        {
-                            enemy_bottom_event.send(MobReachedBottomGateEvent {
-                                mob_type: None,
-                                mob_segment_type: Some(
-                                    mob_segment_component.mob_segment_type.clone(),
-                                ),
-                                defense_interaction,
-                            });
-                        }
+                        enemy_bottom_event.send(MobReachedBottomGateEvent {
+                            mob_type: None,
+                            mob_segment_type: Some(mob_segment_component.mob_segment_type.clone()),
+                            defense_interaction: mob_segment_component.defense_interaction.clone(),
+                        });
                     }
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use bevy::{ecs::system::RunSystemOnce, prelude::*};
+    use bevy_rapier2d::{prelude::CollisionEvent, rapier::prelude::CollisionEventFlags};
+    use std::collections::HashMap;
+    use thetawave_interface::{
+        objective::MobReachedBottomGateEvent,
+        spawnable::{AllyMobType, MobType},
+    };
+
+    use super::{despawn_gates_system, DespawnGateComponent};
+    use crate::spawnable::{MobComponent, MobData, SpawnableComponent};
+
+    #[test]
+    fn test_haulers_without_defense_interaction_reach_the_gate() {
+        let mobs = ron::de::from_bytes::<HashMap<MobType, MobData>>(include_bytes!(
+            "../../assets/data/mobs.ron"
+        ))
+        .unwrap();
+        let hauler_data = &mobs[&MobType::Ally(AllyMobType::Hauler2)];
+        assert!(hauler_data.defense_interaction.is_none());
+
+        let mut world = World::new();
+        world.init_resource::<Events<CollisionEvent>>();
+        world.init_resource::<Events<MobReachedBottomGateEvent>>();
+        let gate = world.spawn(DespawnGateComponent).id();
+        let hauler = world
+            .spawn((
+                SpawnableComponent::default(),
+                MobComponent::from(hauler_data),
+            ))
+            .id();
+        world.send_event(CollisionEvent::Started(
+            gate,
+            hauler,
+            CollisionEventFlags::SENSOR,
+        ));
+
+        world.run_system_once(despawn_gates_system);
+
+        let events = world.resource::<Events<MobReachedBottomGateEvent>>();
+        let reached: Vec<&MobReachedBottomGateEvent> = events.get_reader().read(events).collect();
+        assert_eq!(reached.len(), 1);
+        assert_eq!(
+            reached[0].mob_type,
+            Some(MobType::Ally(AllyMobType::Hauler2))
+        );
+        assert!(reached[0].defense_interaction.is_none());
+        assert!(world.get_entity(hauler).is_none());
+    }
+}
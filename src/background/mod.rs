@@ -42,7 +42,10 @@ use thetawave_interface::{
 };
 use thiserror::Error;
 
-use crate::GameEnterSet;
+use crate::{
+    game::{GameRngResource, RngStream},
+    GameEnterSet,
+};
 
 /// Contains systems to spawn and animate the background of a rotating planet + star at the right
 /// `thetawave_interface::states::AppStates`.
@@ -175,15 +178,21 @@ enum OurGetRandomAssetError {
     InvalidFileName,
 }
 
-fn get_random_asset_file(path: String) -> Result<String, OurGetRandomAssetError> {
-    let mut rng = rand::thread_rng();
-
+fn get_random_asset_file(
+    path: String,
+    rng: &mut impl Rng,
+) -> Result<String, OurGetRandomAssetError> {
     let read_dir = fs::read_dir(path).map_err(|_e| OurGetRandomAssetError::NoPathFound)?;
-    let random_asset = read_dir
-        .choose(&mut rng)
+    // sort the entries so that the same rng state always picks the same file
+    let mut entries = read_dir
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_e| OurGetRandomAssetError::InvalidFileName)?;
+    entries.sort_by_key(|entry| entry.file_name());
+    let random_asset = entries
+        .into_iter()
+        .choose(rng)
         .ok_or(OurGetRandomAssetError::NoFilesInPath)?;
     let chosen_filename = random_asset
-        .map_err(|_e| OurGetRandomAssetError::InvalidFileName)?
         .path()
         .file_name()
         .ok_or(OurGetRandomAssetError::InvalidFileName)?
@@ -202,11 +211,12 @@ fn create_background_system(
     mut star_explode_res: ResMut<StarExplodeResource>,
     backgrounds_res: Res<BackgroundsResource>,
     game_options: Res<GameOptions>,
+    mut game_rng: ResMut<GameRngResource>,
 ) {
     // reset the star explode reource
     *star_explode_res = StarExplodeResource::default();
 
-    let mut rng = rand::thread_rng();
+    let rng = game_rng.stream(RngStream::Background);
 
     // Choose random positions for the bodies
     let background_transform = Transform::from_translation(backgrounds_res.background_transation)
@@ -231,7 +241,7 @@ fn create_background_system(
             .insert(InheritedVisibility::default())
             .insert(Name::new("Planet"));
 
-        match get_random_asset_file("./assets/models/planets".to_string()) {
+        match get_random_asset_file("./assets/models/planets".to_string(), rng) {
             Ok(file_name) => {
                 let planet_model_handle: Handle<Scene> =
                     asset_server.load(format!("models/planets/{file_name}#Scene0"));
@@ -1,6 +1,6 @@
 use crate::{
     arena::ArenaBarrierComponent,
-    spawnable::{MobComponent, MobSegmentComponent, ProjectileComponent},
+    spawnable::{MobComponent, MobSegmentComponent, PooledComponent, ProjectileComponent},
 };
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
@@ -10,7 +10,7 @@ use thetawave_interface::{
     spawnable::{Faction, MobSegmentType, MobType, ProjectileType},
 };
 
-use super::{CollidingEntityPair, SortedCollisionEvent};
+use super::{involves_recycled_entity, CollidingEntityPair, SortedCollisionEvent};
 
 /// Creates events from contact collisions
 #[allow(clippy::too_many_arguments)]
@@ -22,10 +22,15 @@ pub fn contact_collision_system(
     mob_segment_query: Query<(Entity, &MobSegmentComponent)>,
     barrier_query: Query<Entity, With<ArenaBarrierComponent>>,
     projectile_query: Query<(Entity, &ProjectileComponent)>,
+    pooled_query: Query<&PooledComponent>,
     mut sound_effect_event_writer: EventWriter<PlaySoundEffectEvent>,
 ) {
     'collision_events: for contact_event in collision_events.read() {
         if let CollisionEvent::Stopped(collider1_entity, collider2_entity, _) = contact_event {
+            if involves_recycled_entity([*collider1_entity, *collider2_entity], &pooled_query) {
+                continue 'collision_events;
+            }
+
             // Prioritize by the importance of components to eliminate cases to check due to
             // `x collided with y` and `y collided with x` symmetry
             let colliding_entities = {
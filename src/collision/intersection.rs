@@ -1,5 +1,5 @@
 use crate::spawnable::{
-    ConsumableComponent, MobComponent, MobSegmentComponent, ProjectileComponent,
+    ConsumableComponent, MobComponent, MobSegmentComponent, PooledComponent, ProjectileComponent,
 };
 use bevy::prelude::{debug, Entity, EventReader, EventWriter, Query, With};
 use bevy_rapier2d::{prelude::CollisionEvent, rapier::prelude::CollisionEventFlags};
@@ -8,9 +8,10 @@ use thetawave_interface::{
     spawnable::{Faction, ItemComponent, MobSegmentType, MobType, ProjectileType},
 };
 
-use super::{CollidingEntityPair, SortedCollisionEvent};
+use super::{involves_recycled_entity, CollidingEntityPair, SortedCollisionEvent};
 
 /// Creates events from intersection (sensor) collisions
+#[allow(clippy::too_many_arguments)]
 pub fn intersection_collision_system(
     mut collision_event_writer: EventWriter<SortedCollisionEvent>,
     mut collision_events: EventReader<CollisionEvent>,
@@ -20,6 +21,7 @@ pub fn intersection_collision_system(
     mob_query: Query<(Entity, &MobComponent)>,
     mob_segment_query: Query<(Entity, &MobSegmentComponent)>,
     projectile_query: Query<(Entity, &ProjectileComponent)>,
+    pooled_query: Query<&PooledComponent>,
 ) {
     // loop through all collision events
     'collision_events: for collision_event in collision_events.read() {
@@ -30,6 +32,10 @@ pub fn intersection_collision_system(
             CollisionEventFlags::SENSOR,
         ) = collision_event
         {
+            if involves_recycled_entity([*collider1_entity, *collider2_entity], &pooled_query) {
+                continue 'collision_events;
+            }
+
             // 'Canonicalized' pair to deal with/normalize out E.x. (mob, player) vs (player, mob)
             // intersection symmetry of pattern match arms.
             let colliding_entities = {
@@ -1,4 +1,4 @@
-use crate::GameUpdateSet;
+use crate::{spawnable::PooledComponent, GameUpdateSet};
 use bevy::prelude::*;
 use bevy_rapier2d::geometry::Group;
 use thetawave_interface::spawnable::Faction;
@@ -36,6 +36,16 @@ impl Plugin for CollisionPlugin {
     }
 }
 
+/// Whether either entity of a collision is waiting in its pool. Recycled entities have no collider
+/// anymore, but rapier still reports the end of the collisions that they were in.
+fn involves_recycled_entity(entities: [Entity; 2], pooled_query: &Query<&PooledComponent>) -> bool {
+    entities.iter().any(|entity| {
+        pooled_query
+            .get(*entity)
+            .is_ok_and(|pooled| !pooled.is_active())
+    })
+}
+
 /// Types of collisions
 #[derive(Debug, Event)]
 pub enum SortedCollisionEvent {
@@ -0,0 +1,211 @@
+//! Exposes a plugin for loading the files in 'assets/data' through the asset system, so that
+//! changes to them can be applied while the game is running. Without it, the versions of the
+//! files that were embedded in the executable at compile time are used.
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    ecs::event::ManualEventReader,
+    prelude::*,
+    utils::BoxedFuture,
+};
+use ron::{de::from_bytes, error::SpannedError};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+
+/// Applies the contents of a data file to the world, usually by replacing a resource
+type DataReloader = Box<dyn Fn(&[u8], &mut World) -> Result<(), SpannedError> + Send + Sync>;
+
+/// Maps the names of reloadable data files to the functions that apply them
+#[derive(Resource, Default)]
+struct DataReloadersResource {
+    reloaders: HashMap<&'static str, DataReloader>,
+}
+
+/// Handles of the data files being watched, mapped to the names of the files
+#[derive(Resource, Default)]
+struct DataFileHandlesResource {
+    files: HashMap<AssetId<DataFileAsset>, (&'static str, Handle<DataFileAsset>)>,
+}
+
+/// Raw contents of a data file. Parsing is left to the reloader of the file since every file
+/// has a different type.
+#[derive(Asset, TypePath)]
+struct DataFileAsset {
+    bytes: Vec<u8>,
+}
+
+#[derive(Default)]
+struct DataFileAssetLoader;
+
+impl AssetLoader for DataFileAssetLoader {
+    type Asset = DataFileAsset;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(DataFileAsset { bytes })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+/// Allows plugins to mark the resources they build from data files as reloadable
+pub(crate) trait ReloadableDataAppExt {
+    /// Rebuild a resource from `file` in 'assets/data' whenever the file is loaded or changed.
+    /// `into_resource` converts the parsed file into the resource.
+    fn register_reloadable_data<D, R, F>(
+        &mut self,
+        file: &'static str,
+        into_resource: F,
+    ) -> &mut Self
+    where
+        D: DeserializeOwned,
+        R: Resource,
+        F: Fn(D) -> R + Send + Sync + 'static;
+}
+
+impl ReloadableDataAppExt for App {
+    fn register_reloadable_data<D, R, F>(
+        &mut self,
+        file: &'static str,
+        into_resource: F,
+    ) -> &mut Self
+    where
+        D: DeserializeOwned,
+        R: Resource,
+        F: Fn(D) -> R + Send + Sync + 'static,
+    {
+        self.world
+            .get_resource_or_insert_with(DataReloadersResource::default)
+            .reloaders
+            .insert(
+                file,
+                Box::new(move |bytes, world| {
+                    world.insert_resource(into_resource(from_bytes::<D>(bytes)?));
+                    Ok(())
+                }),
+            );
+        self
+    }
+}
+
+/// Loads the data files that other plugins registered as reloadable through the asset system
+/// when `hot_reload` is enabled. The asset plugin needs to be watching for changes for files to
+/// be reloaded after they are first loaded.
+#[derive(Default)]
+pub(super) struct DataReloadPlugin {
+    pub hot_reload: bool,
+}
+
+impl Plugin for DataReloadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DataReloadersResource>();
+
+        if self.hot_reload {
+            app.init_asset::<DataFileAsset>()
+                .init_asset_loader::<DataFileAssetLoader>()
+                .init_resource::<DataFileHandlesResource>()
+                .add_systems(Startup, load_data_files_system)
+                .add_systems(PreUpdate, apply_changed_data_files_system);
+        }
+    }
+}
+
+/// Start loading every reloadable data file
+fn load_data_files_system(
+    asset_server: Res<AssetServer>,
+    reloaders: Res<DataReloadersResource>,
+    mut data_file_handles: ResMut<DataFileHandlesResource>,
+) {
+    for file in reloaders.reloaders.keys() {
+        let handle = asset_server.load::<DataFileAsset>(format!("data/{file}"));
+        data_file_handles
+            .files
+            .insert(handle.id(), (*file, handle.clone()));
+    }
+
+    info!(
+        "Watching {} data files for changes",
+        data_file_handles.files.len()
+    );
+}
+
+/// Rebuild the resources of data files that were loaded or changed. A file that fails to parse is
+/// logged and the resource built from it is left unchanged.
+fn apply_changed_data_files_system(
+    world: &mut World,
+    mut asset_event_reader: Local<ManualEventReader<AssetEvent<DataFileAsset>>>,
+) {
+    let changed_ids: Vec<AssetId<DataFileAsset>> = asset_event_reader
+        .read(world.resource::<Events<AssetEvent<DataFileAsset>>>())
+        .filter_map(|event| match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    for id in changed_ids {
+        let Some((file, _)) = world.resource::<DataFileHandlesResource>().files.get(&id) else {
+            continue;
+        };
+        let file = *file;
+        let Some(bytes) = world
+            .resource::<Assets<DataFileAsset>>()
+            .get(id)
+            .map(|data_file| data_file.bytes.clone())
+        else {
+            continue;
+        };
+
+        world.resource_scope(|world, reloaders: Mut<DataReloadersResource>| {
+            if let Some(reloader) = reloaders.reloaders.get(file) {
+                match reloader(&bytes, world) {
+                    Ok(()) => info!("Reloaded data file: {}", file),
+                    Err(err) => error!("Failed to reload data file {}: {}", file, err),
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::{App, Mut, Resource};
+    use serde::Deserialize;
+
+    use super::{DataReloadersResource, ReloadableDataAppExt};
+
+    #[derive(Resource, Deserialize, Debug, PartialEq)]
+    struct TestDataResource {
+        value: usize,
+    }
+
+    #[test]
+    fn test_reloader_replaces_resource_and_keeps_it_on_parse_error() {
+        let mut app = App::new();
+        app.insert_resource(TestDataResource { value: 1 })
+            .register_reloadable_data("test.ron", |res: TestDataResource| res);
+
+        app.world
+            .resource_scope(|world, reloaders: Mut<DataReloadersResource>| {
+                let reloader = &reloaders.reloaders["test.ron"];
+                assert!(reloader(b"(value: 2)", world).is_ok());
+                assert!(reloader(b"(value: ", world).is_err());
+            });
+
+        assert_eq!(
+            app.world.resource::<TestDataResource>(),
+            &TestDataResource { value: 2 }
+        );
+    }
+}
@@ -1,14 +1,19 @@
 /// Expose all of the mutations for the within-game metric counters via a bevy plugin.
 use crate::collision::SortedCollisionEvent;
 use crate::spawnable::FireWeaponEvent;
-use bevy::prelude::{debug, App, Entity, EventReader, OnEnter, Plugin, Query, ResMut, Update};
+use bevy::prelude::{
+    debug, App, Entity, EventReader, OnEnter, OnTransition, Plugin, Query, Res, ResMut, Update,
+};
 use thetawave_interface::player::PlayerIDComponent;
 
 use std::collections::HashMap;
+use thetawave_interface::game::difficulty::SelectedDifficultyResource;
 use thetawave_interface::game::historical_metrics::{
-    MobKillsByPlayerForCompletedGames, MobKillsByPlayerForCurrentGame, UserStat,
+    MobKillsByPlayerForCompletedGames, MobKillsByPlayerForCurrentGame,
+    RunsByDifficultyForCompletedGames, RunsByModifierForCompletedGames, UserStat,
     UserStatsByPlayerForCompletedGamesCache, UserStatsByPlayerForCurrentGameCache, DEFAULT_USER_ID,
 };
+use thetawave_interface::game::run_modifiers::SelectedRunModifiersResource;
 use thetawave_interface::spawnable::{MobDestroyedEvent, MobType};
 use thetawave_interface::states::AppStates;
 
@@ -22,6 +27,8 @@ impl Plugin for CountingMetricsPlugin {
         app.insert_resource(MobKillsByPlayerForCurrentGame::default());
         app.insert_resource(UserStatsByPlayerForCompletedGamesCache::default());
         app.insert_resource(UserStatsByPlayerForCurrentGameCache::default());
+        app.insert_resource(RunsByDifficultyForCompletedGames::default());
+        app.insert_resource(RunsByModifierForCompletedGames::default());
         app.add_systems(
             Update,
             (
@@ -34,9 +41,25 @@ impl Plugin for CountingMetricsPlugin {
             OnEnter(AppStates::Game),
             roll_current_game_counters_into_completed_game_metrics,
         );
+        // a game over only ends the run once the players go back to the main menu instead of
+        // continuing
         app.add_systems(
-            OnEnter(AppStates::GameOver),
-            inc_completed_games_played_counter,
+            OnTransition {
+                from: AppStates::GameOver,
+                to: AppStates::MainMenu,
+            },
+            (
+                inc_completed_games_played_counter,
+                inc_completed_games_by_difficulty_counter,
+                inc_completed_games_by_modifier_counter,
+            ),
+        );
+        app.add_systems(
+            OnEnter(AppStates::Victory),
+            (
+                inc_completed_games_by_difficulty_counter,
+                inc_completed_games_by_modifier_counter,
+            ),
         );
     }
 }
@@ -60,6 +83,28 @@ fn inc_completed_games_played_counter(
         .total_games_lost += 1;
 }
 
+/// Count the run that just ended towards the difficulty it was played on
+fn inc_completed_games_by_difficulty_counter(
+    mut runs_by_difficulty: ResMut<RunsByDifficultyForCompletedGames>,
+    selected_difficulty: Res<SelectedDifficultyResource>,
+) {
+    inc_usize_map(
+        (**runs_by_difficulty).entry(DEFAULT_USER_ID).or_default(),
+        selected_difficulty.level,
+    );
+}
+
+/// Count the run that just ended towards each run modifier it was played with
+fn inc_completed_games_by_modifier_counter(
+    mut runs_by_modifier: ResMut<RunsByModifierForCompletedGames>,
+    selected_run_modifiers: Res<SelectedRunModifiersResource>,
+) {
+    let player_1_runs_by_modifier = (**runs_by_modifier).entry(DEFAULT_USER_ID).or_default();
+    for modifier in selected_run_modifiers.modifiers.iter() {
+        inc_usize_map(player_1_runs_by_modifier, modifier.clone());
+    }
+}
+
 fn inc_in_memory_mob_destroyed_for_current_game_cache(
     mut mobs_destroyed_counters_by_player: ResMut<MobKillsByPlayerForCurrentGame>,
     mut mob_destroyed_event_reader: EventReader<MobDestroyedEvent>,
@@ -193,13 +238,16 @@ mod test {
     use crate::spawnable::FireWeaponEvent;
     use bevy::input::InputPlugin;
     use bevy::math::Vec2;
-    use bevy::prelude::{App, Component, Events};
+    use bevy::prelude::{App, Component, Events, NextState};
     use bevy::MinimalPlugins;
     use thetawave_interface::audio::SoundEffectType;
     use thetawave_interface::character::{Character, CharacterType};
+    use thetawave_interface::game::difficulty::SelectedDifficultyResource;
     use thetawave_interface::game::historical_metrics::{
-        MobKillsByPlayerForCurrentGame, UserStatsByPlayerForCurrentGameCache, DEFAULT_USER_ID,
+        MobKillsByPlayerForCurrentGame, UserStatsByPlayerForCompletedGamesCache,
+        UserStatsByPlayerForCurrentGameCache, DEFAULT_USER_ID,
     };
+    use thetawave_interface::game::run_modifiers::SelectedRunModifiersResource;
     use thetawave_interface::player::{PlayerBundle, PlayerComponent};
     use thetawave_interface::spawnable::{
         EnemyMobType, Faction, MobDestroyedEvent, MobType, ProjectileType, SpawnPosition,
@@ -270,6 +318,7 @@ mod test {
                 position: SpawnPosition::Local(Vec2::new(0.0, 40.0)),
                 speed: 1.0,
                 direction: FRAC_PI_2,
+                aimed: false,
                 despawn_time: 0.0,
                 count: 1,
                 spread_pattern: SpreadPattern::Arc(ArcPatternData {
@@ -308,4 +357,36 @@ mod test {
             .total_shots_fired;
         assert_eq!(n_p1_shots_fired_2, 2);
     }
+
+    #[test]
+    fn test_games_lost_are_counted_when_the_run_ends() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, CountingMetricsPlugin))
+            .init_state::<AppStates>()
+            .add_event::<SortedCollisionEvent>()
+            .add_event::<MobDestroyedEvent>()
+            .add_event::<FireWeaponEvent>()
+            .insert_resource(SelectedDifficultyResource::default())
+            .insert_resource(SelectedRunModifiersResource::default());
+        let go_to_state = |app: &mut App, state: AppStates| {
+            app.world.resource_mut::<NextState<AppStates>>().set(state);
+            app.update();
+        };
+        let games_lost = |app: &App| {
+            app.world
+                .resource::<UserStatsByPlayerForCompletedGamesCache>()
+                .get(&DEFAULT_USER_ID)
+                .map_or(0, |user_stat| user_stat.total_games_lost)
+        };
+
+        // continuing from the game over screen doesn't end the run
+        go_to_state(&mut app, AppStates::Game);
+        go_to_state(&mut app, AppStates::GameOver);
+        go_to_state(&mut app, AppStates::Game);
+        assert_eq!(games_lost(&app), 0);
+
+        go_to_state(&mut app, AppStates::GameOver);
+        go_to_state(&mut app, AppStates::MainMenu);
+        assert_eq!(games_lost(&app), 1);
+    }
 }
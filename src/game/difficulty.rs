@@ -0,0 +1,85 @@
+//! Multipliers that make runs easier or harder depending on the selected difficulty
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::Deserialize;
+use std::collections::HashMap;
+use strum::IntoEnumIterator;
+use thetawave_interface::game::difficulty::{DifficultyLevel, SelectedDifficultyResource};
+
+use crate::validation::DataValidator;
+
+const DIFFICULTIES_FILE: &str = "difficulties.ron";
+
+/// Multipliers applied to values from the data files for a difficulty
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct DifficultyMultipliers {
+    /// Multiplier for the health of mobs
+    pub mob_health: f32,
+    /// Multiplier for the damage mobs deal on collision
+    pub mob_collision_damage: f32,
+    /// Multiplier for the damage of projectiles fired by mobs
+    pub mob_projectile_damage: f32,
+    /// Multiplier for the period between formations. Lower values spawn formations more often.
+    pub formation_period: f32,
+    /// Multiplier for the damage mobs deal to the defense objective
+    pub defense_damage: f32,
+    /// Multiplier for the healing mobs give to the defense objective
+    pub defense_heal: f32,
+}
+
+impl Default for DifficultyMultipliers {
+    fn default() -> Self {
+        DifficultyMultipliers {
+            mob_health: 1.0,
+            mob_collision_damage: 1.0,
+            mob_projectile_damage: 1.0,
+            formation_period: 1.0,
+            defense_damage: 1.0,
+            defense_heal: 1.0,
+        }
+    }
+}
+
+/// Multiplies a whole number value, rounding to the nearest whole number
+pub fn scale_usize(value: usize, multiplier: f32) -> usize {
+    (value as f32 * multiplier).round() as usize
+}
+
+/// Stores the multipliers of each difficulty
+#[derive(Resource, Deserialize, Default)]
+pub struct DifficultiesResource {
+    pub difficulties: HashMap<DifficultyLevel, DifficultyMultipliers>,
+}
+
+impl DifficultiesResource {
+    /// Get the multipliers for a difficulty, leaving values unchanged if it isn't defined
+    pub fn get_multipliers(&self, level: DifficultyLevel) -> DifficultyMultipliers {
+        self.difficulties.get(&level).copied().unwrap_or_default()
+    }
+}
+
+/// The selected difficulty and the multipliers of every difficulty
+#[derive(SystemParam)]
+pub struct DifficultyParams<'w> {
+    difficulties: Res<'w, DifficultiesResource>,
+    selected_difficulty: Res<'w, SelectedDifficultyResource>,
+}
+
+impl DifficultyParams<'_> {
+    /// Multipliers of the selected difficulty
+    pub fn get_multipliers(&self) -> DifficultyMultipliers {
+        self.difficulties
+            .get_multipliers(self.selected_difficulty.level)
+    }
+}
+
+/// Check that every difficulty that can be selected is defined
+pub(super) fn validate_data(validator: &mut DataValidator) {
+    if let Some(difficulties) = validator.load::<DifficultiesResource>(DIFFICULTIES_FILE) {
+        for level in DifficultyLevel::iter() {
+            if !difficulties.difficulties.contains_key(&level) {
+                validator.report(DIFFICULTIES_FILE, level, "difficulty is not defined");
+            }
+        }
+    }
+}
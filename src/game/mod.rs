@@ -1,12 +1,38 @@
 //! `thetawave` game module
 use bevy::prelude::*;
 use ron::de::from_bytes;
+use thetawave_interface::{
+    game::{
+        difficulty::{DifficultyLevel, SelectedDifficultyResource},
+        run_modifiers::SelectedRunModifiersResource,
+    },
+    states::AppStates,
+};
+
+use crate::{data_reload::ReloadableDataAppExt, validation::DataValidator};
 pub mod counters;
+mod difficulty;
 mod resources;
+mod rng;
+mod run_modifiers;
 
+pub use self::difficulty::{
+    scale_usize, DifficultiesResource, DifficultyMultipliers, DifficultyParams,
+};
 pub use self::resources::GameParametersResource;
+pub use self::rng::{GameRngResource, GameRngState, RngStream};
+pub use self::run_modifiers::{RunModifierEffects, RunModifierParams, RunModifiersResource};
+
+use self::rng::reset_game_rng_system;
 
-pub struct GamePlugin;
+#[derive(Default)]
+pub struct GamePlugin {
+    /// Seed for all gameplay randomness. A random seed is picked if not provided.
+    pub seed: Option<u64>,
+    /// Difficulty selected at startup, which can still be changed on the character selection
+    /// screen. Normal if not provided.
+    pub difficulty: Option<DifficultyLevel>,
+}
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
@@ -15,6 +41,39 @@ impl Plugin for GamePlugin {
                 "../../assets/data/game_parameters.ron"
             ))
             .unwrap(),
-        );
+        )
+        .register_reloadable_data("game_parameters.ron", |res: GameParametersResource| res);
+
+        app.insert_resource(
+            from_bytes::<DifficultiesResource>(include_bytes!(
+                "../../assets/data/difficulties.ron"
+            ))
+            .unwrap(),
+        )
+        .register_reloadable_data("difficulties.ron", |res: DifficultiesResource| res)
+        .insert_resource(SelectedDifficultyResource {
+            level: self.difficulty.unwrap_or_default(),
+        });
+
+        app.insert_resource(
+            from_bytes::<RunModifiersResource>(include_bytes!(
+                "../../assets/data/run_modifiers.ron"
+            ))
+            .unwrap(),
+        )
+        .register_reloadable_data("run_modifiers.ron", |res: RunModifiersResource| res)
+        .insert_resource(SelectedRunModifiersResource::default());
+
+        let seed = self.seed.unwrap_or_else(rand::random);
+        info!("Game seed: {}", seed);
+        app.insert_resource(GameRngResource::new(seed));
+
+        app.add_systems(OnEnter(AppStates::InitializeRun), reset_game_rng_system);
     }
 }
+
+/// Check the difficulties and run modifiers data files
+pub(crate) fn validate_data(validator: &mut DataValidator) {
+    difficulty::validate_data(validator);
+    run_modifiers::validate_data(validator);
+}
@@ -0,0 +1,150 @@
+//! Seedable random number generation shared by the game's systems
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Independent streams of random numbers. Each subsystem draws from its own stream so that, for
+/// example, firing a random spread of projectiles does not change which loot is dropped next.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum RngStream {
+    Formations,
+    Loot,
+    Weapons,
+    Tutorial,
+    MobSegments,
+    Background,
+    Shop,
+    Targeting,
+    Motion,
+}
+
+/// Source of all gameplay randomness. Two runs started with the same seed and the same player
+/// inputs produce the same formations, drops and spreads.
+#[derive(Resource, Debug)]
+pub struct GameRngResource {
+    seed: u64,
+    streams: HashMap<RngStream, StdRng>,
+}
+
+impl GameRngResource {
+    pub fn new(seed: u64) -> Self {
+        GameRngResource {
+            seed,
+            streams: HashMap::new(),
+        }
+    }
+
+    /// Seed that all of the streams are derived from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Get the rng for a stream, creating it from the seed if it hasn't been used yet
+    pub fn stream(&mut self, stream: RngStream) -> &mut StdRng {
+        let seed = self.seed;
+        self.streams.entry(stream).or_insert_with(|| {
+            // spread the stream ids out so that neighboring streams don't get similar seeds
+            StdRng::seed_from_u64(seed ^ (stream as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+        })
+    }
+
+    /// Restart every stream from the seed
+    pub fn reset(&mut self) {
+        self.streams.clear();
+    }
+
+    /// Capture the seed and the streams so that a saved run keeps drawing the same numbers. Streams
+    /// can't be serialized, so each stream in use is restarted from a seed drawn from it, both
+    /// here and in the saved state.
+    pub fn save_state(&mut self) -> GameRngState {
+        let stream_seeds = self
+            .streams
+            .iter_mut()
+            .map(|(stream, rng)| {
+                let stream_seed = rng.gen();
+                *rng = StdRng::seed_from_u64(stream_seed);
+                (*stream, stream_seed)
+            })
+            .collect();
+
+        GameRngState {
+            seed: self.seed,
+            stream_seeds,
+        }
+    }
+}
+
+/// Saved state of a `GameRngResource`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GameRngState {
+    seed: u64,
+    /// Seeds of the streams that were in use when the state was saved
+    stream_seeds: Vec<(RngStream, u64)>,
+}
+
+impl From<GameRngState> for GameRngResource {
+    fn from(state: GameRngState) -> Self {
+        GameRngResource {
+            seed: state.seed,
+            streams: state
+                .stream_seeds
+                .into_iter()
+                .map(|(stream, stream_seed)| (stream, StdRng::seed_from_u64(stream_seed)))
+                .collect(),
+        }
+    }
+}
+
+/// Restart the random number streams so that every run with the same seed plays out the same way
+pub(super) fn reset_game_rng_system(mut game_rng: ResMut<GameRngResource>) {
+    game_rng.reset();
+    info!("Game rng reset with seed: {}", game_rng.seed());
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::math::Vec2;
+
+    use super::{GameRngResource, RngStream};
+    use crate::{spawnable::InitialMotion, tools::weighted_rng};
+
+    #[test]
+    fn test_same_seed_gives_same_weighted_picks() {
+        let picks = |game_rng: &mut GameRngResource| {
+            (0..20)
+                .map(|_| weighted_rng(vec![1.0, 2.0, 3.0], game_rng.stream(RngStream::Loot)))
+                .collect::<Vec<usize>>()
+        };
+        let mut game_rng = GameRngResource::new(42);
+        let first_picks = picks(&mut game_rng);
+
+        // drawing from another stream does not affect the loot stream
+        game_rng.reset();
+        weighted_rng(vec![1.0, 1.0], game_rng.stream(RngStream::Weapons));
+
+        assert_eq!(first_picks, picks(&mut game_rng));
+        assert_eq!(first_picks, picks(&mut GameRngResource::new(42)));
+    }
+
+    #[test]
+    fn test_same_seed_gives_same_spawn_velocities() {
+        let initial_motion = InitialMotion {
+            random_angvel: Some((-1.0, 1.0)),
+            random_linvel: Some((Vec2::new(-10.0, -10.0), Vec2::new(10.0, 10.0))),
+            ..Default::default()
+        };
+        let velocities = |game_rng: &mut GameRngResource| {
+            (0..5)
+                .map(|_| initial_motion.get_velocity(game_rng.stream(RngStream::Motion)))
+                .map(|velocity| (velocity.linvel, velocity.angvel))
+                .collect::<Vec<(Vec2, f32)>>()
+        };
+
+        assert_eq!(
+            velocities(&mut GameRngResource::new(7)),
+            velocities(&mut GameRngResource::new(7))
+        );
+    }
+}
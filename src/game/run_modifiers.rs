@@ -0,0 +1,185 @@
+//! Run modifiers that players can toggle before a run to change how it plays, such as faster enemy
+//! projectiles or more loot
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::Deserialize;
+use std::collections::HashSet;
+use thetawave_interface::{
+    game::run_modifiers::SelectedRunModifiersResource, spawnable::ConsumableType,
+};
+
+use crate::validation::DataValidator;
+
+const RUN_MODIFIERS_FILE: &str = "run_modifiers.ron";
+
+/// Changes a run modifier makes to the game. Values a modifier doesn't set leave the game
+/// unchanged.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct RunModifierEffects {
+    /// Multiplier for the speed of projectiles fired by mobs
+    pub mob_projectile_speed: f32,
+    /// Multiplier for how fast the shields of players regenerate. 0.0 stops them regenerating.
+    pub player_shield_regeneration: f32,
+    /// Multiplier for the number of times each consumable in a drop list is rolled for
+    pub loot_rolls: u32,
+    /// Multiplier for the defense that defense objectives start with
+    pub starting_defense: f32,
+    /// Consumables that are never dropped by mobs
+    pub blocked_consumables: Vec<ConsumableType>,
+}
+
+impl Default for RunModifierEffects {
+    fn default() -> Self {
+        RunModifierEffects {
+            mob_projectile_speed: 1.0,
+            player_shield_regeneration: 1.0,
+            loot_rolls: 1,
+            starting_defense: 1.0,
+            blocked_consumables: vec![],
+        }
+    }
+}
+
+impl RunModifierEffects {
+    /// Apply the effects of another modifier on top of these ones
+    fn combine(mut self, other: &RunModifierEffects) -> Self {
+        self.mob_projectile_speed *= other.mob_projectile_speed;
+        self.player_shield_regeneration *= other.player_shield_regeneration;
+        self.loot_rolls *= other.loot_rolls;
+        self.starting_defense *= other.starting_defense;
+        self.blocked_consumables
+            .extend(other.blocked_consumables.iter().cloned());
+        self
+    }
+
+    /// Returns true if mobs can drop the consumable
+    pub fn can_drop(&self, consumable_type: &ConsumableType) -> bool {
+        !self.blocked_consumables.contains(consumable_type)
+    }
+}
+
+/// A run modifier that can be toggled on the character selection screen
+#[derive(Deserialize, Debug, Clone)]
+pub struct RunModifierData {
+    /// Used to refer to the modifier in selections and stats
+    pub key: String,
+    /// Shown on the character selection screen and the HUD
+    pub name: String,
+    pub effects: RunModifierEffects,
+}
+
+/// Stores every run modifier, in the order they are listed on the character selection screen
+#[derive(Resource, Deserialize, Default)]
+pub struct RunModifiersResource {
+    pub modifiers: Vec<RunModifierData>,
+}
+
+impl RunModifiersResource {
+    pub fn get(&self, key: &str) -> Option<&RunModifierData> {
+        self.modifiers.iter().find(|modifier| modifier.key == key)
+    }
+
+    /// Combined effects of the modifiers with the given keys, ignoring keys that aren't defined
+    pub fn get_effects(&self, keys: &[String]) -> RunModifierEffects {
+        keys.iter()
+            .filter_map(|key| self.get(key))
+            .fold(RunModifierEffects::default(), |effects, modifier| {
+                effects.combine(&modifier.effects)
+            })
+    }
+}
+
+/// The selected run modifiers and the data of every run modifier
+#[derive(SystemParam)]
+pub struct RunModifierParams<'w> {
+    run_modifiers: Res<'w, RunModifiersResource>,
+    selected_run_modifiers: Res<'w, SelectedRunModifiersResource>,
+}
+
+impl RunModifierParams<'_> {
+    /// Combined effects of the selected modifiers
+    pub fn get_effects(&self) -> RunModifierEffects {
+        self.run_modifiers
+            .get_effects(&self.selected_run_modifiers.modifiers)
+    }
+
+    /// Names of the selected modifiers
+    pub fn get_active_names(&self) -> Vec<&str> {
+        self.selected_run_modifiers
+            .modifiers
+            .iter()
+            .filter_map(|key| self.run_modifiers.get(key))
+            .map(|modifier| modifier.name.as_str())
+            .collect()
+    }
+}
+
+/// Check that run modifier keys are unique and that their multipliers make sense
+pub(super) fn validate_data(validator: &mut DataValidator) {
+    if let Some(run_modifiers) = validator.load::<RunModifiersResource>(RUN_MODIFIERS_FILE) {
+        let mut keys = HashSet::new();
+
+        for modifier in run_modifiers.modifiers.iter() {
+            if !keys.insert(modifier.key.as_str()) {
+                validator.report(
+                    RUN_MODIFIERS_FILE,
+                    &modifier.key,
+                    "key is used more than once",
+                );
+            }
+
+            let effects = &modifier.effects;
+            for (name, value) in [
+                ("mob_projectile_speed", effects.mob_projectile_speed),
+                (
+                    "player_shield_regeneration",
+                    effects.player_shield_regeneration,
+                ),
+                ("starting_defense", effects.starting_defense),
+            ] {
+                if value < 0.0 {
+                    validator.report(
+                        RUN_MODIFIERS_FILE,
+                        &modifier.key,
+                        format!("{name} is negative"),
+                    );
+                }
+            }
+
+            if effects.starting_defense == 0.0 {
+                validator.report(
+                    RUN_MODIFIERS_FILE,
+                    &modifier.key,
+                    "starting_defense of 0.0 fails defense objectives immediately",
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use thetawave_interface::spawnable::ConsumableType;
+
+    use super::RunModifiersResource;
+
+    #[test]
+    fn test_run_modifier_effects_are_combined() {
+        let run_modifiers = ron::de::from_bytes::<RunModifiersResource>(include_bytes!(
+            "../../assets/data/run_modifiers.ron"
+        ))
+        .unwrap();
+
+        let effects = run_modifiers.get_effects(&[
+            "DoubleLoot".to_string(),
+            "NoHealthDrops".to_string(),
+            "NotAModifier".to_string(),
+        ]);
+
+        assert_eq!(effects.loot_rolls, 2);
+        assert!(!effects.can_drop(&ConsumableType::HealthWrench));
+        assert!(effects.can_drop(&ConsumableType::Money1));
+        assert_eq!(run_modifiers.get_effects(&[]), Default::default());
+    }
+}
@@ -1,10 +1,13 @@
 //! Exposes a plugin that changes a player/mob's health and shields based on time and events
-use crate::spawnable::SpawnEffectEvent;
+use crate::{
+    game::RunModifierParams, spawnable::SpawnEffectEvent, status_effect::StatusEffectsComponent,
+};
 use bevy::prelude::{
-    App, Entity, EventReader, EventWriter, Plugin, Query, Res, Time, Transform, Update,
+    App, Entity, EventReader, EventWriter, Has, Plugin, Query, Res, Time, Transform, Update,
 };
 use thetawave_interface::{
     health::{DamageDealtEvent, HealthComponent},
+    player::PlayerComponent,
     spawnable::{EffectType, TextEffectType},
 };
 /// Includes systems to decrease a player's health and regenerate their shields over time.
@@ -17,23 +20,46 @@ impl Plugin for HealthPlugin {
     }
 }
 
-/// Handle player health regeneration
-fn regenerate_shields_system(mut health_query: Query<&mut HealthComponent>, time: Res<Time>) {
-    for mut health in health_query.iter_mut() {
-        health.regenerate_shields(time.delta());
+/// Handle player health regeneration. Run modifiers can slow down or stop the regeneration of the
+/// players' shields.
+fn regenerate_shields_system(
+    mut health_query: Query<(&mut HealthComponent, Has<PlayerComponent>)>,
+    time: Res<Time>,
+    run_modifiers: RunModifierParams,
+) {
+    let player_shield_regeneration = run_modifiers.get_effects().player_shield_regeneration;
+
+    for (mut health, is_player) in health_query.iter_mut() {
+        if is_player {
+            health.regenerate_shields(time.delta().mul_f32(player_shield_regeneration));
+        } else {
+            health.regenerate_shields(time.delta());
+        }
     }
 }
 
-/// Receive damage dealt events, apply damage, and spawn effects
+/// Receive damage dealt events, apply damage, and spawn effects. Damage is increased on targets
+/// made vulnerable by status effects.
 fn damage_system(
     mut damage_dealt_events: EventReader<DamageDealtEvent>,
-    mut health_query: Query<(Entity, &Transform, &mut HealthComponent)>,
+    mut health_query: Query<(
+        Entity,
+        &Transform,
+        &mut HealthComponent,
+        Option<&StatusEffectsComponent>,
+    )>,
     mut spawn_effect_event_writer: EventWriter<SpawnEffectEvent>,
 ) {
     for event in damage_dealt_events.read() {
-        if let Ok((_entity, transform, mut health_component)) = health_query.get_mut(event.target) {
+        if let Ok((_entity, transform, mut health_component, status_effects)) =
+            health_query.get_mut(event.target)
+        {
+            let damage = status_effects.map_or(event.damage, |status_effects| {
+                status_effects.scale_damage(event.damage)
+            });
+
             // take damage from health
-            health_component.take_damage(event.damage);
+            health_component.take_damage(damage);
 
             // spawn damage dealt text effect
             spawn_effect_event_writer.send(SpawnEffectEvent {
@@ -43,7 +69,7 @@ fn damage_system(
                     scale: transform.scale,
                     ..Default::default()
                 },
-                text: Some(event.damage.to_string()),
+                text: Some(damage.to_string()),
                 ..Default::default()
             });
         }
@@ -27,16 +27,17 @@ pub struct ConsumableLootDrop {
 }
 
 impl ConsumableLootDrop {
-    /// Roll for loot drops and spawn consumables
+    /// Roll for loot drops and spawn consumables, rolling `rolls_multiplier` times as often as
+    /// specified
     pub fn roll_and_spawn(
         &self,
         consumable_event_writer: &mut EventWriter<SpawnConsumableEvent>,
         position: Vec2,
+        rng: &mut impl Rng,
+        rolls_multiplier: u32,
     ) {
-        let mut rng = rand::thread_rng();
-
         // roll specified amount of times
-        for _ in 0..self.rolls {
+        for _ in 0..self.rolls * rolls_multiplier {
             // roll using the probability
             let roll = rng.gen_bool(self.probability);
 
@@ -1,5 +1,6 @@
 //! Exposes resources with methods to compute loot drops from killed mobs.
 use bevy::prelude::{App, EventWriter, Plugin, Resource, Vec2};
+use rand::Rng;
 use ron::de::from_bytes;
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -7,7 +8,9 @@ use thetawave_interface::spawnable::{ItemType, SpawnItemEvent};
 
 mod consumable;
 
-use crate::spawnable::SpawnConsumableEvent;
+use crate::{
+    data_reload::ReloadableDataAppExt, game::RunModifierEffects, spawnable::SpawnConsumableEvent,
+};
 
 pub use self::consumable::*;
 
@@ -18,7 +21,8 @@ impl Plugin for LootPlugin {
         app.insert_resource(
             from_bytes::<LootDropsResource>(include_bytes!("../../assets/data/loot_drops.ron"))
                 .unwrap(),
-        );
+        )
+        .register_reloadable_data("loot_drops.ron", |res: LootDropsResource| res);
     }
 }
 
@@ -36,13 +40,16 @@ pub enum LootDrop {
 }
 
 impl LootDropsResource {
-    /// Roll for consumables from drop list and emit events when those rolls succeed.
+    /// Roll for consumables from drop list and emit events when those rolls succeed. Run modifiers
+    /// can add rolls and stop consumables from dropping.
     pub fn spawn_loot_drops(
         &self,
         drop_list_type: &DropListType,
         consumable_event_writer: &mut EventWriter<SpawnConsumableEvent>,
         item_event_writer: &mut EventWriter<SpawnItemEvent>,
         position: Vec2,
+        rng: &mut impl Rng,
+        run_modifier_effects: &RunModifierEffects,
     ) {
         // get drops list from resource
         let drop_list = &self.drops[drop_list_type];
@@ -51,7 +58,14 @@ impl LootDropsResource {
         for loot_drop in drop_list.iter() {
             match loot_drop {
                 LootDrop::Consumable(consumable_loot_drop) => {
-                    consumable_loot_drop.roll_and_spawn(consumable_event_writer, position);
+                    if run_modifier_effects.can_drop(&consumable_loot_drop.consumable) {
+                        consumable_loot_drop.roll_and_spawn(
+                            consumable_event_writer,
+                            position,
+                            rng,
+                            run_modifier_effects.loot_rolls,
+                        );
+                    }
                 }
                 LootDrop::Item(item_type) => {
                     item_event_writer.send(SpawnItemEvent {
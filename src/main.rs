@@ -6,11 +6,12 @@ use bevy::prelude::{
 };
 use bevy_kira_audio::prelude::AudioPlugin;
 
-use crate::options::display::DisplayConfig;
+use crate::options::{continues::get_continues_config, display::DisplayConfig};
 use bevy_rapier2d::prelude::{
     NoUserData, RapierConfiguration, RapierDebugRenderPlugin, RapierPhysicsPlugin, TimestepMode,
 };
 use options::{generate_config_files, GameInitCLIOptions};
+use std::path::PathBuf;
 use thetawave_interface::states::{AppStates, GameStates};
 
 /// Used by a physics engine to translate physics calculations to graphics
@@ -23,17 +24,22 @@ mod audio;
 mod background;
 mod camera;
 mod collision;
+mod data_reload;
 mod game;
 mod health;
 mod loot;
 mod options;
 mod player;
 mod run;
+mod save;
 mod scanner;
+mod simulation;
 mod spawnable;
 mod states;
+mod status_effect;
 mod tools;
 mod ui;
+mod validation;
 mod weapon;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
@@ -94,18 +100,44 @@ fn our_default_plugins(
         })
         .set(ImagePlugin::default_nearest());
 
+    // only watch for changes to assets when data files are being hot reloaded
+    let watch_for_changes_override = opts.hot_reload_data.then_some(true);
+
     match &opts.assets_dir {
         Some(path_) => res.set(AssetPlugin {
             file_path: path_.to_string_lossy().to_string(),
+            watch_for_changes_override,
+            ..Default::default()
+        }),
+        None => res.set(AssetPlugin {
+            watch_for_changes_override,
             ..Default::default()
         }),
-        None => res,
     }
 }
 
 #[allow(unused_variables, unused_mut)] // The options are only used on some platforms/with some installs
 fn our_game_plugins(opts: &GameInitCLIOptions) -> PluginGroupBuilder {
-    let mut res = ThetawaveGamePlugins.build();
+    let selected_run = match (opts.generate_run, &opts.run) {
+        (Some(seed), _) => run::SelectedRunResource::Generated(seed),
+        (None, Some(run_key)) => run::SelectedRunResource::Premade(run_key.clone()),
+        (None, None) => run::SelectedRunResource::default(),
+    };
+    let mut res = ThetawaveGamePlugins
+        .build()
+        .set(run::RunPlugin {
+            selected_run,
+            continues: run::ContinuesResource {
+                max_continues: get_continues_config().max_continues,
+            },
+        })
+        .set(game::GamePlugin {
+            seed: opts.seed,
+            difficulty: opts.difficulty,
+        })
+        .set(data_reload::DataReloadPlugin {
+            hot_reload: opts.hot_reload_data,
+        });
     #[cfg(feature = "arcade")]
     {
         if opts.arcade {
@@ -130,10 +162,59 @@ fn main() {
 
     let opts =
         options::GameInitCLIOptions::from_environ_on_supported_platforms_with_default_fallback();
+
+    if opts.list_runs {
+        println!("{}", run::premade_runs_listing());
+        return;
+    }
+
+    if opts.validate_data {
+        let data_dir = opts
+            .assets_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("assets"))
+            .join("data");
+        let errors = validation::validate_data(&data_dir);
+        for err in errors.iter() {
+            eprintln!("{err}");
+        }
+        if !errors.is_empty() {
+            eprintln!(
+                "Found {} problem(s) in {}",
+                errors.len(),
+                data_dir.display()
+            );
+            std::process::exit(1);
+        }
+        println!("All data in {} is valid", data_dir.display());
+        return;
+    }
+
+    if let Some(run_key) = &opts.run {
+        if let Err(err) = run::validate_premade_run_key(run_key) {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+    }
+
+    if opts.simulate {
+        match simulation::run_simulation(&opts) {
+            Ok(report) => println!("{report}"),
+            Err(err) => {
+                eprintln!("{err}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     let mut app = build_app(
         our_default_plugins(display_config, &opts),
         our_game_plugins(&opts),
     );
+    if cfg!(debug_assertions) {
+        app.add_plugins(RapierDebugRenderPlugin::default());
+    }
 
     app.run();
 }
@@ -155,9 +236,6 @@ fn build_app<P1: PluginGroup, P2: PluginGroup>(base_plugins: P1, game_plugins: P
         OnEnter(AppStates::Game),
         setup_physics.in_set(GameEnterSet::Initialize),
     );
-    if cfg!(debug_assertions) && !cfg!(test) {
-        app.add_plugins(RapierDebugRenderPlugin::default());
-    }
     app
 }
 
@@ -181,9 +259,10 @@ impl PluginGroup for ThetawaveGamePlugins {
         let mut res = PluginGroupBuilder::start::<Self>()
             .add(player::PlayerPlugin)
             .add(spawnable::SpawnablePlugin)
-            .add(run::RunPlugin)
+            .add(run::RunPlugin::default())
+            .add(save::SavePlugin)
             .add(loot::LootPlugin)
-            .add(game::GamePlugin)
+            .add(game::GamePlugin::default())
             .add(background::BackgroundPlugin)
             .add(AudioPlugin)
             .add(camera::CameraPlugin)
@@ -194,6 +273,7 @@ impl PluginGroup for ThetawaveGamePlugins {
             .add(states::StatesPlugin)
             .add(game::counters::plugin::CountingMetricsPlugin)
             .add(health::HealthPlugin)
+            .add(status_effect::StatusEffectPlugin)
             .add(weapon::WeaponPlugin)
             .add(
                 RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(PHYSICS_PIXELS_PER_METER)
@@ -201,7 +281,8 @@ impl PluginGroup for ThetawaveGamePlugins {
             )
             .add(ui::UiPlugin)
             .add(options::OptionsPlugin::default())
-            .add(audio::ThetawaveAudioPlugin);
+            .add(audio::ThetawaveAudioPlugin)
+            .add(data_reload::DataReloadPlugin::default());
         #[cfg(feature = "arcade")]
         {
             res = res.add(thetawave_arcade::arduino::ArcadeArduinoPlugin);
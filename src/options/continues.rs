@@ -0,0 +1,31 @@
+//! Config for continuing a run after a game over.
+use serde::Deserialize;
+
+/// Continue settings, mainly for arcade operators
+#[derive(Deserialize)]
+pub struct ContinuesConfig {
+    /// Maximum number of times a failed level can be restarted in a run. Unlimited if None.
+    pub max_continues: Option<usize>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn get_continues_config() -> ContinuesConfig {
+    use ron::{de::from_bytes, from_str};
+    use std::{env::current_dir, fs::read_to_string};
+
+    let config_path = current_dir().unwrap().join("config");
+
+    // the config files are only generated by the game executable, so use the defaults when
+    // running without them, like in tests
+    match read_to_string(config_path.join("continues.ron")) {
+        Ok(config) => from_str::<ContinuesConfig>(&config).unwrap(),
+        Err(_) => from_bytes::<ContinuesConfig>(include_bytes!("continues.ron")).unwrap(),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn get_continues_config() -> ContinuesConfig {
+    use ron::de::from_bytes;
+
+    from_bytes::<ContinuesConfig>(include_bytes!("continues.ron")).unwrap()
+}
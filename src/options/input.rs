@@ -37,6 +37,40 @@ impl From<InputBindings> for InputsResource {
     }
 }
 
+impl InputBindings {
+    /// Add the default bindings of every action that has no binding. Config files are never
+    /// rewritten once generated, so this binds the actions added since the file was generated.
+    fn with_missing_defaults(mut self, defaults: InputBindings) -> Self {
+        add_missing_bindings(&mut self.menu_keyboard, defaults.menu_keyboard);
+        add_missing_bindings(&mut self.menu_gamepad, defaults.menu_gamepad);
+        add_missing_bindings(&mut self.player_keyboard, defaults.player_keyboard);
+        add_missing_bindings(&mut self.player_gamepad, defaults.player_gamepad);
+        add_missing_bindings(&mut self.player_mouse, defaults.player_mouse);
+        self
+    }
+}
+
+/// Add the default bindings of the actions that aren't bound to anything in `bindings`
+fn add_missing_bindings<A: PartialEq, B>(bindings: &mut Vec<(A, B)>, defaults: Vec<(A, B)>) {
+    let missing_bindings: Vec<_> = defaults
+        .into_iter()
+        .filter(|(action, _)| {
+            !bindings
+                .iter()
+                .any(|(bound_action, _)| bound_action == action)
+        })
+        .collect();
+
+    bindings.extend(missing_bindings);
+}
+
+/// Bindings of the config file included in the game
+fn get_default_input_bindings() -> InputBindings {
+    use ron::de::from_bytes;
+
+    from_bytes::<InputBindings>(include_bytes!("input.ron")).unwrap()
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub(super) fn get_input_bindings() -> InputBindings {
     use ron::from_str;
@@ -44,12 +78,99 @@ pub(super) fn get_input_bindings() -> InputBindings {
 
     let config_path = current_dir().unwrap().join("config");
 
-    from_str::<InputBindings>(&read_to_string(config_path.join("input.ron")).unwrap()).unwrap()
+    // the config files are only generated by the game executable, so use the defaults when
+    // running without them, like in tests
+    match read_to_string(config_path.join("input.ron")) {
+        Ok(config) => from_str::<InputBindings>(&config)
+            .unwrap()
+            .with_missing_defaults(get_default_input_bindings()),
+        Err(_) => get_default_input_bindings(),
+    }
 }
 
 #[cfg(target_arch = "wasm32")]
 pub(super) fn get_input_bindings() -> InputBindings {
-    use ron::de::from_bytes;
+    get_default_input_bindings()
+}
 
-    from_bytes::<InputBindings>(include_bytes!("input.ron")).unwrap()
+#[cfg(test)]
+mod test {
+    use bevy::prelude::*;
+    use thetawave_interface::input::{MenuAction, PlayerAction};
+
+    use super::{get_default_input_bindings, InputBindings};
+
+    /// Bindings of a config file generated before some of the actions were added
+    fn old_input_bindings() -> InputBindings {
+        InputBindings {
+            menu_keyboard: vec![
+                (MenuAction::Confirm, KeyCode::Space),
+                (MenuAction::Back, KeyCode::Escape),
+            ],
+            menu_gamepad: vec![(MenuAction::Confirm, GamepadButtonType::Start)],
+            player_keyboard: vec![(PlayerAction::MoveUp, KeyCode::ArrowUp)],
+            player_gamepad: vec![(PlayerAction::MoveUp, GamepadButtonType::DPadUp)],
+            player_mouse: vec![],
+        }
+    }
+
+    #[test]
+    fn test_difficulty_bindings_are_added_to_old_configs() {
+        let bindings = old_input_bindings().with_missing_defaults(get_default_input_bindings());
+
+        assert!(bindings
+            .menu_keyboard
+            .contains(&(MenuAction::ChangeDifficulty, KeyCode::KeyE)));
+        assert!(bindings
+            .menu_gamepad
+            .contains(&(MenuAction::ChangeDifficulty, GamepadButtonType::North)));
+
+        // actions that are already bound keep only their own bindings
+        let confirm_keys: Vec<_> = bindings
+            .menu_keyboard
+            .iter()
+            .filter(|(action, _)| *action == MenuAction::Confirm)
+            .collect();
+        assert_eq!(confirm_keys, [&(MenuAction::Confirm, KeyCode::Space)]);
+        assert!(!bindings
+            .player_keyboard
+            .contains(&(PlayerAction::MoveUp, KeyCode::KeyW)));
+    }
+
+    #[test]
+    fn test_run_modifier_bindings_are_added_to_old_configs() {
+        let bindings = old_input_bindings().with_missing_defaults(get_default_input_bindings());
+
+        assert!(bindings
+            .menu_keyboard
+            .contains(&(MenuAction::SelectRunModifier, KeyCode::KeyQ)));
+        assert!(bindings
+            .menu_keyboard
+            .contains(&(MenuAction::ToggleRunModifier, KeyCode::KeyF)));
+        assert!(bindings.menu_gamepad.contains(&(
+            MenuAction::SelectRunModifier,
+            GamepadButtonType::RightTrigger
+        )));
+        assert!(bindings
+            .menu_gamepad
+            .contains(&(MenuAction::ToggleRunModifier, GamepadButtonType::West)));
+    }
+
+    #[test]
+    fn test_shop_bindings_are_added_to_old_configs() {
+        let bindings = old_input_bindings().with_missing_defaults(get_default_input_bindings());
+
+        assert!(bindings
+            .player_keyboard
+            .contains(&(PlayerAction::ShopSelect, KeyCode::KeyQ)));
+        assert!(bindings
+            .player_keyboard
+            .contains(&(PlayerAction::ShopBuy, KeyCode::KeyF)));
+        assert!(bindings
+            .player_gamepad
+            .contains(&(PlayerAction::ShopSelect, GamepadButtonType::North)));
+        assert!(bindings
+            .player_gamepad
+            .contains(&(PlayerAction::ShopBuy, GamepadButtonType::West)));
+    }
 }
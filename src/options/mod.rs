@@ -13,14 +13,16 @@ use bevy::{
 };
 use leafwing_input_manager::prelude::InputManagerPlugin;
 use thetawave_interface::{
-    game::options::GameOptions,
+    game::{difficulty::DifficultyLevel, options::GameOptions},
     input::{InputsResource, MenuAction},
     states,
 };
 
+pub(super) mod continues;
 pub(super) mod display;
 mod input;
 
+use crate::simulation::SimulationPolicy;
 use input::get_input_bindings;
 use std::default::Default;
 use std::env::current_dir;
@@ -51,6 +53,46 @@ pub struct GameInitCLIOptions {
     /// whether to use instructions, serial port IO, etc. specific to deploying on an arcade
     /// machine. This should almost never be enabled.
     pub arcade: bool,
+    #[cfg_attr(all(not(target_arch = "wasm32"), feature = "cli"), argh(option))]
+    /// the key of the run in 'premade_runs.ron' to play. Defaults to 'test_run'. Use
+    /// '--list-runs' to see the available runs.
+    pub run: Option<String>,
+    #[cfg_attr(all(not(target_arch = "wasm32"), feature = "cli"), argh(switch))]
+    /// print the keys and level names of all available runs, then exit.
+    pub list_runs: bool,
+    #[cfg_attr(all(not(target_arch = "wasm32"), feature = "cli"), argh(option))]
+    /// procedurally generate a run from the given seed instead of playing a premade run.
+    pub generate_run: Option<u64>,
+    #[cfg_attr(all(not(target_arch = "wasm32"), feature = "cli"), argh(option))]
+    /// seed for all gameplay randomness (formations, loot, projectile spreads, etc). Runs played
+    /// with the same seed and inputs play out the same way. Random if not provided.
+    pub seed: Option<u64>,
+    #[cfg_attr(all(not(target_arch = "wasm32"), feature = "cli"), argh(option))]
+    /// difficulty to start with: easy, normal, hard or nightmare. It can still be changed on the
+    /// character selection screen. Defaults to normal.
+    pub difficulty: Option<DifficultyLevel>,
+    #[cfg_attr(all(not(target_arch = "wasm32"), feature = "cli"), argh(switch))]
+    /// check the files in 'ASSETS_DIR/data' for broken references between them, print every
+    /// problem found, then exit.
+    pub validate_data: bool,
+    #[cfg_attr(all(not(target_arch = "wasm32"), feature = "cli"), argh(switch))]
+    /// load the gameplay data files from 'ASSETS_DIR/data' instead of the versions built into the
+    /// game, and reapply them whenever they change. Files are only watched for changes when the
+    /// game is built with the 'hot_reload' feature.
+    pub hot_reload_data: bool,
+    #[cfg_attr(all(not(target_arch = "wasm32"), feature = "cli"), argh(switch))]
+    /// play the selected run without a window, faster than real time and with scripted players,
+    /// then print stats for every phase and exit. Uses '--run', '--generate-run', '--seed' and
+    /// '--difficulty' to choose what is played.
+    pub simulate: bool,
+    #[cfg_attr(all(not(target_arch = "wasm32"), feature = "cli"), argh(option))]
+    /// how the players play in a simulated run: stand-still, fire or sweep. Defaults to
+    /// stand-still.
+    pub simulation_policy: Option<SimulationPolicy>,
+    #[cfg_attr(all(not(target_arch = "wasm32"), feature = "cli"), argh(option))]
+    /// seconds of game time after which a simulated run is stopped, for runs that never end like
+    /// endless levels. Defaults to 1800.
+    pub simulation_time_limit: Option<u64>,
 }
 impl GameInitCLIOptions {
     pub fn from_environ_on_supported_platforms_with_default_fallback() -> Self {
@@ -94,6 +136,11 @@ fn apply_game_options_system(
     }
 }
 
+/// Player and menu bindings from the input config file
+pub(super) fn get_inputs_resource() -> InputsResource {
+    InputsResource::from(get_input_bindings())
+}
+
 /// Whether we are playing on an arcade machine. This affects some different UI elements.
 /// Generally this will be set at app startup either inferred or explicitly provided as a game
 /// startup parameter, and should probably not be mutated during the game.
@@ -110,7 +157,7 @@ impl Plugin for OptionsPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(InputManagerPlugin::<MenuAction>::default());
 
-        app.insert_resource(InputsResource::from(get_input_bindings()));
+        app.insert_resource(get_inputs_resource());
         app.insert_resource(PlayingOnArcadeResource(self.arcade));
         app.insert_resource(GameOptions::default());
 
@@ -162,6 +209,7 @@ macro_rules! confgen {
 pub(super) fn generate_config_files() {
     confgen!("display.ron");
     confgen!("input.ron");
+    confgen!("continues.ron");
 }
 
 #[cfg(all(test, not(target_arch = "wasm32"), feature = "cli"))]
@@ -176,4 +224,14 @@ mod cli_tests {
             Some(std::path::PathBuf::from("myassets/"))
         );
     }
+
+    #[test]
+    fn test_cli_parse_run_key() {
+        assert_eq!(
+            super::GameInitCLIOptions::from_args(&["thetawave"], &["--run", "my_run"])
+                .unwrap()
+                .run,
+            Some("my_run".to_string())
+        );
+    }
 }
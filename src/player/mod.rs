@@ -2,7 +2,9 @@
 //! and dying.
 use bevy::{
     app::{App, Plugin, Update},
-    ecs::schedule::{common_conditions::in_state, IntoSystemConfigs, OnEnter, OnExit},
+    ecs::schedule::{
+        common_conditions::in_state, IntoSystemConfigs, OnEnter, OnExit, OnTransition,
+    },
 };
 use leafwing_input_manager::prelude::InputManagerPlugin;
 use ron::de::from_bytes;
@@ -14,7 +16,7 @@ use thetawave_interface::{
     states::{AppStates, GameStates},
 };
 
-use crate::{GameEnterSet, GameUpdateSet};
+use crate::{data_reload::ReloadableDataAppExt, GameEnterSet, GameUpdateSet};
 
 pub use self::resources::CharactersResource;
 use self::{
@@ -53,6 +55,9 @@ impl Plugin for PlayerPlugin {
                 .unwrap(),
         );
 
+        app.register_reloadable_data("characters.ron", |res: CharactersResource| res)
+            .register_reloadable_data("abilities.ron", |res: AbilitiesResource| res);
+
         app.insert_resource(PlayersResource::default())
             .insert_resource(InputRestrictionsAtSpawn::default());
 
@@ -78,8 +83,15 @@ impl Plugin for PlayerPlugin {
                 .run_if(in_state(GameStates::Playing)),
         );
 
-        // reset the run after exiting the end game screens and when entering the main menu
-        app.add_systems(OnExit(AppStates::GameOver), players_reset_system);
+        // reset the run after exiting the end game screens and when entering the main menu. The
+        // players are kept when going from the game over screen back to the game to continue.
+        app.add_systems(
+            OnTransition {
+                from: AppStates::GameOver,
+                to: AppStates::MainMenu,
+            },
+            players_reset_system,
+        );
         app.add_systems(OnExit(AppStates::Victory), players_reset_system);
         app.add_systems(OnEnter(AppStates::MainMenu), players_reset_system);
     }
@@ -20,6 +20,7 @@ use thetawave_interface::player::{
 use thetawave_interface::weapon::WeaponProjectileData;
 
 use crate::spawnable::{FireWeaponEvent, InitialMotion};
+use crate::status_effect::{is_stunned, StatusEffectsComponent};
 
 /// Tick ability cooldown timers for each player
 pub(in crate::player) fn player_ability_cooldown_system(
@@ -33,18 +34,25 @@ pub(in crate::player) fn player_ability_cooldown_system(
 
 /// Checks all abilities for if their cooldown timers (in `AbilityCooldownComponent`) are finished, if they are,
 /// and the player has the ability's respective input pressed, sends an ActivateAbilityEvent
-/// and resets the ability's cooldown timer
+/// and resets the ability's cooldown timer. Stunned players can't use their abilities.
 pub(in crate::player) fn player_ability_input_system(
     player_input_query: Query<(
         &ActionState<PlayerAction>,
         &PlayerOutgoingDamageComponent,
         &PlayerIDComponent,
         &Children,
+        Option<&StatusEffectsComponent>,
     )>,
     mut ability_query: Query<(&mut AbilityCooldownComponent, &AbilitySlotIDComponent)>,
     mut ability_event_writer: EventWriter<ActivateAbilityEvent>,
 ) {
-    for (action_state, player_damage, player_id, children) in player_input_query.iter() {
+    for (action_state, player_damage, player_id, children, status_effects) in
+        player_input_query.iter()
+    {
+        if is_stunned(status_effects) {
+            continue;
+        }
+
         for child in children {
             if let Ok((mut ability_cooldown, ability_id)) = ability_query.get_mut(*child) {
                 match ability_id {
@@ -119,6 +127,7 @@ pub(in crate::player) fn standard_weapon_ability_system(
                                 position: player_damage.projectile_spawn_position.clone(),
                                 speed: weapon.speed_multiplier * player_damage.projectile_speed,
                                 direction: weapon.direction,
+                                aimed: false,
                                 despawn_time: weapon.despawn_time_multiplier
                                     * player_damage.projectile_despawn_time,
                                 count: ((weapon.count_multiplier
@@ -10,21 +10,24 @@ use thetawave_interface::input::PlayerAction;
 use thetawave_interface::player::{PlayerComponent, PlayerMovementComponent};
 
 use crate::game::GameParametersResource;
+use crate::status_effect::{is_stunned, StatusEffectsComponent};
 
-/// Move player by modifying velocity with input
+/// Move player by modifying velocity with input. Stunned players ignore their input and slow down.
 pub(in crate::player) fn player_movement_system(
     game_parameters: Res<GameParametersResource>,
     mut player_info: Query<(
         &PlayerMovementComponent,
         &mut Velocity,
         &ActionState<PlayerAction>,
+        Option<&StatusEffectsComponent>,
     )>,
 ) {
-    for (player_movement, mut vel, action_state) in player_info.iter_mut() {
-        let up = action_state.pressed(&PlayerAction::MoveUp);
-        let down = action_state.pressed(&PlayerAction::MoveDown);
-        let left = action_state.pressed(&PlayerAction::MoveLeft);
-        let right = action_state.pressed(&PlayerAction::MoveRight);
+    for (player_movement, mut vel, action_state, status_effects) in player_info.iter_mut() {
+        let stunned = is_stunned(status_effects);
+        let up = action_state.pressed(&PlayerAction::MoveUp) && !stunned;
+        let down = action_state.pressed(&PlayerAction::MoveDown) && !stunned;
+        let left = action_state.pressed(&PlayerAction::MoveLeft) && !stunned;
+        let right = action_state.pressed(&PlayerAction::MoveRight) && !stunned;
 
         if !player_movement.movement_enabled {
             continue;
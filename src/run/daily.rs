@@ -0,0 +1,375 @@
+//! Daily challenge runs. Every daily challenge run played on a day is generated from the same seed,
+//! derived from the local date, so that everyone plays the same run with the same run modifiers
+//! that day. Daily challenges are always played on the same difficulty, can't be continued and
+//! aren't saved, and a result is recorded for the day's leaderboard when the run ends or is quit.
+use bevy::prelude::*;
+use chrono::{Datelike, Local, NaiveDate};
+use rand::{rngs::StdRng, seq::IteratorRandom, SeedableRng};
+use std::time::Duration;
+use thetawave_interface::{
+    character::CharacterType,
+    game::{
+        daily_challenge::{
+            DailyOutcome, DailyResult, DailyResultRecordedEvent, DailyResultsResource,
+        },
+        difficulty::{DifficultyLevel, SelectedDifficultyResource},
+        run_modifiers::SelectedRunModifiersResource,
+    },
+    player::PlayersResource,
+    spawnable::{MobDestroyedEvent, MobType},
+    states::AppStates,
+};
+
+use crate::game::{GameRngResource, RunModifiersResource};
+
+use super::{ContinuesResource, CurrentRunProgressResource};
+
+/// Format of the dates of daily challenges
+const DATE_FORMAT: &str = "%Y-%m-%d";
+/// Mixed into the seeds of daily challenges, so that they don't match the seeds of runs generated
+/// with small numbers
+const DAILY_SEED_SALT: u64 = 0x7468_6574_6177_6176;
+/// Points scored for each enemy destroyed
+const MOB_DESTROYED_SCORE: usize = 10;
+/// Points scored for each level completed
+const LEVEL_COMPLETED_SCORE: usize = 1000;
+/// Points scored for winning the run
+const VICTORY_SCORE: usize = 5000;
+/// Number of run modifiers that daily challenges are played with
+const DAILY_RUN_MODIFIERS: usize = 1;
+/// Difficulty that daily challenges are played on, so that scores can be compared
+const DAILY_DIFFICULTY: DifficultyLevel = DifficultyLevel::Normal;
+
+/// A daily challenge run in progress
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyChallenge {
+    /// Local date of the challenge, formatted as YYYY-MM-DD
+    pub date: String,
+    /// Seed that the run and all of its randomness are generated from
+    pub seed: u64,
+    /// Enemies destroyed so far in the run
+    pub mobs_destroyed: usize,
+    /// How long the run has been played for, including restarted levels
+    pub run_time: Duration,
+}
+
+impl DailyChallenge {
+    pub fn new(date: NaiveDate) -> Self {
+        DailyChallenge {
+            date: format_date(date),
+            seed: get_daily_seed(date),
+            mobs_destroyed: 0,
+            run_time: Duration::ZERO,
+        }
+    }
+
+    pub fn get_score(&self, levels_completed: usize, victory: bool) -> usize {
+        self.mobs_destroyed * MOB_DESTROYED_SCORE
+            + levels_completed * LEVEL_COMPLETED_SCORE
+            + if victory { VICTORY_SCORE } else { 0 }
+    }
+}
+
+/// Tracks the daily challenge, if the run being played is one
+#[derive(Resource, Debug, Default)]
+pub struct DailyChallengeResource {
+    /// The daily challenge being played. None when a normal run is played.
+    pub challenge: Option<DailyChallenge>,
+    /// Seed of the game rng before the daily challenge was started, restored when it ends
+    previous_seed: Option<u64>,
+    /// Run modifiers selected before the daily challenge was started, restored when it ends
+    previous_run_modifiers: Vec<String>,
+    /// Difficulty selected before the daily challenge was started, restored when it ends
+    previous_difficulty: DifficultyLevel,
+    /// Continues allowed before the daily challenge was started, restored when it ends
+    previous_continues: ContinuesResource,
+}
+
+impl DailyChallengeResource {
+    pub fn is_active(&self) -> bool {
+        self.challenge.is_some()
+    }
+}
+
+/// Run condition for systems that shouldn't run during daily challenges
+pub fn daily_challenge_inactive(daily_challenge: Option<Res<DailyChallengeResource>>) -> bool {
+    !daily_challenge.is_some_and(|daily_challenge| daily_challenge.is_active())
+}
+
+/// Sent to start playing today's daily challenge
+#[derive(Event)]
+pub struct StartDailyChallengeEvent;
+
+/// Get the local date
+pub fn get_today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+pub fn format_date(date: NaiveDate) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+/// Derive the seed of a day's challenge from its date. This must not change between versions of
+/// the game, or players on different versions would play different runs.
+pub fn get_daily_seed(date: NaiveDate) -> u64 {
+    // splitmix64, to spread consecutive days out over the whole range of seeds
+    let mut seed = (date.num_days_from_ce() as u64) ^ DAILY_SEED_SALT;
+    seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    seed = (seed ^ (seed >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    seed = (seed ^ (seed >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    seed ^ (seed >> 31)
+}
+
+/// Pick the run modifiers of a day's challenge from its seed, in the order they are defined
+pub fn get_daily_run_modifiers(seed: u64, run_modifiers: &RunModifiersResource) -> Vec<String> {
+    let mut daily_run_modifiers = run_modifiers
+        .modifiers
+        .iter()
+        .enumerate()
+        .choose_multiple(&mut StdRng::seed_from_u64(seed), DAILY_RUN_MODIFIERS);
+    daily_run_modifiers.sort_by_key(|(i, _)| *i);
+
+    daily_run_modifiers
+        .into_iter()
+        .map(|(_, modifier)| modifier.key.clone())
+        .collect()
+}
+
+/// Start today's daily challenge, seeding all of the gameplay randomness from the date and picking
+/// its run modifiers before going on to the instructions and character selection. The difficulty
+/// is fixed and continues are disabled for the challenge.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn start_daily_challenge_system(
+    mut start_daily_challenge_event_reader: EventReader<StartDailyChallengeEvent>,
+    mut daily_challenge: ResMut<DailyChallengeResource>,
+    mut game_rng: ResMut<GameRngResource>,
+    run_modifiers: Res<RunModifiersResource>,
+    mut selected_run_modifiers: ResMut<SelectedRunModifiersResource>,
+    mut selected_difficulty: ResMut<SelectedDifficultyResource>,
+    mut continues: ResMut<ContinuesResource>,
+    mut next_app_state: ResMut<NextState<AppStates>>,
+) {
+    if start_daily_challenge_event_reader.read().last().is_none() {
+        return;
+    }
+
+    let challenge = DailyChallenge::new(get_today());
+    info!(
+        "Starting daily challenge for {} with seed {}",
+        challenge.date, challenge.seed
+    );
+
+    if daily_challenge.previous_seed.is_none() {
+        daily_challenge.previous_seed = Some(game_rng.seed());
+        daily_challenge.previous_run_modifiers = selected_run_modifiers.modifiers.clone();
+        daily_challenge.previous_difficulty = selected_difficulty.level;
+        daily_challenge.previous_continues = *continues;
+    }
+    *game_rng = GameRngResource::new(challenge.seed);
+    selected_run_modifiers.modifiers = get_daily_run_modifiers(challenge.seed, &run_modifiers);
+    selected_difficulty.level = DAILY_DIFFICULTY;
+    *continues = ContinuesResource {
+        max_continues: Some(0),
+    };
+    daily_challenge.challenge = Some(challenge);
+
+    next_app_state.set(AppStates::Instructions);
+}
+
+/// Keep track of the time and enemies destroyed in the daily challenge, for its score
+pub(super) fn track_daily_challenge_system(
+    mut daily_challenge: ResMut<DailyChallengeResource>,
+    mut mob_destroyed_event_reader: EventReader<MobDestroyedEvent>,
+    time: Res<Time>,
+) {
+    if let Some(challenge) = &mut daily_challenge.challenge {
+        challenge.run_time += time.delta();
+        challenge.mobs_destroyed += mob_destroyed_event_reader
+            .read()
+            .filter(|event| matches!(event.mob_type, MobType::Enemy(_)))
+            .count();
+    }
+}
+
+fn record_daily_result(
+    outcome: DailyOutcome,
+    daily_challenge: &DailyChallengeResource,
+    run_res: &CurrentRunProgressResource,
+    players: &PlayersResource,
+    daily_results: &mut DailyResultsResource,
+    daily_result_recorded_event_writer: &mut EventWriter<DailyResultRecordedEvent>,
+) {
+    let Some(challenge) = &daily_challenge.challenge else {
+        return;
+    };
+
+    let result = DailyResult {
+        date: challenge.date.clone(),
+        score: challenge.get_score(
+            run_res.completed_levels.len(),
+            outcome == DailyOutcome::Victory,
+        ),
+        time_secs: challenge.run_time.as_secs(),
+        outcome,
+        character: players
+            .player_data
+            .first()
+            .and_then(|player_data| player_data.as_ref())
+            .map(|player_data| player_data.character.clone())
+            .unwrap_or(CharacterType::Captain),
+    };
+
+    info!("Recording daily challenge result: {:?}", result);
+    daily_results.push(result.clone());
+    daily_result_recorded_event_writer.send(DailyResultRecordedEvent(result));
+}
+
+/// Record a won daily challenge
+pub(super) fn record_daily_victory_system(
+    daily_challenge: Res<DailyChallengeResource>,
+    run_res: Res<CurrentRunProgressResource>,
+    players: Res<PlayersResource>,
+    mut daily_results: ResMut<DailyResultsResource>,
+    mut daily_result_recorded_event_writer: EventWriter<DailyResultRecordedEvent>,
+) {
+    record_daily_result(
+        DailyOutcome::Victory,
+        &daily_challenge,
+        &run_res,
+        &players,
+        &mut daily_results,
+        &mut daily_result_recorded_event_writer,
+    );
+}
+
+/// Record a lost daily challenge. This runs when leaving the game over screen rather than when
+/// entering it, since the players can still continue the run from there, and when the players quit
+/// the run, since daily challenges aren't saved to be resumed.
+pub(super) fn record_daily_defeat_system(
+    daily_challenge: Res<DailyChallengeResource>,
+    run_res: Res<CurrentRunProgressResource>,
+    players: Res<PlayersResource>,
+    mut daily_results: ResMut<DailyResultsResource>,
+    mut daily_result_recorded_event_writer: EventWriter<DailyResultRecordedEvent>,
+) {
+    record_daily_result(
+        DailyOutcome::Defeat,
+        &daily_challenge,
+        &run_res,
+        &players,
+        &mut daily_results,
+        &mut daily_result_recorded_event_writer,
+    );
+}
+
+/// Stop the daily challenge when going back to the main menu, restoring the seed that normal runs
+/// are generated from and the run modifiers, difficulty and continues that were selected
+pub(super) fn end_daily_challenge_system(
+    mut daily_challenge: ResMut<DailyChallengeResource>,
+    mut game_rng: ResMut<GameRngResource>,
+    mut selected_run_modifiers: ResMut<SelectedRunModifiersResource>,
+    mut selected_difficulty: ResMut<SelectedDifficultyResource>,
+    mut continues: ResMut<ContinuesResource>,
+) {
+    if let Some(previous_seed) = daily_challenge.previous_seed.take() {
+        *game_rng = GameRngResource::new(previous_seed);
+        selected_run_modifiers.modifiers =
+            std::mem::take(&mut daily_challenge.previous_run_modifiers);
+        selected_difficulty.level = daily_challenge.previous_difficulty;
+        *continues = daily_challenge.previous_continues;
+    }
+    daily_challenge.challenge = None;
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::{ecs::system::RunSystemOnce, prelude::*};
+    use chrono::NaiveDate;
+    use thetawave_interface::{
+        game::{
+            difficulty::{DifficultyLevel, SelectedDifficultyResource},
+            run_modifiers::SelectedRunModifiersResource,
+        },
+        states::AppStates,
+    };
+
+    use crate::{
+        game::{GameRngResource, RunModifiersResource},
+        run::ContinuesResource,
+    };
+
+    use super::{
+        end_daily_challenge_system, get_daily_run_modifiers, get_daily_seed,
+        start_daily_challenge_system, DailyChallenge, DailyChallengeResource,
+        StartDailyChallengeEvent, DAILY_DIFFICULTY, DAILY_RUN_MODIFIERS,
+    };
+
+    #[test]
+    fn test_daily_challenge_is_derived_from_date() {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let challenge = DailyChallenge::new(date);
+
+        assert_eq!(challenge.date, "2024-05-01");
+        assert_eq!(challenge.seed, DailyChallenge::new(date).seed);
+        assert_ne!(challenge.seed, get_daily_seed(date.succ_opt().unwrap()));
+
+        let run_modifiers = ron::de::from_bytes::<RunModifiersResource>(include_bytes!(
+            "../../assets/data/run_modifiers.ron"
+        ))
+        .unwrap();
+        let daily_run_modifiers = get_daily_run_modifiers(challenge.seed, &run_modifiers);
+        assert_eq!(daily_run_modifiers.len(), DAILY_RUN_MODIFIERS);
+        assert_eq!(
+            daily_run_modifiers,
+            get_daily_run_modifiers(challenge.seed, &run_modifiers)
+        );
+    }
+
+    #[test]
+    fn test_daily_challenge_fixes_difficulty_and_disables_continues() {
+        let mut world = World::new();
+        world.init_resource::<Events<StartDailyChallengeEvent>>();
+        world.init_resource::<NextState<AppStates>>();
+        world.insert_resource(DailyChallengeResource::default());
+        world.insert_resource(GameRngResource::new(3));
+        world.insert_resource(
+            ron::de::from_bytes::<RunModifiersResource>(include_bytes!(
+                "../../assets/data/run_modifiers.ron"
+            ))
+            .unwrap(),
+        );
+        world.insert_resource(SelectedRunModifiersResource::default());
+        world.insert_resource(SelectedDifficultyResource {
+            level: DifficultyLevel::Easy,
+        });
+        world.insert_resource(ContinuesResource {
+            max_continues: None,
+        });
+
+        world.send_event(StartDailyChallengeEvent);
+        world.run_system_once(start_daily_challenge_system);
+
+        assert!(world.resource::<DailyChallengeResource>().is_active());
+        assert_eq!(
+            world.resource::<SelectedDifficultyResource>().level,
+            DAILY_DIFFICULTY
+        );
+        assert!(!world.resource::<ContinuesResource>().can_continue(0));
+
+        // the difficulty and continues of normal runs come back once the challenge is over
+        world.run_system_once(end_daily_challenge_system);
+
+        assert!(!world.resource::<DailyChallengeResource>().is_active());
+        assert_eq!(
+            world.resource::<SelectedDifficultyResource>().level,
+            DifficultyLevel::Easy
+        );
+        assert_eq!(
+            *world.resource::<ContinuesResource>(),
+            ContinuesResource {
+                max_continues: None
+            }
+        );
+        assert_eq!(world.resource::<GameRngResource>().seed(), 3);
+    }
+}
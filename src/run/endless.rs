@@ -0,0 +1,308 @@
+//! Exposes `EndlessPhase`, a level phase that spawns escalating waves of formations until the
+//! players are defeated.
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thetawave_interface::spawnable::SpawnMobEvent;
+
+use super::{
+    formation::{FormationPoolsResource, FormationWeightContext, SpawnFormationEvent},
+    level_phase::BossSpawn,
+};
+
+/// Data and state for an endless phase. Every wave cycles to the next formation pool, spawns
+/// formations more often, and makes newly spawned mobs tougher.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EndlessPhase {
+    /// Formation pools that are cycled through, one pool per wave
+    pub formation_pools: Vec<String>,
+    /// Duration of each wave in seconds
+    pub wave_time: f32,
+    /// Time before the first formation spawns
+    pub first_spawn_delay: f32,
+    /// Multiplier applied to the period between formations for every wave survived
+    pub spawn_period_decay: f32,
+    /// Lowest multiplier that can be applied to the period between formations
+    pub min_spawn_period_multiplier: f32,
+    /// Fraction of a mob's base health that is added for every wave survived
+    pub health_growth_per_wave: f32,
+    /// Number of waves before an additional formation is spawned each time the spawn timer fires
+    pub waves_per_extra_formation: usize,
+    /// Vertical distance between formations that are spawned at the same time
+    pub extra_formation_spacing: f32,
+    /// Number of waves between boss spawns. Bosses are never spawned if 0.
+    pub boss_interval: usize,
+    /// Bosses that can be spawned
+    pub bosses: Vec<BossSpawn>,
+    /// Number of waves that have been survived
+    #[serde(default)]
+    pub wave: usize,
+    #[serde(default)]
+    wave_timer: Option<Timer>,
+    #[serde(default)]
+    spawn_timer: Option<Timer>,
+}
+
+impl EndlessPhase {
+    /// The wave currently being played, starting at 1
+    pub fn get_wave_number(&self) -> usize {
+        self.wave + 1
+    }
+
+    /// Seconds until the next wave begins
+    pub fn get_wave_remaining_secs(&self) -> f32 {
+        self.wave_timer
+            .as_ref()
+            .map_or(self.wave_time, |wave_timer| wave_timer.remaining_secs())
+    }
+
+    /// Multiplier applied to the health of mobs spawned during the current wave
+    pub fn get_health_multiplier(&self) -> f32 {
+        1.0 + self.health_growth_per_wave * self.wave as f32
+    }
+
+    /// Multiplier applied to the period between formations during the current wave
+    fn get_spawn_period_multiplier(&self) -> f32 {
+        self.spawn_period_decay
+            .powi(self.wave as i32)
+            .max(self.min_spawn_period_multiplier)
+    }
+
+    /// Number of formations spawned each time the spawn timer fires during the current wave
+    fn get_formations_per_spawn(&self) -> usize {
+        if self.waves_per_extra_formation > 0 {
+            1 + self.wave / self.waves_per_extra_formation
+        } else {
+            1
+        }
+    }
+
+    /// Progress waves and spawn formations and bosses. An endless phase is never completed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn tick(
+        &mut self,
+        time: &Time,
+        spawn_formation_event_writer: &mut EventWriter<SpawnFormationEvent>,
+        spawn_mob_event_writer: &mut EventWriter<SpawnMobEvent>,
+        formations_res: &FormationPoolsResource,
+        period_multiplier: f32,
+        level_idx: usize,
+        rng: &mut impl Rng,
+    ) -> bool {
+        let wave_timer = self
+            .wave_timer
+            .get_or_insert_with(|| Timer::from_seconds(self.wave_time, TimerMode::Repeating));
+        wave_timer.tick(time.delta());
+
+        if wave_timer.just_finished() {
+            self.wave += 1;
+            info!("Endless wave {} started", self.get_wave_number());
+
+            if self.boss_interval > 0 && self.wave % self.boss_interval == 0 {
+                if let Some(boss) = self.bosses.choose(rng) {
+                    spawn_mob_event_writer.send(SpawnMobEvent {
+                        mob_type: boss.mob_type.clone(),
+                        position: boss.position,
+                        rotation: Quat::default(),
+                        boss: true,
+                        parent: None,
+                    });
+                }
+            }
+        }
+
+        let spawn_timer = self
+            .spawn_timer
+            .get_or_insert_with(|| Timer::from_seconds(self.first_spawn_delay, TimerMode::Once));
+        spawn_timer.tick(time.delta());
+
+        if spawn_timer.just_finished() && !self.formation_pools.is_empty() {
+            let pool_key = &self.formation_pools[self.wave % self.formation_pools.len()];
+            // endless phases have no set length, so only level index curves change their weights
+            let weight_context = FormationWeightContext {
+                phase_progress: 0.0,
+                level_idx,
+            };
+            let mut period = None;
+
+            for formation_idx in 0..self.get_formations_per_spawn() {
+                if let Some(mut formation) =
+                    formations_res.get_random_formation(pool_key.clone(), &weight_context, rng)
+                {
+                    // stack extra formations above each other so they don't overlap
+                    for formation_spawnable in formation.formation_spawnables.iter_mut() {
+                        formation_spawnable.position.y +=
+                            formation_idx as f32 * self.extra_formation_spacing;
+                    }
+
+                    period.get_or_insert(formation.period);
+                    spawn_formation_event_writer.send(SpawnFormationEvent { formation });
+                }
+            }
+
+            if let Some(period) = period {
+                let period = period * self.get_spawn_period_multiplier() * period_multiplier;
+                let spawn_timer = self.spawn_timer.get_or_insert_with(Timer::default);
+                spawn_timer.set_duration(Duration::from_secs_f32(period));
+                spawn_timer.reset();
+                info!("Spawn timer duration reset to: {}", period);
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::{ecs::system::SystemState, prelude::*};
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::{collections::HashMap, time::Duration};
+    use thetawave_interface::spawnable::{EnemyMobType, MobType, SpawnMobEvent, SpawnableType};
+
+    use super::EndlessPhase;
+    use crate::run::{
+        formation::{
+            Formation, FormationPoolsResource, FormationProjectile, FormationSpawnable,
+            SpawnFormationEvent,
+        },
+        level_phase::BossSpawn,
+    };
+
+    fn endless_phase() -> EndlessPhase {
+        EndlessPhase {
+            formation_pools: vec!["test".to_string()],
+            wave_time: 10.0,
+            first_spawn_delay: 1.0,
+            spawn_period_decay: 0.5,
+            min_spawn_period_multiplier: 0.2,
+            health_growth_per_wave: 0.25,
+            waves_per_extra_formation: 2,
+            extra_formation_spacing: 100.0,
+            boss_interval: 3,
+            bosses: vec![BossSpawn {
+                mob_type: MobType::Enemy(EnemyMobType::Repeater),
+                position: Vec2::new(0.0, 500.0),
+            }],
+            wave: 0,
+            wave_timer: None,
+            spawn_timer: None,
+        }
+    }
+
+    fn formation_pools() -> FormationPoolsResource {
+        FormationPoolsResource {
+            formation_pools: HashMap::from([(
+                "test".to_string(),
+                vec![Formation {
+                    formation_spawnables: vec![FormationSpawnable {
+                        spawnable_type: SpawnableType::Mob(MobType::Enemy(EnemyMobType::Drone)),
+                        position: Vec2::new(0.0, 500.0),
+                        delay: 0.0,
+                        projectile: FormationProjectile::default(),
+                    }],
+                    weight: 1.0,
+                    period: 4.0,
+                    weight_curve: None,
+                    mirror_chance: 0.0,
+                    x_jitter: 0.0,
+                }],
+            )]),
+        }
+    }
+
+    /// Tick an endless phase for a number of one second steps, returning the number of formations
+    /// and bosses that were spawned
+    fn tick_seconds(endless_phase: &mut EndlessPhase, seconds: u32) -> (usize, usize) {
+        let mut world = World::new();
+        world.init_resource::<Events<SpawnFormationEvent>>();
+        world.init_resource::<Events<SpawnMobEvent>>();
+        let mut system_state: SystemState<(
+            EventWriter<SpawnFormationEvent>,
+            EventWriter<SpawnMobEvent>,
+        )> = SystemState::new(&mut world);
+        let (mut formation_writer, mut mob_writer) = system_state.get_mut(&mut world);
+
+        let formations_res = formation_pools();
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut time = Time::<()>::default();
+
+        for _ in 0..seconds {
+            time.advance_by(Duration::from_secs(1));
+            endless_phase.tick(
+                &time,
+                &mut formation_writer,
+                &mut mob_writer,
+                &formations_res,
+                1.0,
+                0,
+                &mut rng,
+            );
+        }
+        system_state.apply(&mut world);
+
+        (
+            world.resource::<Events<SpawnFormationEvent>>().len(),
+            world.resource::<Events<SpawnMobEvent>>().len(),
+        )
+    }
+
+    #[test]
+    fn test_waves_scale_mob_health_and_spawn_rate() {
+        let mut endless_phase = endless_phase();
+
+        assert_eq!(endless_phase.get_health_multiplier(), 1.0);
+        assert_eq!(endless_phase.get_spawn_period_multiplier(), 1.0);
+        assert_eq!(endless_phase.get_formations_per_spawn(), 1);
+
+        endless_phase.wave = 2;
+        assert_eq!(endless_phase.get_wave_number(), 3);
+        assert_eq!(endless_phase.get_health_multiplier(), 1.5);
+        assert_eq!(endless_phase.get_spawn_period_multiplier(), 0.25);
+        assert_eq!(endless_phase.get_formations_per_spawn(), 2);
+
+        // the spawn period stops shrinking at the minimum multiplier
+        endless_phase.wave = 10;
+        assert_eq!(endless_phase.get_spawn_period_multiplier(), 0.2);
+        assert_eq!(endless_phase.get_formations_per_spawn(), 6);
+    }
+
+    #[test]
+    fn test_wave_timer_advances_waves_and_spawns_formations() {
+        let mut endless_phase = endless_phase();
+
+        // formations spawn after the first delay, then every 4 seconds during the first wave
+        let (formations, _) = tick_seconds(&mut endless_phase, 9);
+        assert_eq!(endless_phase.get_wave_number(), 1);
+        assert_eq!(formations, 3);
+
+        let (_, bosses) = tick_seconds(&mut endless_phase, 1);
+        assert_eq!(endless_phase.get_wave_number(), 2);
+        assert_eq!(bosses, 0);
+    }
+
+    #[test]
+    fn test_bosses_spawn_every_boss_interval() {
+        let mut endless_phase = endless_phase();
+
+        let (_, bosses) = tick_seconds(&mut endless_phase, 29);
+        assert_eq!(bosses, 0);
+
+        let (_, bosses) = tick_seconds(&mut endless_phase, 1);
+        assert_eq!(endless_phase.wave, 3);
+        assert_eq!(bosses, 1);
+
+        let (_, bosses) = tick_seconds(&mut endless_phase, 30);
+        assert_eq!(endless_phase.wave, 6);
+        assert_eq!(bosses, 1);
+
+        // no bosses are spawned when the interval is 0
+        let mut endless_phase = EndlessPhase {
+            boss_interval: 0,
+            ..self::endless_phase()
+        };
+        let (_, bosses) = tick_seconds(&mut endless_phase, 60);
+        assert_eq!(bosses, 0);
+    }
+}
@@ -1,9 +1,24 @@
-use bevy::prelude::*;
-use serde::Deserialize;
-use std::collections::HashMap;
-use thetawave_interface::spawnable::{SpawnMobEvent, SpawnableType};
+use bevy::{ecs::system::SystemParam, prelude::*};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    f32::consts::{FRAC_PI_2, PI},
+};
+use thetawave_interface::{
+    audio::SoundEffectType,
+    spawnable::{
+        EnemyMobType, MobType, SpawnItemEvent, SpawnMobEvent, SpawnMobSegmentEvent, SpawnPosition,
+        SpawnableType,
+    },
+    weapon::{ArcPatternData, SpreadPattern, WeaponProjectileData},
+};
 
-use crate::{spawnable::SpawnConsumableEvent, tools::weighted_rng};
+use crate::{
+    game::{GameParametersResource, GameRngResource, RngStream},
+    spawnable::{FireWeaponEvent, InitialMotion, SpawnConsumableEvent, SpawnEffectEvent},
+    tools::weighted_rng,
+};
 
 /// Resource for storing collections of formations of spawnables
 #[derive(Resource, Deserialize)]
@@ -12,7 +27,12 @@ pub struct FormationPoolsResource {
 }
 
 impl FormationPoolsResource {
-    pub fn get_random_formation(&self, pool_key: String) -> Option<Formation> {
+    pub fn get_random_formation(
+        &self,
+        pool_key: String,
+        weight_context: &FormationWeightContext,
+        rng: &mut impl Rng,
+    ) -> Option<Formation> {
         let formation_pool = match self.formation_pools.get(&pool_key) {
             Some(pool) => pool,
             None => {
@@ -21,9 +41,20 @@ impl FormationPoolsResource {
             }
         };
 
-        let weights = formation_pool.iter().map(|x| x.weight).collect();
+        let weights: Vec<f32> = formation_pool
+            .iter()
+            .map(|x| x.get_weight(weight_context))
+            .collect();
+
+        if weights.iter().sum::<f32>() <= 0.0 {
+            error!(
+                "Every formation in pool {} has a weight of 0 at this point of the run",
+                pool_key
+            );
+            return None;
+        }
 
-        let random_idx = weighted_rng(weights);
+        let random_idx = weighted_rng(weights, rng);
 
         formation_pool.get(random_idx).cloned()
     }
@@ -32,17 +63,132 @@ impl FormationPoolsResource {
 /// Collection of formations that can be chosen to be spawned
 pub type FormationPool = Vec<Formation>;
 
+/// Where the players are in the run, for evaluating the weight curves of formations
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FormationWeightContext {
+    /// Fraction (0.0 to 1.0) of the current phase that has been played
+    pub phase_progress: f32,
+    /// Index of the current level in the run, starting at 0
+    pub level_idx: usize,
+}
+
+/// Multiplier for the weight of a formation that changes over the course of a phase or a run.
+/// Points are `(x, multiplier)` pairs in increasing order of x. The multiplier is interpolated
+/// linearly between points, and stays at the value of the first or last point outside of them.
+#[derive(Deserialize, Clone, Debug)]
+pub enum WeightCurve {
+    /// x is the fraction (0.0 to 1.0) of the phase that has been played. Phases without a set
+    /// length, like endless phases, are always at 0.0.
+    PhaseProgress(Vec<(f32, f32)>),
+    /// x is the index of the level in the run, starting at 0
+    LevelIndex(Vec<(f32, f32)>),
+}
+
+impl WeightCurve {
+    pub fn get_points(&self) -> &[(f32, f32)] {
+        match self {
+            WeightCurve::PhaseProgress(points) | WeightCurve::LevelIndex(points) => points,
+        }
+    }
+
+    /// Weight multiplier at a point of the run
+    pub fn get_multiplier(&self, context: &FormationWeightContext) -> f32 {
+        let x = match self {
+            WeightCurve::PhaseProgress(_) => context.phase_progress,
+            WeightCurve::LevelIndex(_) => context.level_idx as f32,
+        };
+        let points = self.get_points();
+
+        let Some(&(first_x, first_multiplier)) = points.first() else {
+            return 1.0;
+        };
+        if x <= first_x {
+            return first_multiplier;
+        }
+
+        for segment in points.windows(2) {
+            let ((x0, multiplier0), (x1, multiplier1)) = (segment[0], segment[1]);
+            if x <= x1 {
+                let t = if x1 > x0 { (x - x0) / (x1 - x0) } else { 1.0 };
+                return multiplier0 + (multiplier1 - multiplier0) * t;
+            }
+        }
+
+        points[points.len() - 1].1
+    }
+}
+
 /// Used for storing information about a spawnables in formations
-#[derive(Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct FormationSpawnable {
     /// Type of spawnable in formation
     pub spawnable_type: SpawnableType,
     /// Position of the spawnable
     pub position: Vec2,
+    /// Seconds after the formation spawns before the spawnable is spawned
+    #[serde(default)]
+    pub delay: f32,
+    /// How the spawnable is fired, if it is a projectile
+    #[serde(default)]
+    pub projectile: FormationProjectile,
+}
+
+impl FormationSpawnable {
+    /// Get the spawnable flipped to the other side of the arena. Mobs with left and right variants
+    /// are swapped for the other variant.
+    fn mirrored(&self) -> Self {
+        let spawnable_type = match &self.spawnable_type {
+            SpawnableType::Mob(MobType::Enemy(enemy_mob_type)) => {
+                SpawnableType::Mob(MobType::Enemy(match enemy_mob_type {
+                    EnemyMobType::StraferRight => EnemyMobType::StraferLeft,
+                    EnemyMobType::StraferLeft => EnemyMobType::StraferRight,
+                    EnemyMobType::CrustlingRight => EnemyMobType::CrustlingLeft,
+                    EnemyMobType::CrustlingLeft => EnemyMobType::CrustlingRight,
+                    other => *other,
+                }))
+            }
+            other => other.clone(),
+        };
+
+        FormationSpawnable {
+            spawnable_type,
+            position: Vec2::new(-self.position.x, self.position.y),
+            delay: self.delay,
+            projectile: FormationProjectile {
+                direction: PI - self.projectile.direction,
+                ..self.projectile.clone()
+            },
+        }
+    }
+}
+
+/// How a projectile in a formation is fired
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FormationProjectile {
+    /// Damage dealt by the projectile
+    pub damage: usize,
+    /// Speed of the projectile
+    pub speed: f32,
+    /// Angle in radians that the projectile moves in
+    pub direction: f32,
+    /// Time before the projectile despawns
+    pub despawn_time: f32,
+}
+
+/// Defaults to the projectiles fired down by enemies
+impl Default for FormationProjectile {
+    fn default() -> Self {
+        FormationProjectile {
+            damage: 10,
+            speed: 400.0,
+            direction: 3.0 * FRAC_PI_2,
+            despawn_time: 2.5,
+        }
+    }
 }
 
 /// A group of spawnables to be spawned at the same time
-#[derive(Deserialize, Clone)]
+#[derive(Deserialize, Clone, Debug)]
 pub struct Formation {
     /// Vector of spawnables with positions
     pub formation_spawnables: Vec<FormationSpawnable>,
@@ -50,38 +196,50 @@ pub struct Formation {
     pub weight: f32,
     /// Time until next spawn
     pub period: f32,
+    /// Scales the weight over the course of the phase or the run
+    #[serde(default)]
+    pub weight_curve: Option<WeightCurve>,
+    /// Chance (0.0 to 1.0) of the formation being flipped horizontally when it spawns
+    #[serde(default)]
+    pub mirror_chance: f32,
+    /// Maximum distance that the whole formation is randomly moved left or right when it spawns
+    #[serde(default)]
+    pub x_jitter: f32,
 }
 
 impl Formation {
-    /// Spawn all spawnables in the formation at once
-    pub fn spawn_formation(
-        &self,
-        spawn_consumable: &mut EventWriter<SpawnConsumableEvent>,
-        spawn_mob: &mut EventWriter<SpawnMobEvent>,
-    ) {
-        // iterate through all spawnables in the formation and spawn at given position
-        for formation_spawnable in self.formation_spawnables.iter() {
-            // TODO: add cases for items, consumables, etc, as they are added
-            // call the appropriate spawn function for the spawnable
-            match &formation_spawnable.spawnable_type {
-                thetawave_interface::spawnable::SpawnableType::Mob(mob_type) => {
-                    spawn_mob.send(SpawnMobEvent {
-                        mob_type: mob_type.clone(),
-                        position: formation_spawnable.position,
-                        rotation: Quat::default(),
-                        boss: false,
-                    });
-                }
-
-                SpawnableType::Consumable(consumable_type) => {
-                    spawn_consumable.send(SpawnConsumableEvent {
-                        consumable_type: consumable_type.clone(),
-                        position: formation_spawnable.position,
-                    });
-                }
-                _ => {}
-            }
-        }
+    /// Relative likelihood of spawning at a point of the run
+    pub fn get_weight(&self, weight_context: &FormationWeightContext) -> f32 {
+        let multiplier = self.weight_curve.as_ref().map_or(1.0, |weight_curve| {
+            weight_curve.get_multiplier(weight_context)
+        });
+
+        (self.weight * multiplier).max(0.0)
+    }
+
+    /// Get the spawnables of the formation, randomly mirrored and moved according to the options
+    /// of the formation. Randomness is only used by the options that are set, so that formations
+    /// without them always spawn the same way.
+    pub fn get_placed_spawnables(&self, rng: &mut impl Rng) -> Vec<FormationSpawnable> {
+        let mirrored = self.mirror_chance > 0.0 && rng.gen_bool(self.mirror_chance.min(1.0) as f64);
+        let x_offset = if self.x_jitter > 0.0 {
+            rng.gen_range(-self.x_jitter..=self.x_jitter)
+        } else {
+            0.0
+        };
+
+        self.formation_spawnables
+            .iter()
+            .map(|formation_spawnable| {
+                let mut placed = if mirrored {
+                    formation_spawnable.mirrored()
+                } else {
+                    formation_spawnable.clone()
+                };
+                placed.position.x += x_offset;
+                placed
+            })
+            .collect()
     }
 }
 
@@ -92,15 +250,231 @@ pub struct SpawnFormationEvent {
     pub formation: Formation,
 }
 
-/// Manages spawning of formations
+/// Spawnables of formations that are waiting for their delay to pass
+#[derive(Resource, Default)]
+pub struct DelayedFormationSpawnsResource(Vec<(Timer, FormationSpawnable)>);
+
+/// Event writers for every type of spawnable that can be in a formation
+#[derive(SystemParam)]
+pub struct FormationSpawnParams<'w> {
+    spawn_consumable: EventWriter<'w, SpawnConsumableEvent>,
+    spawn_mob: EventWriter<'w, SpawnMobEvent>,
+    spawn_mob_segment: EventWriter<'w, SpawnMobSegmentEvent>,
+    spawn_item: EventWriter<'w, SpawnItemEvent>,
+    spawn_effect: EventWriter<'w, SpawnEffectEvent>,
+    fire_weapon: EventWriter<'w, FireWeaponEvent>,
+    game_parameters: Res<'w, GameParametersResource>,
+}
+
+impl FormationSpawnParams<'_> {
+    /// Send the spawn event matching the type of the spawnable
+    fn spawn(&mut self, formation_spawnable: &FormationSpawnable) {
+        let position = formation_spawnable.position;
+
+        match &formation_spawnable.spawnable_type {
+            SpawnableType::Mob(mob_type) => {
+                self.spawn_mob.send(SpawnMobEvent {
+                    mob_type: mob_type.clone(),
+                    position,
+                    rotation: Quat::default(),
+                    boss: false,
+                    parent: None,
+                });
+            }
+            SpawnableType::MobSegment(mob_segment_type) => {
+                self.spawn_mob_segment.send(SpawnMobSegmentEvent {
+                    mob_segment_type: mob_segment_type.clone(),
+                    position,
+                });
+            }
+            SpawnableType::Consumable(consumable_type) => {
+                self.spawn_consumable.send(SpawnConsumableEvent {
+                    consumable_type: consumable_type.clone(),
+                    position,
+                });
+            }
+            SpawnableType::Item(item_type) => {
+                self.spawn_item.send(SpawnItemEvent {
+                    item_type: item_type.clone(),
+                    position,
+                });
+            }
+            SpawnableType::Effect(effect_type) => {
+                self.spawn_effect.send(SpawnEffectEvent {
+                    effect_type: effect_type.clone(),
+                    transform: Transform {
+                        translation: position.extend(0.0),
+                        scale: Vec3::new(
+                            self.game_parameters.sprite_scale,
+                            self.game_parameters.sprite_scale,
+                            1.0,
+                        ),
+                        ..Default::default()
+                    },
+                    initial_motion: InitialMotion::default(),
+                    text: None,
+                });
+            }
+            SpawnableType::Projectile(projectile_type) => {
+                let projectile = &formation_spawnable.projectile;
+
+                // projectiles in formations aren't fired by anything
+                self.fire_weapon.send(FireWeaponEvent {
+                    weapon_projectile_data: WeaponProjectileData {
+                        ammunition: *projectile_type,
+                        damage: projectile.damage,
+                        position: SpawnPosition::Global(position),
+                        speed: projectile.speed,
+                        direction: projectile.direction,
+                        aimed: false,
+                        despawn_time: projectile.despawn_time,
+                        count: 1,
+                        spread_pattern: SpreadPattern::Arc(ArcPatternData {
+                            spread_weights: Vec2::ONE,
+                            max_spread: 0.0,
+                            projectile_gap: 0.0,
+                        }),
+                        size: 1.0,
+                        sound: SoundEffectType::EnemyFireBlast,
+                    },
+                    source_transform: Transform::from_translation(position.extend(0.0)),
+                    source_entity: Entity::PLACEHOLDER,
+                    initial_motion: InitialMotion::default(),
+                });
+            }
+        }
+    }
+}
+
+/// Manages spawning of formations, holding back the spawnables that have a delay
 pub fn spawn_formation_system(
     mut spawn_formation: EventReader<SpawnFormationEvent>,
-    mut spawn_consumable: EventWriter<SpawnConsumableEvent>,
-    mut spawn_mob: EventWriter<SpawnMobEvent>,
+    mut spawn_params: FormationSpawnParams,
+    mut delayed_spawns: ResMut<DelayedFormationSpawnsResource>,
+    mut game_rng: ResMut<GameRngResource>,
+    time: Res<Time>,
 ) {
+    // spawn the delayed spawnables whose delay has passed
+    delayed_spawns.0.retain_mut(|(timer, formation_spawnable)| {
+        if timer.tick(time.delta()).finished() {
+            spawn_params.spawn(formation_spawnable);
+            false
+        } else {
+            true
+        }
+    });
+
     for event in spawn_formation.read() {
-        event
+        for formation_spawnable in event
             .formation
-            .spawn_formation(&mut spawn_consumable, &mut spawn_mob);
+            .get_placed_spawnables(game_rng.stream(RngStream::Formations))
+        {
+            if formation_spawnable.delay > 0.0 {
+                delayed_spawns.0.push((
+                    Timer::from_seconds(formation_spawnable.delay, TimerMode::Once),
+                    formation_spawnable,
+                ));
+            } else {
+                spawn_params.spawn(&formation_spawnable);
+            }
+        }
+    }
+}
+
+/// Drop the delayed spawnables of formations from the last game
+pub fn clear_delayed_formation_spawns_system(
+    mut delayed_spawns: ResMut<DelayedFormationSpawnsResource>,
+) {
+    delayed_spawns.0.clear();
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::math::Vec2;
+    use rand::{rngs::StdRng, SeedableRng};
+    use thetawave_interface::spawnable::{EnemyMobType, MobType, SpawnableType};
+
+    use super::{
+        Formation, FormationProjectile, FormationSpawnable, FormationWeightContext, WeightCurve,
+    };
+
+    fn strafer_formation(mirror_chance: f32, x_jitter: f32) -> Formation {
+        Formation {
+            formation_spawnables: vec![
+                FormationSpawnable {
+                    spawnable_type: SpawnableType::Mob(MobType::Enemy(EnemyMobType::StraferRight)),
+                    position: Vec2::new(-100.0, 500.0),
+                    delay: 0.0,
+                    projectile: FormationProjectile::default(),
+                },
+                FormationSpawnable {
+                    spawnable_type: SpawnableType::Mob(MobType::Enemy(EnemyMobType::Pawn)),
+                    position: Vec2::new(200.0, 600.0),
+                    delay: 1.5,
+                    projectile: FormationProjectile::default(),
+                },
+            ],
+            weight: 1.0,
+            period: 5.0,
+            weight_curve: None,
+            mirror_chance,
+            x_jitter,
+        }
+    }
+
+    #[test]
+    fn test_mirrored_formation_is_flipped_with_swapped_variants() {
+        let placed =
+            strafer_formation(1.0, 0.0).get_placed_spawnables(&mut StdRng::seed_from_u64(0));
+
+        assert_eq!(
+            placed[0].spawnable_type,
+            SpawnableType::Mob(MobType::Enemy(EnemyMobType::StraferLeft))
+        );
+        assert_eq!(placed[0].position, Vec2::new(100.0, 500.0));
+        assert_eq!(placed[1].position, Vec2::new(-200.0, 600.0));
+        assert_eq!(placed[1].delay, 1.5);
+    }
+
+    #[test]
+    fn test_weight_curve_is_interpolated_between_points() {
+        let weight_curve = WeightCurve::PhaseProgress(vec![(0.25, 0.0), (0.75, 2.0)]);
+        let multiplier_at = |phase_progress| {
+            weight_curve.get_multiplier(&FormationWeightContext {
+                phase_progress,
+                level_idx: 0,
+            })
+        };
+
+        assert_eq!(multiplier_at(0.0), 0.0);
+        assert_eq!(multiplier_at(0.5), 1.0);
+        assert_eq!(multiplier_at(1.0), 2.0);
+
+        let level_curve = WeightCurve::LevelIndex(vec![(0.0, 1.0), (2.0, 3.0)]);
+        let formation = Formation {
+            weight_curve: Some(level_curve),
+            ..strafer_formation(0.0, 0.0)
+        };
+        let context = FormationWeightContext {
+            phase_progress: 0.0,
+            level_idx: 1,
+        };
+
+        assert_eq!(formation.get_weight(&context), 2.0);
+    }
+
+    #[test]
+    fn test_jittered_formation_keeps_its_layout() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..20 {
+            let placed = strafer_formation(0.0, 50.0).get_placed_spawnables(&mut rng);
+            let offset = placed[0].position.x + 100.0;
+            let gap = placed[1].position.x - placed[0].position.x;
+
+            assert!(offset.abs() <= 50.0);
+            assert!((gap - 300.0).abs() < 0.001);
+            assert_eq!(placed[0].position.y, 500.0);
+        }
     }
 }
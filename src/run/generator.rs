@@ -0,0 +1,289 @@
+//! Exposes a resource for procedurally generating the levels of a run from a seed, as an
+//! alternative to the hand written levels in 'premade_levels.ron'.
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+use serde::Deserialize;
+use std::{collections::VecDeque, ops::Range};
+use thetawave_interface::{objective::Objective, spawnable::MobType};
+
+use super::{
+    formation::FormationPoolsResource,
+    level::{BGMusicTransition, Level, LevelData, LevelPhase},
+    level_phase::{BossSpawn, LevelPhaseType},
+    OurRunGenerationError,
+};
+
+/// Maps the progress through a run (0.0 at the first phase, 1.0 at the last) to a difficulty
+/// between `start` and `end`
+#[derive(Deserialize, Clone, Debug)]
+pub struct DifficultyCurve {
+    pub start: f32,
+    pub end: f32,
+    /// Values above 1.0 keep the early game easier for longer, values below 1.0 ramp up quickly
+    pub exponent: f32,
+}
+
+impl DifficultyCurve {
+    /// Difficulty at the given progress through the run
+    pub fn sample(&self, progress: f32) -> f32 {
+        let difficulty =
+            self.start + (self.end - self.start) * progress.clamp(0.0, 1.0).powf(self.exponent);
+
+        difficulty.clamp(0.0, 1.0)
+    }
+}
+
+/// A boss that generated levels can end with
+#[derive(Deserialize, Clone, Debug)]
+pub struct GeneratorBossData {
+    pub mob_type: MobType,
+    pub position: Vec2,
+    /// Lowest difficulty of a level that the boss can end
+    #[serde(default)]
+    pub min_difficulty: f32,
+}
+
+impl From<&GeneratorBossData> for BossSpawn {
+    fn from(boss: &GeneratorBossData) -> Self {
+        BossSpawn {
+            mob_type: boss.mob_type.clone(),
+            position: boss.position,
+        }
+    }
+}
+
+/// Parameters used to procedurally generate runs
+#[derive(Resource, Deserialize)]
+pub struct RunGeneratorResource {
+    /// Number of levels in a generated run
+    pub levels: usize,
+    /// Number of formation spawn phases before the boss of each level
+    pub formation_phases_per_level: usize,
+    /// Keys of formation pools, ordered from easiest to hardest
+    pub formation_pools: Vec<String>,
+    /// Maximum number of pools above or below the difficulty that a phase can pick from
+    pub formation_pool_jitter: usize,
+    /// Difficulty of a phase given how far into the run it is
+    pub difficulty_curve: DifficultyCurve,
+    /// Bosses that can be picked for the final phase of each level, given the difficulty of the
+    /// level
+    pub bosses: Vec<GeneratorBossData>,
+    /// Duration of formation spawn phases, from lowest to highest difficulty
+    pub phase_time: Range<f32>,
+    /// Multiplier applied to the period of spawned formations, from lowest to highest difficulty
+    pub spawn_period_multiplier: Range<f32>,
+    /// Time before the first formation of a phase spawns
+    pub first_spawn_delay: f32,
+    /// Duration of the breaks between formation spawn phases
+    pub break_time: f32,
+    /// Time before the boss of a level spawns
+    pub boss_spawn_delay: f32,
+    /// Duration of the shop phase after the boss of every level but the last. No shop phases are
+    /// generated if None.
+    #[serde(default)]
+    pub shop_time: Option<f32>,
+    /// Objective of every generated level
+    pub objective: Option<Objective>,
+    pub level_intro_text: Option<String>,
+    pub boss_intro_text: Option<String>,
+    #[serde(default)]
+    pub shop_intro_text: Option<String>,
+    pub level_music_transition: Option<BGMusicTransition>,
+    pub boss_music_transition: Option<BGMusicTransition>,
+}
+
+/// Linearly interpolate within a range
+fn lerp(range: &Range<f32>, t: f32) -> f32 {
+    range.start + (range.end - range.start) * t
+}
+
+impl RunGeneratorResource {
+    /// Generate the levels of a run. The same rng state and parameters always produce the same
+    /// levels.
+    pub fn generate_levels(
+        &self,
+        rng: &mut impl Rng,
+        formations_res: &FormationPoolsResource,
+    ) -> Result<VecDeque<Level>, OurRunGenerationError> {
+        if let Some(pool_key) = self
+            .formation_pools
+            .iter()
+            .find(|pool_key| !formations_res.formation_pools.contains_key(*pool_key))
+        {
+            return Err(OurRunGenerationError::UnknownFormationPool(
+                pool_key.clone(),
+            ));
+        }
+
+        let total_phases = self.levels * self.formation_phases_per_level;
+        let mut levels = VecDeque::new();
+
+        for level_idx in 0..self.levels {
+            let mut phases = vec![];
+            let mut difficulty = self.difficulty_curve.sample(0.0);
+
+            for phase_idx in 0..self.formation_phases_per_level {
+                // progress through the whole run, so that later levels are harder
+                let run_phase_idx = level_idx * self.formation_phases_per_level + phase_idx;
+                let progress = if total_phases > 1 {
+                    run_phase_idx as f32 / (total_phases - 1) as f32
+                } else {
+                    0.0
+                };
+                difficulty = self.difficulty_curve.sample(progress);
+
+                // the first phase of each level introduces the level, later phases follow a break
+                if phase_idx == 0 {
+                    phases.push(LevelPhase {
+                        phase_type: self.generate_formation_phase(rng, difficulty),
+                        bg_music_transition: self.level_music_transition.clone(),
+                        intro_text: self.level_intro_text.clone(),
+                        id: None,
+                        branch_only: false,
+                        branches: vec![],
+                    });
+                } else {
+                    phases.push(LevelPhase {
+                        phase_type: LevelPhaseType::Break {
+                            phase_timer: Timer::from_seconds(self.break_time, TimerMode::Once),
+                        },
+                        bg_music_transition: None,
+                        intro_text: None,
+                        id: None,
+                        branch_only: false,
+                        branches: vec![],
+                    });
+                    phases.push(LevelPhase {
+                        phase_type: self.generate_formation_phase(rng, difficulty),
+                        bg_music_transition: None,
+                        intro_text: None,
+                        id: None,
+                        branch_only: false,
+                        branches: vec![],
+                    });
+                }
+            }
+
+            // the boss matches the difficulty of the last formation phase of the level
+            if let Some(boss) = self.choose_boss(rng, difficulty) {
+                phases.push(LevelPhase {
+                    phase_type: LevelPhaseType::Boss {
+                        mob_type: boss.mob_type.clone(),
+                        position: boss.position,
+                        spawn_timer: Timer::from_seconds(self.boss_spawn_delay, TimerMode::Once),
+                    },
+                    bg_music_transition: self.boss_music_transition.clone(),
+                    intro_text: self.boss_intro_text.clone(),
+                    id: None,
+                    branch_only: false,
+                    branches: vec![],
+                });
+            }
+
+            if let Some(shop_time) = self.shop_time.filter(|_| level_idx + 1 < self.levels) {
+                phases.push(LevelPhase {
+                    phase_type: LevelPhaseType::Shop {
+                        phase_timer: Timer::from_seconds(shop_time, TimerMode::Once),
+                    },
+                    bg_music_transition: None,
+                    intro_text: self.shop_intro_text.clone(),
+                    id: None,
+                    branch_only: false,
+                    branches: vec![],
+                });
+            }
+
+            levels.push_back(Level::from(&LevelData {
+                phases,
+                objective: self.objective.clone(),
+                name: format!("Level {}", level_idx + 1),
+            }));
+        }
+
+        Ok(levels)
+    }
+
+    /// Pick a boss for a level of the given difficulty. If no boss is easy enough, the easiest one
+    /// is picked.
+    fn choose_boss(&self, rng: &mut impl Rng, difficulty: f32) -> Option<BossSpawn> {
+        let eligible_bosses: Vec<&GeneratorBossData> = self
+            .bosses
+            .iter()
+            .filter(|boss| boss.min_difficulty <= difficulty)
+            .collect();
+
+        eligible_bosses
+            .choose(rng)
+            .copied()
+            .or_else(|| {
+                self.bosses
+                    .iter()
+                    .min_by(|a, b| a.min_difficulty.total_cmp(&b.min_difficulty))
+            })
+            .map(BossSpawn::from)
+    }
+
+    /// Create a formation spawn phase, picking a pool near the given difficulty
+    fn generate_formation_phase(&self, rng: &mut impl Rng, difficulty: f32) -> LevelPhaseType {
+        let max_pool_idx = self.formation_pools.len().saturating_sub(1);
+        let target_pool_idx = (difficulty * max_pool_idx as f32).round() as usize;
+        let pool_idx = rng.gen_range(
+            target_pool_idx.saturating_sub(self.formation_pool_jitter)
+                ..=(target_pool_idx + self.formation_pool_jitter).min(max_pool_idx),
+        );
+
+        LevelPhaseType::FormationSpawn {
+            phase_timer: Timer::from_seconds(lerp(&self.phase_time, difficulty), TimerMode::Once),
+            spawn_timer: Timer::from_seconds(self.first_spawn_delay, TimerMode::Once),
+            formation_pool: self
+                .formation_pools
+                .get(pool_idx)
+                .cloned()
+                .unwrap_or_default(),
+            spawn_period_multiplier: Some(lerp(&self.spawn_period_multiplier, difficulty)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::math::Vec2;
+    use rand::{rngs::StdRng, SeedableRng};
+    use thetawave_interface::spawnable::{EnemyMobType, MobType};
+
+    use super::{GeneratorBossData, RunGeneratorResource};
+
+    fn boss(enemy_mob_type: EnemyMobType, min_difficulty: f32) -> GeneratorBossData {
+        GeneratorBossData {
+            mob_type: MobType::Enemy(enemy_mob_type),
+            position: Vec2::new(0.0, 600.0),
+            min_difficulty,
+        }
+    }
+
+    #[test]
+    fn test_bosses_are_picked_by_difficulty() {
+        let mut run_generator = ron::de::from_bytes::<RunGeneratorResource>(include_bytes!(
+            "../../assets/data/run_generator.ron"
+        ))
+        .unwrap();
+        run_generator.bosses = vec![
+            boss(EnemyMobType::Repeater, 0.5),
+            boss(EnemyMobType::Drone, 0.8),
+        ];
+        let mut rng = StdRng::seed_from_u64(3);
+
+        // bosses are only picked once levels are hard enough for them
+        for _ in 0..10 {
+            let boss = run_generator.choose_boss(&mut rng, 0.6).unwrap();
+            assert_eq!(boss.mob_type, MobType::Enemy(EnemyMobType::Repeater));
+        }
+
+        // levels easier than every boss get the easiest boss
+        let boss = run_generator.choose_boss(&mut rng, 0.1).unwrap();
+        assert_eq!(boss.mob_type, MobType::Enemy(EnemyMobType::Repeater));
+
+        run_generator.bosses.clear();
+        assert!(run_generator.choose_boss(&mut rng, 1.0).is_none());
+    }
+}
@@ -4,7 +4,8 @@ use crate::run::level_phase::LevelPhaseType;
 use crate::run::tutorial::modify_player_spawn_params_for_lesson_phase;
 use bevy::{prelude::*, time::Stopwatch};
 use leafwing_input_manager::prelude::ActionState;
-use serde::Deserialize;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, VecDeque},
     time::Duration,
@@ -19,9 +20,17 @@ use thetawave_interface::{
     spawnable::{MobDestroyedEvent, MobSegmentDestroyedEvent, SpawnMobEvent},
 };
 
-use crate::spawnable::BossesDestroyedEvent;
+use crate::{
+    game::{scale_usize, DifficultyMultipliers, GameRngResource, RngStream},
+    spawnable::BossesDestroyedEvent,
+};
 
-use super::{FormationPoolsResource, SpawnFormationEvent};
+use super::{
+    formation::FormationWeightContext,
+    phase_branch::{choose_branch, PhaseBranch, PhaseConditionContext},
+    timeline::TimelineParams,
+    FormationPoolsResource, SpawnFormationEvent,
+};
 
 #[derive(Resource, Deserialize)]
 pub(super) struct PremadeLevelsResource {
@@ -29,17 +38,27 @@ pub(super) struct PremadeLevelsResource {
 }
 
 /// A defined section of the level
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct LevelPhase {
     /// phase type
     pub phase_type: LevelPhaseType,
     /// music to play during phase
     pub bg_music_transition: Option<BGMusicTransition>,
     pub intro_text: Option<String>,
+    /// Name that other phases of the level can branch to
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Whether the phase is skipped unless another phase branches to it
+    #[serde(default)]
+    pub branch_only: bool,
+    /// Checked in order when the phase is completed. The first branch with a condition that is met
+    /// picks the next phase, otherwise the next phase in the level is played.
+    #[serde(default)]
+    pub branches: Vec<PhaseBranch>,
 }
 
 /// Background music transition
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct BGMusicTransition {
     pub loop_from: Option<f64>,
     pub bg_music_type: Option<BGMusicType>,
@@ -72,7 +91,7 @@ pub struct LevelData {
 pub type LevelPhases = VecDeque<LevelPhase>;
 
 /// The state of a full level. This will be mutated while the level is being played.
-#[derive(Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Level {
     /// Phases that have been completed so far in the run
     pub completed_phases: LevelPhases,
@@ -84,8 +103,13 @@ pub struct Level {
     pub objective: Option<Objective>,
     /// Tracks how long the player has been in the level
     pub level_time: Stopwatch,
+    /// Tracks how long the player has been in the current phase
+    pub phase_time: Stopwatch,
     /// Name of the level
     pub name: String,
+    /// Phases and objective the level started with, for restarting the level
+    initial_phases: LevelPhases,
+    initial_objective: Option<Objective>,
 }
 
 impl Level {
@@ -106,14 +130,63 @@ impl From<&LevelData> for Level {
             queued_phases: data.phases.clone().into(),
             objective: data.objective.clone(),
             level_time: Stopwatch::new(),
+            phase_time: Stopwatch::new(),
             name: data.name.clone(),
+            initial_phases: data.phases.clone().into(),
+            initial_objective: data.objective.clone(),
         }
     }
 }
 
 impl Level {
+    /// Scale the defense that the level's defense objective starts with, also when the level is
+    /// restarted. The maximum defense is unchanged.
+    pub fn scale_starting_defense(&mut self, multiplier: f32) {
+        for objective in [&mut self.objective, &mut self.initial_objective]
+            .into_iter()
+            .flatten()
+        {
+            if let Objective::Defense(defense_data) = objective {
+                defense_data.defense =
+                    scale_usize(defense_data.defense, multiplier).min(defense_data.max_defense);
+            }
+        }
+    }
+
+    /// Put the level back in the state it started in. The first phase still needs to be cycled to.
+    pub fn restart(&mut self) {
+        self.completed_phases.clear();
+        self.current_phase = None;
+        self.queued_phases = self.initial_phases.clone();
+        self.objective = self.initial_objective.clone();
+        self.level_time.reset();
+        self.phase_time.reset();
+    }
+
+    /// Put the current phase back in the state it started in, keeping the progress made in the
+    /// rest of the level
+    pub fn restart_current_phase(&mut self) {
+        if self.current_phase.is_none() {
+            return;
+        }
+
+        // phases are only ever taken from the front of the queue, so the queued phases are always
+        // the end of the initial phases and the current phase is the one right before them
+        if let Some(phase_idx) = self
+            .initial_phases
+            .len()
+            .checked_sub(self.queued_phases.len() + 1)
+        {
+            self.current_phase = self.initial_phases.get(phase_idx).cloned();
+            self.phase_time.reset();
+        }
+    }
+
+    /// Move on to the next phase, or to the phase with the id `branch_to` if it is provided. Returns
+    /// true if there are no phases left.
     pub fn cycle_phase(
         &mut self,
+        branch_to: Option<&str>,
         cycle_phase_event_writer: &mut EventWriter<CyclePhaseEvent>,
     ) -> bool {
         // "clean up" the just completed phase & push it to the back of the queue to be replayed
@@ -122,8 +195,33 @@ impl Level {
             self.current_phase = None;
         }
 
-        // pop the next level (if it exists) into the the current level
-        self.current_phase = self.queued_phases.pop_front();
+        // skip the phases before the phase that is branched to
+        if let Some(branch_to) = branch_to {
+            match self
+                .queued_phases
+                .iter()
+                .position(|phase| phase.id.as_deref() == Some(branch_to))
+            {
+                Some(phase_idx) => {
+                    self.queued_phases.drain(..phase_idx);
+                    self.current_phase = self.queued_phases.pop_front();
+                    info!("Phase branched to: {}", branch_to);
+                }
+                None => warn!("No queued phase to branch to with id: {}", branch_to),
+            }
+        }
+
+        // otherwise pop the next phase that isn't only played when branched to
+        if self.current_phase.is_none() {
+            while let Some(phase) = self.queued_phases.pop_front() {
+                if !phase.branch_only {
+                    self.current_phase = Some(phase);
+                    break;
+                }
+            }
+        }
+
+        self.phase_time.reset();
 
         info!("Phase cycled");
 
@@ -147,12 +245,14 @@ impl Level {
     }
 
     // returns true if level has been completed
+    #[allow(clippy::too_many_arguments)]
     pub fn tick(
         &mut self,
         time: &Time,
         player_query: &Query<&ActionState<PlayerAction>, With<PlayerComponent>>,
         spawn_formation_event_writer: &mut EventWriter<SpawnFormationEvent>,
         formations_res: &FormationPoolsResource,
+        level_idx: usize,
         spawn_mob_event_writer: &mut EventWriter<SpawnMobEvent>,
         bosses_destroyed_event_reader: &mut EventReader<BossesDestroyedEvent>,
         change_bg_music_event_writer: &mut EventWriter<ChangeBackgroundMusicEvent>,
@@ -162,8 +262,13 @@ impl Level {
         mob_segment_destroyed_event: &mut EventReader<MobSegmentDestroyedEvent>,
         play_sound_effect_event_writer: &mut EventWriter<PlaySoundEffectEvent>,
         mut player_spawn_params: ResMut<InputRestrictionsAtSpawn>,
+        timeline_params: &mut TimelineParams,
+        game_rng: &mut GameRngResource,
+        difficulty_multipliers: &DifficultyMultipliers,
+        player_health_percentages: &[f32],
     ) -> bool {
         self.level_time.tick(time.delta());
+        self.phase_time.tick(time.delta());
 
         if let Some(mut modified_current_phase) = self.current_phase.clone() {
             let phase_completed = match &mut modified_current_phase.phase_type {
@@ -171,21 +276,29 @@ impl Level {
                     phase_timer,
                     spawn_timer,
                     formation_pool,
-                    ..
+                    spawn_period_multiplier,
                 } => {
+                    let weight_context = FormationWeightContext {
+                        phase_progress: phase_timer.fraction(),
+                        level_idx,
+                    };
+
                     Self::tick_spawn_timer(
                         spawn_timer,
                         time,
                         spawn_formation_event_writer,
                         formations_res,
                         formation_pool.to_string(),
+                        &weight_context,
+                        spawn_period_multiplier.unwrap_or(1.0)
+                            * difficulty_multipliers.formation_period,
+                        game_rng.stream(RngStream::Formations),
                     );
 
                     Self::tick_phase_timer(phase_timer, time)
                 }
-                LevelPhaseType::Break { phase_timer, .. } => {
-                    Self::tick_phase_timer(phase_timer, time)
-                }
+                LevelPhaseType::Break { phase_timer, .. }
+                | LevelPhaseType::Shop { phase_timer } => Self::tick_phase_timer(phase_timer, time),
                 LevelPhaseType::Boss {
                     mob_type,
                     position,
@@ -209,11 +322,31 @@ impl Level {
                                 position: *position,
                                 rotation: Quat::default(),
                                 boss: true,
+                                parent: None,
                             });
                         }
                         false
                     }
                 }
+                LevelPhaseType::Endless(endless_phase) => endless_phase.tick(
+                    time,
+                    spawn_formation_event_writer,
+                    spawn_mob_event_writer,
+                    formations_res,
+                    difficulty_multipliers.formation_period,
+                    level_idx,
+                    game_rng.stream(RngStream::Formations),
+                ),
+                LevelPhaseType::Timeline(timeline_phase) => timeline_phase.tick(
+                    time,
+                    spawn_formation_event_writer,
+                    spawn_mob_event_writer,
+                    change_bg_music_event_writer,
+                    timeline_params,
+                    formations_res,
+                    level_idx,
+                    game_rng.stream(RngStream::Formations),
+                ),
                 LevelPhaseType::Tutorial {
                     tutorial_lesson, ..
                 } => {
@@ -229,6 +362,7 @@ impl Level {
                         mob_reached_bottom_event,
                         mob_segment_destroyed_event,
                         play_sound_effect_event_writer,
+                        game_rng.stream(RngStream::Tutorial),
                     );
                     if finished_tutorial_section {
                         *player_spawn_params = InputRestrictionsAtSpawn::default();
@@ -237,12 +371,25 @@ impl Level {
                 }
             };
 
+            // check the branches of the phase while the state it was completed in is available
+            let branch_to = if phase_completed {
+                let context = PhaseConditionContext {
+                    phase_time: self.phase_time.elapsed(),
+                    objective: self.objective.as_ref(),
+                    player_health_percentages,
+                };
+                choose_branch(&modified_current_phase.branches, &context)
+                    .map(|branch_to| branch_to.to_string())
+            } else {
+                None
+            };
+
             self.current_phase = Some(modified_current_phase);
 
             // this will short circuit and not call cycle_phase if !phase_completed
             if phase_completed {
                 info!("Phase completed");
-                if !self.cycle_phase(cycle_phase_event_writer) {
+                if !self.cycle_phase(branch_to.as_deref(), cycle_phase_event_writer) {
                     self.init_phase(change_bg_music_event_writer);
                 }
             }
@@ -259,23 +406,28 @@ impl Level {
         phase_timer.just_finished()
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn tick_spawn_timer(
         spawn_timer: &mut Timer,
         time: &Time,
         spawn_formation_event_writer: &mut EventWriter<SpawnFormationEvent>,
         formations_res: &FormationPoolsResource,
         formation_key: String,
+        weight_context: &FormationWeightContext,
+        period_multiplier: f32,
+        rng: &mut impl Rng,
     ) {
         spawn_timer.tick(time.delta());
 
         if spawn_timer.just_finished() {
-            if let Some(formation) = formations_res.get_random_formation(formation_key) {
-                spawn_formation_event_writer.send(SpawnFormationEvent {
-                    formation: formation.clone(),
-                });
-                spawn_timer.set_duration(Duration::from_secs_f32(formation.period));
+            if let Some(formation) =
+                formations_res.get_random_formation(formation_key, weight_context, rng)
+            {
+                let period = formation.period * period_multiplier;
+                spawn_formation_event_writer.send(SpawnFormationEvent { formation });
+                spawn_timer.set_duration(Duration::from_secs_f32(period));
                 spawn_timer.reset();
-                info!("Spawn timer duration reset to: {}", formation.period);
+                info!("Spawn timer duration reset to: {}", period);
             }
         }
     }
@@ -1,20 +1,29 @@
+use crate::run::endless::EndlessPhase;
+use crate::run::timeline::TimelinePhase;
 use crate::run::tutorial::TutorialLesson;
 use bevy::math::Vec2;
 use bevy::prelude::Timer;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use thetawave_interface::spawnable::MobType;
 
 /// Describes a distinct portion of the level
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum LevelPhaseType {
     FormationSpawn {
         phase_timer: Timer,
         spawn_timer: Timer,
         formation_pool: String,
+        /// Scales the period of every formation spawned in the phase
+        #[serde(default)]
+        spawn_period_multiplier: Option<f32>,
     },
     Break {
         phase_timer: Timer,
     },
+    /// A break where the players can spend their money in the shop
+    Shop {
+        phase_timer: Timer,
+    },
     Boss {
         mob_type: MobType,
         position: Vec2,
@@ -23,6 +32,10 @@ pub enum LevelPhaseType {
     Tutorial {
         tutorial_lesson: TutorialLesson,
     },
+    /// Waves of formations that get harder over time and never end
+    Endless(EndlessPhase),
+    /// Hand-authored entries that fire at set times
+    Timeline(TimelinePhase),
 }
 
 impl LevelPhaseType {
@@ -30,10 +43,22 @@ impl LevelPhaseType {
         match self {
             LevelPhaseType::FormationSpawn { .. } => "Formation Invasion".to_string(),
             LevelPhaseType::Break { .. } => "Break".to_string(),
+            LevelPhaseType::Shop { .. } => "Shop".to_string(),
             LevelPhaseType::Boss { mob_type, .. } => format!("Boss: {}", mob_type.get_name()),
             LevelPhaseType::Tutorial {
                 tutorial_lesson, ..
             } => format!("Tutorial: {}", tutorial_lesson.get_name()),
+            LevelPhaseType::Endless(endless_phase) => {
+                format!("Endless: Wave {}", endless_phase.get_wave_number())
+            }
+            LevelPhaseType::Timeline(_) => "Formation Invasion".to_string(),
         }
     }
 }
+
+/// A boss mob and where to spawn it
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BossSpawn {
+    pub mob_type: MobType,
+    pub position: Vec2,
+}
@@ -1,37 +1,89 @@
 //! Exposes a plugin and resources that deal with level progression and the player's progress
 //! towards "winning."
-use bevy::prelude::*;
+use bevy::{app::AppExit, prelude::*};
 
 use leafwing_input_manager::prelude::ActionState;
+use rand::{rngs::StdRng, SeedableRng};
 use ron::de::from_bytes;
-use serde::Deserialize;
-use std::collections::{HashMap, VecDeque};
-use thetawave_interface::input::PlayerAction;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+use thetawave_interface::input::{MenuAction, MenuExplorer, PlayerAction};
 use thetawave_interface::player::InputRestrictionsAtSpawn;
 use thetawave_interface::{
     audio::{ChangeBackgroundMusicEvent, PlaySoundEffectEvent, SoundEffectType},
+    game::daily_challenge::{DailyResultRecordedEvent, DailyResultsResource},
+    health::HealthComponent,
     objective::{DefenseInteraction, MobReachedBottomGateEvent, Objective},
-    player::{PlayerComponent, PlayersResource},
-    run::{CyclePhaseEvent, RunDefeatType, RunEndEvent, RunOutcomeType},
+    player::{PlayerComponent, PlayerInventoryComponent, PlayersResource},
+    run::{CyclePhaseEvent, IntroTextEvent, RunDefeatType, RunEndEvent, RunOutcomeType},
     spawnable::{MobDestroyedEvent, MobSegmentDestroyedEvent, SpawnMobEvent},
     states::{AppStates, GameStates},
 };
 
-use crate::{spawnable::BossesDestroyedEvent, GameUpdateSet};
+use thiserror::Error;
+
+use crate::{
+    data_reload::ReloadableDataAppExt,
+    game::{
+        scale_usize, DifficultyMultipliers, DifficultyParams, GameRngResource, RunModifierParams,
+    },
+    spawnable::{BossesDestroyedEvent, MobStatMultipliersResource},
+    GameUpdateSet,
+};
 
+mod daily;
+mod endless;
 mod formation;
+mod generator;
 mod level;
 pub(crate) mod level_phase;
+mod phase_branch;
+pub(crate) mod shop;
+mod timeline;
 pub(crate) mod tutorial;
+mod validation;
 
 use self::{
-    formation::{spawn_formation_system, FormationPoolsResource, SpawnFormationEvent},
+    daily::{
+        end_daily_challenge_system, record_daily_defeat_system, record_daily_victory_system,
+        start_daily_challenge_system, track_daily_challenge_system,
+    },
+    formation::{
+        clear_delayed_formation_spawns_system, spawn_formation_system,
+        DelayedFormationSpawnsResource, FormationPoolsResource, SpawnFormationEvent,
+    },
+    generator::RunGeneratorResource,
     level::{Level, PremadeLevelsResource},
+    level_phase::LevelPhaseType,
+    shop::{shop_input_system, update_shop_system, ShopDataResource},
+    timeline::TimelineParams,
 };
 
+pub(crate) use self::{
+    daily::{
+        daily_challenge_inactive, format_date, get_daily_run_modifiers, get_daily_seed, get_today,
+        DailyChallengeResource, StartDailyChallengeEvent,
+    },
+    level::BGMusicTransition,
+    shop::{close_shop_system, ShopResource},
+    validation::validate_data,
+};
+
+/// Key of the premade run that is played when no other run is selected
+const DEFAULT_RUN_KEY: &str = "test_run";
+
 /// Contains systems that deal with level progression and transitions of `AppStates`. This includes
 /// keeping track of when the run ends/the player loses.
-pub(super) struct RunPlugin;
+#[derive(Default)]
+pub(super) struct RunPlugin {
+    /// The run to play when a new run is initialized
+    pub selected_run: SelectedRunResource,
+    /// How many times the players can continue after a game over
+    pub continues: ContinuesResource,
+}
 
 impl Plugin for RunPlugin {
     fn build(&self, app: &mut App) {
@@ -41,27 +93,47 @@ impl Plugin for RunPlugin {
             ))
             .unwrap(),
         )
+        .insert_resource(premade_runs_from_data())
+        .insert_resource(premade_levels_from_data())
         .insert_resource(
-            from_bytes::<PremadeRunsResource>(include_bytes!("../../assets/data/premade_runs.ron"))
-                .unwrap(),
-        )
-        .insert_resource(
-            from_bytes::<PremadeLevelsResource>(include_bytes!(
-                "../../assets/data/premade_levels.ron"
+            from_bytes::<RunGeneratorResource>(include_bytes!(
+                "../../assets/data/run_generator.ron"
             ))
             .unwrap(),
         )
-        .insert_resource(CurrentRunProgressResource::default());
+        .insert_resource(
+            from_bytes::<ShopDataResource>(include_bytes!("../../assets/data/shop.ron")).unwrap(),
+        )
+        .insert_resource(ShopResource::default())
+        .insert_resource(CurrentRunProgressResource::default())
+        .insert_resource(DelayedFormationSpawnsResource::default())
+        .insert_resource(DailyChallengeResource::default())
+        .insert_resource(DailyResultsResource::default())
+        .insert_resource(self.selected_run.clone())
+        .insert_resource(self.continues)
+        .register_reloadable_data("formation_pools.ron", |res: FormationPoolsResource| res)
+        .register_reloadable_data("premade_levels.ron", |res: PremadeLevelsResource| res)
+        .register_reloadable_data("shop.ron", |res: ShopDataResource| res);
 
         app.add_event::<SpawnFormationEvent>()
             .add_event::<RunEndEvent>()
-            .add_event::<CyclePhaseEvent>();
+            .add_event::<CyclePhaseEvent>()
+            .add_event::<IntroTextEvent>()
+            .add_event::<StartDailyChallengeEvent>()
+            .add_event::<DailyResultRecordedEvent>();
 
         app.add_systems(OnEnter(AppStates::InitializeRun), init_run_system);
 
         app.add_systems(
             Update,
-            (tick_run_system, handle_objective_system, run_end_system)
+            (
+                tick_run_system,
+                handle_objective_system,
+                run_end_system,
+                scale_mob_stats_system,
+                track_daily_challenge_system,
+                (update_shop_system, shop_input_system).chain(),
+            )
                 .in_set(GameUpdateSet::Level)
                 .run_if(in_state(AppStates::Game))
                 .run_if(in_state(GameStates::Playing)),
@@ -75,21 +147,214 @@ impl Plugin for RunPlugin {
                 .run_if(in_state(GameStates::Playing)),
         );
 
-        // reset the run after exiting the end game screens and when entering the main menu
-        app.add_systems(OnExit(AppStates::GameOver), run_reset_system);
+        // spawnables of formations that are still delayed are dropped and the shop is closed when
+        // the game is left
+        app.add_systems(
+            OnExit(AppStates::Game),
+            (clear_delayed_formation_spawns_system, close_shop_system),
+        );
+
+        app.add_systems(
+            Update,
+            continue_run_system.run_if(in_state(AppStates::GameOver)),
+        );
+
+        // reset the run after exiting the end game screens and when entering the main menu. The
+        // run is kept when going from the game over screen back to the game to continue it.
+        app.add_systems(
+            OnTransition {
+                from: AppStates::GameOver,
+                to: AppStates::MainMenu,
+            },
+            (record_daily_defeat_system, run_reset_system).chain(),
+        );
         app.add_systems(OnExit(AppStates::Victory), run_reset_system);
-        app.add_systems(OnEnter(AppStates::MainMenu), run_reset_system);
+        app.add_systems(
+            OnEnter(AppStates::MainMenu),
+            (run_reset_system, end_daily_challenge_system),
+        );
+
+        // daily challenges are started from the daily leaderboard, and their results are recorded
+        // once the run can't be continued anymore
+        app.add_systems(
+            Update,
+            start_daily_challenge_system.run_if(in_state(AppStates::DailyLeaderboard)),
+        );
+        app.add_systems(OnEnter(AppStates::Victory), record_daily_victory_system);
+
+        // daily challenges aren't saved, so quitting one to the main menu or closing the game
+        // during one loses it. The state has already changed when exiting the game.
+        app.add_systems(
+            OnExit(AppStates::Game),
+            record_daily_defeat_system.run_if(in_state(AppStates::MainMenu)),
+        );
+        app.add_systems(
+            PostUpdate,
+            record_daily_defeat_system
+                .run_if(in_state(AppStates::Game).or_else(in_state(AppStates::GameOver)))
+                .run_if(on_event::<AppExit>()),
+        );
     }
 }
 
+fn premade_runs_from_data() -> PremadeRunsResource {
+    from_bytes::<PremadeRunsResource>(include_bytes!("../../assets/data/premade_runs.ron")).unwrap()
+}
+
+fn premade_levels_from_data() -> PremadeLevelsResource {
+    from_bytes::<PremadeLevelsResource>(include_bytes!("../../assets/data/premade_levels.ron"))
+        .unwrap()
+}
+
+/// Progress of the default premade run, in the second phase of its second level. Used to test
+/// systems outside of this module that keep track of the run.
+#[cfg(test)]
+pub(crate) fn test_run_progress() -> CurrentRunProgressResource {
+    let mut run_res = CurrentRunProgressResource {
+        tutorials_on: false,
+        ..default()
+    };
+    run_res
+        .generate_premade(
+            DEFAULT_RUN_KEY,
+            &premade_runs_from_data(),
+            &premade_levels_from_data(),
+        )
+        .unwrap();
+    run_res.cycle_level();
+    run_res.cycle_level();
+
+    if let Some(current_level) = &mut run_res.current_level {
+        for _ in 0..2 {
+            let next_phase = current_level.queued_phases.pop_front();
+            if let Some(completed_phase) =
+                std::mem::replace(&mut current_level.current_phase, next_phase)
+            {
+                current_level.completed_phases.push_back(completed_phase);
+            }
+        }
+    }
+
+    run_res
+}
+
+/// Errors that can occur when generating a run from the premade runs and levels
+#[derive(Error, Debug, PartialEq, Eq)]
+pub(super) enum OurRunGenerationError {
+    #[error("No premade run found with key '{0}'.")]
+    UnknownRun(String),
+    #[error("Premade run '{run_key}' references unknown level key '{level_key}'.")]
+    UnknownLevel { run_key: String, level_key: String },
+    #[error("Run generator references unknown formation pool '{0}'.")]
+    UnknownFormationPool(String),
+}
+
 #[derive(Resource, Deserialize)]
 pub(super) struct PremadeRunsResource {
     pub runs: HashMap<String, Vec<String>>,
 }
 
+impl PremadeRunsResource {
+    /// Get the level keys of a premade run
+    fn get_level_keys(&self, run_key: &str) -> Result<&Vec<String>, OurRunGenerationError> {
+        self.runs
+            .get(run_key)
+            .ok_or_else(|| OurRunGenerationError::UnknownRun(run_key.to_string()))
+    }
+
+    /// Human readable list of every premade run key along with the names of its levels
+    fn describe(&self, premade_levels_res: &PremadeLevelsResource) -> String {
+        let mut run_keys: Vec<&String> = self.runs.keys().collect();
+        run_keys.sort();
+
+        let mut description = "Available runs:".to_string();
+        for run_key in run_keys {
+            let level_names: Vec<&str> = self.runs[run_key]
+                .iter()
+                .map(|level_key| {
+                    premade_levels_res
+                        .levels_data
+                        .get(level_key)
+                        .map_or(level_key.as_str(), |level_data| level_data.name.as_str())
+                })
+                .collect();
+            description.push_str(&format!("\n  {run_key}: {}", level_names.join(", ")));
+        }
+
+        description
+    }
+}
+
+/// Human readable list of the runs in 'premade_runs.ron'. Used by the `--list-runs` option.
+pub(super) fn premade_runs_listing() -> String {
+    premade_runs_from_data().describe(&premade_levels_from_data())
+}
+
+/// Check that a run key can be used to generate a run, returning the list of available runs in
+/// the error message if it cannot.
+pub(super) fn validate_premade_run_key(run_key: &str) -> Result<(), String> {
+    let premade_runs_res = premade_runs_from_data();
+    let premade_levels_res = premade_levels_from_data();
+
+    CurrentRunProgressResource::default()
+        .generate_premade(run_key, &premade_runs_res, &premade_levels_res)
+        .map_err(|err| format!("{err}\n{}", premade_runs_res.describe(&premade_levels_res)))
+}
+
+/// The run that will be generated when a run is initialized. Generally set at app startup and not
+/// mutated while the game is running.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub enum SelectedRunResource {
+    /// Key of a run in 'premade_runs.ron'
+    Premade(String),
+    /// Seed used to procedurally generate a run with the `RunGeneratorResource`
+    Generated(u64),
+}
+
+impl Default for SelectedRunResource {
+    fn default() -> Self {
+        SelectedRunResource::Premade(DEFAULT_RUN_KEY.to_string())
+    }
+}
+
+/// Limits how many times the players can restart the level they failed from the game over screen
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContinuesResource {
+    /// Unlimited if None. `Some(0)` disables continuing.
+    pub max_continues: Option<usize>,
+}
+
+impl ContinuesResource {
+    /// Continues left in a run after `continues_used`, or None if they are unlimited
+    pub fn get_remaining(&self, continues_used: usize) -> Option<usize> {
+        self.max_continues
+            .map(|max_continues| max_continues.saturating_sub(continues_used))
+    }
+
+    pub fn can_continue(&self, continues_used: usize) -> bool {
+        self.get_remaining(continues_used) != Some(0)
+    }
+}
+
+/// Get a level from the premade levels resource
+fn get_premade_level(
+    premade_levels_res: &PremadeLevelsResource,
+    run_key: &str,
+    level_key: &str,
+) -> Result<Level, OurRunGenerationError> {
+    premade_levels_res
+        .levels_data
+        .get(level_key)
+        .map(Level::from)
+        .ok_or_else(|| OurRunGenerationError::UnknownLevel {
+            run_key: run_key.to_string(),
+            level_key: level_key.to_string(),
+        })
+}
+
 /// The most up to date information on how close the player is to winning. This also keeps the
 /// state required to transition to new sections of the level.
-#[derive(Resource, Debug)]
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
 pub struct CurrentRunProgressResource {
     /// List of string level keys that are matched to values in the levelsresource
     pub queued_levels: VecDeque<Level>,
@@ -98,6 +363,8 @@ pub struct CurrentRunProgressResource {
     pub current_level: Option<Level>,
     /// If true will append tutorial level to beginning of the run
     pub tutorials_on: bool,
+    /// Number of times the current level was restarted after a game over
+    pub continues_used: usize,
 }
 
 impl Default for CurrentRunProgressResource {
@@ -107,6 +374,7 @@ impl Default for CurrentRunProgressResource {
             completed_levels: VecDeque::new(),
             current_level: None,
             tutorials_on: true,
+            continues_used: 0,
         }
     }
 }
@@ -115,30 +383,69 @@ impl CurrentRunProgressResource {
     /// Generate a premade level using a String run key
     fn generate_premade(
         &mut self,
-        run_key: String,
+        run_key: &str,
         premade_runs_res: &PremadeRunsResource,
         premade_levels_res: &PremadeLevelsResource,
-    ) {
-        // get the level keys from the premade runs resource
-        let level_keys = premade_runs_res.runs.get(&run_key).unwrap();
-
-        // get levels from the levels resource
-        let mut levels: VecDeque<Level> = level_keys
+    ) -> Result<(), OurRunGenerationError> {
+        // get levels from the levels resource using the level keys of the run
+        let mut levels = premade_runs_res
+            .get_level_keys(run_key)?
             .iter()
-            .map(|key| Level::from(premade_levels_res.levels_data.get(key).unwrap()))
-            .collect();
+            .map(|level_key| get_premade_level(premade_levels_res, run_key, level_key))
+            .collect::<Result<VecDeque<Level>, OurRunGenerationError>>()?;
 
         // push a tutorial level to be the first level played
         if self.tutorials_on {
-            levels.push_front(Level::from(
-                premade_levels_res.levels_data.get("tutorial").unwrap(),
-            ));
+            levels.push_front(get_premade_level(premade_levels_res, run_key, "tutorial")?);
+        }
+
+        // set levels in the run resource
+        self.queued_levels = levels;
+
+        info!("Generated premade run '{}'", run_key);
+
+        Ok(())
+    }
+
+    /// Procedurally generate the levels of a run from a seed
+    fn generate_procedural(
+        &mut self,
+        seed: u64,
+        run_generator_res: &RunGeneratorResource,
+        formations_res: &FormationPoolsResource,
+        premade_levels_res: &PremadeLevelsResource,
+    ) -> Result<(), OurRunGenerationError> {
+        let mut levels =
+            run_generator_res.generate_levels(&mut StdRng::seed_from_u64(seed), formations_res)?;
+
+        // push a tutorial level to be the first level played
+        if self.tutorials_on {
+            levels.push_front(get_premade_level(
+                premade_levels_res,
+                "generated",
+                "tutorial",
+            )?);
         }
 
         // set levels in the run resource
         self.queued_levels = levels;
 
-        info!("Generated premade level");
+        info!("Generated run from seed {}", seed);
+
+        Ok(())
+    }
+
+    /// The wave reached and time survived if the current level is in an endless phase
+    pub fn get_endless_progress(&self) -> Option<(usize, Duration)> {
+        let current_level = self.current_level.as_ref()?;
+
+        match &current_level.current_phase.as_ref()?.phase_type {
+            LevelPhaseType::Endless(endless_phase) => Some((
+                endless_phase.get_wave_number(),
+                current_level.level_time.elapsed(),
+            )),
+            _ => None,
+        }
     }
 
     fn cycle_level(&mut self) {
@@ -160,7 +467,7 @@ impl CurrentRunProgressResource {
         cycle_phase_event_writer: &mut EventWriter<CyclePhaseEvent>,
     ) {
         if let Some(current_level) = &mut self.current_level {
-            let level_completed = current_level.cycle_phase(cycle_phase_event_writer);
+            let level_completed = current_level.cycle_phase(None, cycle_phase_event_writer);
 
             if !level_completed {
                 current_level.init_phase(change_bg_music_event_writer);
@@ -170,6 +477,23 @@ impl CurrentRunProgressResource {
         }
     }
 
+    /// Restart the current level from its first phase, keeping the completed levels
+    fn continue_current_level(
+        &mut self,
+        change_bg_music_event_writer: &mut EventWriter<ChangeBackgroundMusicEvent>,
+        cycle_phase_event_writer: &mut EventWriter<CyclePhaseEvent>,
+    ) {
+        if let Some(current_level) = &mut self.current_level {
+            current_level.restart();
+        }
+        self.continues_used += 1;
+
+        self.init_current_level(change_bg_music_event_writer, cycle_phase_event_writer);
+
+        info!("Continued run ({} continues used)", self.continues_used);
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn tick(
         &mut self,
         time: &Time,
@@ -186,14 +510,22 @@ impl CurrentRunProgressResource {
         mob_segment_destroyed_event: &mut EventReader<MobSegmentDestroyedEvent>,
         play_sound_effect_event_writer: &mut EventWriter<PlaySoundEffectEvent>,
         player_spawn_params: ResMut<InputRestrictionsAtSpawn>,
+        timeline_params: &mut TimelineParams,
+        game_rng: &mut GameRngResource,
+        difficulty_multipliers: &DifficultyMultipliers,
+        player_health_percentages: &[f32],
     ) {
         if let Some(current_level) = &mut self.current_level {
             // cycle level when done with all phases
+            // the current level isn't in the completed levels yet, so this is its index
+            let level_idx = self.completed_levels.len();
+
             if current_level.tick(
                 time,
                 player_query,
                 spawn_formation_event_writer,
                 formations_res,
+                level_idx,
                 spawn_mob_event_writer,
                 bosses_destroyed_event_reader,
                 change_bg_music_event_writer,
@@ -203,6 +535,10 @@ impl CurrentRunProgressResource {
                 mob_segment_destroyed_event,
                 play_sound_effect_event_writer,
                 player_spawn_params,
+                timeline_params,
+                game_rng,
+                difficulty_multipliers,
+                player_health_percentages,
             ) {
                 self.cycle_level();
                 self.init_current_level(change_bg_music_event_writer, cycle_phase_event_writer);
@@ -215,11 +551,17 @@ impl CurrentRunProgressResource {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn init_run_system(
     mut run_res: ResMut<CurrentRunProgressResource>,
     players: Res<PlayersResource>,
+    selected_run: Res<SelectedRunResource>,
+    daily_challenge: Res<DailyChallengeResource>,
+    run_modifiers: RunModifierParams,
     premade_runs_res: Res<PremadeRunsResource>,
     premade_levels_res: Res<PremadeLevelsResource>,
+    run_generator_res: Res<RunGeneratorResource>,
+    formations_res: Res<FormationPoolsResource>,
     mut next_app_state: ResMut<NextState<AppStates>>,
     mut change_bg_music_event_writer: EventWriter<ChangeBackgroundMusicEvent>,
     mut cycle_phase_event_writer: EventWriter<CyclePhaseEvent>,
@@ -227,15 +569,44 @@ fn init_run_system(
     // Enable tutorials if and only if:
     // - It was specifically toggled on when the user was setting up the run
     // - We have exactly 1 player. TODO: Maybe enhance the tutorial to also work for many players.
+    // - This is not a daily challenge, which should be the same run for everyone
     run_res.tutorials_on = run_res.tutorials_on
-        && ((players.player_data.len() <= 1) || players.player_data[1].is_none());
+        && ((players.player_data.len() <= 1) || players.player_data[1].is_none())
+        && !daily_challenge.is_active();
     info!("Tutorials are on: {}", run_res.tutorials_on);
-    // generate the run
-    run_res.generate_premade(
-        "test_run".to_string(),
-        &premade_runs_res,
-        &premade_levels_res,
-    );
+    // generate the run, going back to the main menu if the selected run can't be generated
+    let generated = match (&daily_challenge.challenge, &*selected_run) {
+        // daily challenges are generated from the date instead of the selected run
+        (Some(challenge), _) => run_res.generate_procedural(
+            challenge.seed,
+            &run_generator_res,
+            &formations_res,
+            &premade_levels_res,
+        ),
+        (None, SelectedRunResource::Premade(run_key)) => {
+            run_res.generate_premade(run_key, &premade_runs_res, &premade_levels_res)
+        }
+        (None, SelectedRunResource::Generated(seed)) => run_res.generate_procedural(
+            *seed,
+            &run_generator_res,
+            &formations_res,
+            &premade_levels_res,
+        ),
+    };
+    if let Err(err) = generated {
+        error!(
+            "Failed to generate run: {err}\n{}",
+            premade_runs_res.describe(&premade_levels_res)
+        );
+        next_app_state.set(AppStates::MainMenu);
+        return;
+    }
+
+    // apply run modifiers that change how levels start
+    let starting_defense = run_modifiers.get_effects().starting_defense;
+    for level in run_res.queued_levels.iter_mut() {
+        level.scale_starting_defense(starting_defense);
+    }
 
     // cycle to set the current level to the first level
     run_res.cycle_level();
@@ -269,7 +640,19 @@ fn tick_run_system(
     mut mob_segment_destroyed_event_reader: EventReader<MobSegmentDestroyedEvent>,
     mut play_sound_effect_event_writer: EventWriter<PlaySoundEffectEvent>,
     player_spawn_params: ResMut<InputRestrictionsAtSpawn>,
+    // grouped to stay within bevy's limit on the number of system parameters
+    (mut timeline_params, mut game_rng, difficulty, player_health_query): (
+        TimelineParams,
+        ResMut<GameRngResource>,
+        DifficultyParams,
+        Query<&HealthComponent, With<PlayerComponent>>,
+    ),
 ) {
+    let player_health_percentages: Vec<f32> = player_health_query
+        .iter()
+        .map(|health| health.get_health_percentage())
+        .collect();
+
     run_res.tick(
         &time,
         &player_query,
@@ -285,51 +668,132 @@ fn tick_run_system(
         &mut mob_segment_destroyed_event_reader,
         &mut play_sound_effect_event_writer,
         player_spawn_params,
+        &mut timeline_params,
+        &mut game_rng,
+        &difficulty.get_multipliers(),
+        &player_health_percentages,
     );
 }
 
+/// Updates the objective of the current level, ending the run if the objective is failed and
+/// moving on to the next level if the objective is completed
+#[allow(clippy::too_many_arguments)]
 fn handle_objective_system(
     mut run_res: ResMut<CurrentRunProgressResource>,
+    time: Res<Time>,
     mut bottom_gate_event: EventReader<MobReachedBottomGateEvent>,
+    mut mob_destroyed_event: EventReader<MobDestroyedEvent>,
+    player_inventory_query: Query<&PlayerInventoryComponent>,
     mut run_end_event: EventWriter<RunEndEvent>,
     mut sound_effect_event_writer: EventWriter<PlaySoundEffectEvent>,
+    mut change_bg_music_event_writer: EventWriter<ChangeBackgroundMusicEvent>,
+    mut cycle_phase_event_writer: EventWriter<CyclePhaseEvent>,
+    difficulty: DifficultyParams,
 ) {
+    let mut objective_completed = false;
+    let difficulty_multipliers = difficulty.get_multipliers();
+
+    // read the events every frame whatever the objective is, so that they aren't counted by the
+    // objective of the next level
+    let bottom_gate_events: Vec<&MobReachedBottomGateEvent> = bottom_gate_event.read().collect();
+    let mob_destroyed_events: Vec<&MobDestroyedEvent> = mob_destroyed_event.read().collect();
+
     if let Some(current_level) = &mut run_res.current_level {
         if let Some(objective) = &mut current_level.objective {
             match objective {
                 Objective::Defense(defense_data) => {
-                    for event in bottom_gate_event.read() {
+                    for event in bottom_gate_events.iter() {
                         match event.defense_interaction {
-                            DefenseInteraction::Heal(value) => {
+                            Some(DefenseInteraction::Heal(value)) => {
                                 // heal defense objective
-                                defense_data.gain_defense(value);
+                                defense_data.gain_defense(scale_usize(
+                                    value,
+                                    difficulty_multipliers.defense_heal,
+                                ));
 
                                 // play heal sound effect
                                 sound_effect_event_writer.send(PlaySoundEffectEvent {
                                     sound_effect_type: SoundEffectType::DefenseHeal,
                                 });
                             }
-                            DefenseInteraction::Damage(value) => {
+                            Some(DefenseInteraction::Damage(value)) => {
                                 // damage defense objective
-                                defense_data.take_damage(value);
+                                defense_data.take_damage(scale_usize(
+                                    value,
+                                    difficulty_multipliers.defense_damage,
+                                ));
 
                                 //play damage sound effect
                                 sound_effect_event_writer.send(PlaySoundEffectEvent {
                                     sound_effect_type: SoundEffectType::DefenseDamage,
                                 });
                             }
+                            None => {}
                         };
                     }
+                }
+                Objective::Escort(escort_data) => {
+                    // escorted haulers are delivered when they reach the bottom of the arena
+                    for event in bottom_gate_events.iter() {
+                        if let Some(mob_type) = &event.mob_type {
+                            if escort_data.is_escorted(mob_type) {
+                                escort_data.delivered += 1;
+                            }
+                        }
+                    }
 
-                    if defense_data.is_failed() {
-                        run_end_event.send(RunEndEvent {
-                            outcome: RunOutcomeType::Defeat(RunDefeatType::DefenseDestroyed),
-                        });
+                    for event in mob_destroyed_events.iter() {
+                        if escort_data.is_escorted(&event.mob_type) {
+                            escort_data.lost += 1;
+                        }
                     }
                 }
+                Objective::Survive(survive_data) => {
+                    survive_data.elapsed += time.delta_seconds();
+                }
+                Objective::Destroy(destroy_data) => {
+                    destroy_data.destroyed += mob_destroyed_events
+                        .iter()
+                        .filter(|event| event.mob_type == destroy_data.mob_type)
+                        .count();
+                    destroy_data.time_limit.elapsed += time.delta_seconds();
+                }
+                Objective::Collect(collect_data) => {
+                    // only count money gained since the level started, spending it doesn't undo
+                    // the progress
+                    let held_money: usize = player_inventory_query
+                        .iter()
+                        .map(|player_inventory| player_inventory.money)
+                        .sum();
+                    let previous_held_money = collect_data.held_money.unwrap_or(held_money);
+                    collect_data.collected += held_money.saturating_sub(previous_held_money);
+                    collect_data.held_money = Some(held_money);
+                    collect_data.time_limit.elapsed += time.delta_seconds();
+                }
+            }
+
+            if let Some(defeat_type) = objective.get_defeat_type() {
+                run_end_event.send(RunEndEvent {
+                    outcome: RunOutcomeType::Defeat(defeat_type),
+                });
+            } else if objective.is_completed() {
+                objective_completed = true;
             }
         }
     }
+
+    // completing the objective completes the level
+    if objective_completed {
+        info!("Objective completed");
+        sound_effect_event_writer.send(PlaySoundEffectEvent {
+            sound_effect_type: SoundEffectType::ObjectiveCompleted,
+        });
+        run_res.cycle_level();
+        run_res.init_current_level(
+            &mut change_bg_music_event_writer,
+            &mut cycle_phase_event_writer,
+        );
+    }
 }
 
 fn run_end_system(
@@ -347,39 +811,112 @@ fn run_end_system(
                 match defeat_type {
                     RunDefeatType::PlayersDestroyed => info!("Players destroyed"),
                     RunDefeatType::DefenseDestroyed => info!("Defense objective failed"),
+                    RunDefeatType::EscortDestroyed => info!("Escort objective failed"),
+                    RunDefeatType::ObjectiveTimeExpired => info!("Objective time expired"),
                 };
             }
         }
     }
 }
 
+/// Apply the selected difficulty and run modifiers to newly spawned mobs, and make them tougher as
+/// the waves of an endless phase go on
+fn scale_mob_stats_system(
+    run_res: Res<CurrentRunProgressResource>,
+    difficulty: DifficultyParams,
+    run_modifiers: RunModifierParams,
+    mut mob_stat_multipliers: ResMut<MobStatMultipliersResource>,
+) {
+    let difficulty_multipliers = difficulty.get_multipliers();
+
+    let endless_health = match run_res
+        .current_level
+        .as_ref()
+        .and_then(|level| level.current_phase.as_ref())
+        .map(|phase| &phase.phase_type)
+    {
+        Some(LevelPhaseType::Endless(endless_phase)) => endless_phase.get_health_multiplier(),
+        _ => 1.0,
+    };
+
+    mob_stat_multipliers.set_if_neq(MobStatMultipliersResource {
+        health: difficulty_multipliers.mob_health * endless_health,
+        collision_damage: difficulty_multipliers.mob_collision_damage,
+        projectile_damage: difficulty_multipliers.mob_projectile_damage,
+        projectile_speed: run_modifiers.get_effects().mob_projectile_speed,
+    });
+}
+
+/// Go back to the level that was failed when the players choose to continue on the game over screen
+fn continue_run_system(
+    menu_input_query: Query<&ActionState<MenuAction>, With<MenuExplorer>>,
+    continues: Res<ContinuesResource>,
+    mut run_res: ResMut<CurrentRunProgressResource>,
+    mut next_app_state: ResMut<NextState<AppStates>>,
+    mut change_bg_music_event_writer: EventWriter<ChangeBackgroundMusicEvent>,
+    mut cycle_phase_event_writer: EventWriter<CyclePhaseEvent>,
+) {
+    let Ok(action_state) = menu_input_query.get_single() else {
+        return;
+    };
+
+    if action_state.just_released(&MenuAction::Confirm)
+        && continues.can_continue(run_res.continues_used)
+    {
+        run_res.continue_current_level(
+            &mut change_bg_music_event_writer,
+            &mut cycle_phase_event_writer,
+        );
+        next_app_state.set(AppStates::Game);
+    }
+}
+
 /// clear/reset various globals to the defaults to prepare for playing another run/game
 fn run_reset_system(
     mut run_resource: ResMut<CurrentRunProgressResource>,
     mut spawn_restrictions: ResMut<InputRestrictionsAtSpawn>,
+    mut mob_stat_multipliers: ResMut<MobStatMultipliersResource>,
 ) {
     *run_resource = CurrentRunProgressResource::default();
     *spawn_restrictions = InputRestrictionsAtSpawn::default();
+    *mob_stat_multipliers = MobStatMultipliersResource::default();
 }
 
 #[cfg(test)]
 mod test {
+    use crate::game::{
+        DifficultiesResource, GameParametersResource, GameRngResource, RunModifiersResource,
+    };
     use crate::run::{RunPlugin, SpawnFormationEvent};
-    use crate::spawnable::{BossesDestroyedEvent, SpawnConsumableEvent};
+    use crate::spawnable::{
+        BossesDestroyedEvent, FireWeaponEvent, MobBehaviorSequenceEvent,
+        MobStatMultipliersResource, SpawnConsumableEvent, SpawnEffectEvent,
+    };
     use bevy::app::App;
     use bevy::log::{Level, LogPlugin};
-    use bevy::prelude::{default, NextState, State};
+    use bevy::prelude::{default, Entity, NextState, State};
     use bevy::MinimalPlugins;
+    use leafwing_input_manager::prelude::ActionState;
     use rstest::rstest;
     use thetawave_interface::audio::{ChangeBackgroundMusicEvent, PlaySoundEffectEvent};
-    use thetawave_interface::objective::{DefenseInteraction, MobReachedBottomGateEvent};
+    use thetawave_interface::game::difficulty::{DifficultyLevel, SelectedDifficultyResource};
+    use thetawave_interface::game::run_modifiers::SelectedRunModifiersResource;
+    use thetawave_interface::input::{MenuAction, MenuExplorer};
+    use thetawave_interface::objective::{
+        DefenseData, DefenseInteraction, EscortData, MobReachedBottomGateEvent, Objective,
+    };
     use thetawave_interface::player::{InputRestrictionsAtSpawn, PlayersResource};
     use thetawave_interface::spawnable::{
-        MobDestroyedEvent, MobSegmentDestroyedEvent, SpawnMobEvent,
+        AllyMobType, MobDestroyedEvent, MobSegmentDestroyedEvent, MobType, SpawnItemEvent,
+        SpawnMobEvent, SpawnMobSegmentEvent,
     };
     use thetawave_interface::states::{AppStates, GameStates};
 
-    use super::CurrentRunProgressResource;
+    use super::{
+        level::Level, premade_levels_from_data, premade_runs_from_data, ContinuesResource,
+        CurrentRunProgressResource, FormationPoolsResource, OurRunGenerationError,
+        RunGeneratorResource, SelectedRunResource,
+    };
 
     fn _minimal_app_for_run_progression_defend_gate_objective() -> App {
         let mut app = App::new();
@@ -398,11 +935,28 @@ mod test {
             .add_event::<BossesDestroyedEvent>()
             .add_event::<SpawnFormationEvent>()
             .add_event::<SpawnMobEvent>()
+            .add_event::<SpawnMobSegmentEvent>()
+            .add_event::<SpawnItemEvent>()
+            .add_event::<SpawnEffectEvent>()
+            .add_event::<MobBehaviorSequenceEvent>()
+            .add_event::<FireWeaponEvent>()
             .add_event::<MobDestroyedEvent>()
             .add_event::<MobSegmentDestroyedEvent>()
             .insert_resource(PlayersResource::default())
             .insert_resource(InputRestrictionsAtSpawn::default())
-            .add_plugins(RunPlugin);
+            .insert_resource(GameRngResource::new(0))
+            .insert_resource(
+                ron::de::from_bytes::<GameParametersResource>(include_bytes!(
+                    "../../assets/data/game_parameters.ron"
+                ))
+                .unwrap(),
+            )
+            .insert_resource(MobStatMultipliersResource::default())
+            .insert_resource(DifficultiesResource::default())
+            .insert_resource(SelectedDifficultyResource::default())
+            .insert_resource(RunModifiersResource::default())
+            .insert_resource(SelectedRunModifiersResource::default())
+            .add_plugins(RunPlugin::default());
         app.world
             .get_resource_mut::<CurrentRunProgressResource>()
             .unwrap()
@@ -437,7 +991,47 @@ mod test {
         );
         // This is the main part of the test
         app.world.send_event(MobReachedBottomGateEvent {
-            defense_interaction: DefenseInteraction::Damage(damage_amount),
+            defense_interaction: Some(DefenseInteraction::Damage(damage_amount)),
+            mob_type: None,
+            mob_segment_type: None,
+        });
+        app.update();
+        app.update();
+        app.update();
+        assert_eq!(
+            &want_end_state,
+            app.world.get_resource::<State<AppStates>>().unwrap().get()
+        );
+    }
+
+    #[rstest]
+    #[case::nightmare_doubles_gate_damage(DifficultyLevel::Nightmare, 60, AppStates::GameOver)]
+    #[case::easy_halves_gate_damage(DifficultyLevel::Easy, 150, AppStates::Game)]
+    fn test_difficulty_scales_gate_damage(
+        #[case] difficulty: DifficultyLevel,
+        #[case] damage_amount: usize,
+        #[case] want_end_state: AppStates,
+    ) {
+        let mut app = _minimal_app_for_run_progression_defend_gate_objective();
+        app.insert_resource(
+            ron::de::from_bytes::<DifficultiesResource>(include_bytes!(
+                "../../assets/data/difficulties.ron"
+            ))
+            .unwrap(),
+        )
+        .insert_resource(SelectedDifficultyResource { level: difficulty });
+        app.world
+            .get_resource_mut::<NextState<AppStates>>()
+            .unwrap()
+            .set(AppStates::InitializeRun);
+        app.world
+            .get_resource_mut::<NextState<GameStates>>()
+            .unwrap()
+            .set(GameStates::Playing);
+        app.update();
+        app.update();
+        app.world.send_event(MobReachedBottomGateEvent {
+            defense_interaction: Some(DefenseInteraction::Damage(damage_amount)),
             mob_type: None,
             mob_segment_type: None,
         });
@@ -449,4 +1043,287 @@ mod test {
             app.world.get_resource::<State<AppStates>>().unwrap().get()
         );
     }
+
+    #[rstest]
+    #[case::weak_gate_fails_to_smaller_damage(60, AppStates::GameOver)]
+    #[case::weak_gate_survives_small_damage(40, AppStates::Game)]
+    fn test_weak_gate_modifier_halves_starting_defense(
+        #[case] damage_amount: usize,
+        #[case] want_end_state: AppStates,
+    ) {
+        let mut app = _minimal_app_for_run_progression_defend_gate_objective();
+        app.insert_resource(
+            ron::de::from_bytes::<RunModifiersResource>(include_bytes!(
+                "../../assets/data/run_modifiers.ron"
+            ))
+            .unwrap(),
+        )
+        .insert_resource(SelectedRunModifiersResource {
+            modifiers: vec!["WeakGate".to_string()],
+        });
+        app.world
+            .get_resource_mut::<NextState<AppStates>>()
+            .unwrap()
+            .set(AppStates::InitializeRun);
+        app.world
+            .get_resource_mut::<NextState<GameStates>>()
+            .unwrap()
+            .set(GameStates::Playing);
+        app.update();
+        app.update();
+        app.world.send_event(MobReachedBottomGateEvent {
+            defense_interaction: Some(DefenseInteraction::Damage(damage_amount)),
+            mob_type: None,
+            mob_segment_type: None,
+        });
+        app.update();
+        app.update();
+        app.update();
+        assert_eq!(
+            &want_end_state,
+            app.world.get_resource::<State<AppStates>>().unwrap().get()
+        );
+    }
+
+    #[rstest]
+    #[case::unlimited_continues(None, AppStates::Game)]
+    #[case::continues_disabled(Some(0), AppStates::GameOver)]
+    fn test_continue_restarts_failed_level(
+        #[case] max_continues: Option<usize>,
+        #[case] want_end_state: AppStates,
+    ) {
+        let mut app = _minimal_app_for_run_progression_defend_gate_objective();
+        app.insert_resource(ContinuesResource { max_continues });
+        let menu_explorer = app
+            .world
+            .spawn((MenuExplorer, ActionState::<MenuAction>::default()))
+            .id();
+        app.world
+            .get_resource_mut::<NextState<AppStates>>()
+            .unwrap()
+            .set(AppStates::InitializeRun);
+        app.world
+            .get_resource_mut::<NextState<GameStates>>()
+            .unwrap()
+            .set(GameStates::Playing);
+        app.update();
+        app.update();
+        app.world.send_event(MobReachedBottomGateEvent {
+            defense_interaction: Some(DefenseInteraction::Damage(101)),
+            mob_type: None,
+            mob_segment_type: None,
+        });
+        app.update();
+        app.update();
+        app.update();
+        assert_eq!(
+            &AppStates::GameOver,
+            app.world.get_resource::<State<AppStates>>().unwrap().get()
+        );
+
+        // This is the main part of the test
+        let mut action_state = app
+            .world
+            .get_mut::<ActionState<MenuAction>>(menu_explorer)
+            .unwrap();
+        action_state.press(&MenuAction::Confirm);
+        action_state.release(&MenuAction::Confirm);
+        app.update();
+        app.update();
+        assert_eq!(
+            &want_end_state,
+            app.world.get_resource::<State<AppStates>>().unwrap().get()
+        );
+
+        if want_end_state == AppStates::Game {
+            let run_res = app.world.resource::<CurrentRunProgressResource>();
+            assert_eq!(run_res.continues_used, 1);
+            // the gate is repaired when the level is restarted
+            assert!(matches!(
+                run_res.current_level.as_ref().unwrap().objective,
+                Some(Objective::Defense(DefenseData {
+                    defense: 100,
+                    max_defense: 100
+                }))
+            ));
+        }
+    }
+
+    #[rstest]
+    #[case::losing_too_many_haulers_triggers_game_over(2, AppStates::GameOver)]
+    #[case::losing_allowed_haulers_keeps_game_going(1, AppStates::Game)]
+    fn test_escort_losses_transition_app_state(
+        #[case] haulers_lost: usize,
+        #[case] want_end_state: AppStates,
+    ) {
+        // The first level of the objectives run allows 1 hauler to be lost
+        let mut app = _minimal_app_for_run_progression_defend_gate_objective();
+        app.insert_resource(SelectedRunResource::Premade("objectives".to_string()));
+        app.world
+            .get_resource_mut::<NextState<AppStates>>()
+            .unwrap()
+            .set(AppStates::InitializeRun);
+        app.world
+            .get_resource_mut::<NextState<GameStates>>()
+            .unwrap()
+            .set(GameStates::Playing);
+        app.update();
+        app.update();
+        for _ in 0..haulers_lost {
+            app.world.send_event(MobDestroyedEvent {
+                mob_type: MobType::Ally(AllyMobType::Hauler2),
+                entity: Entity::PLACEHOLDER,
+                is_boss: false,
+            });
+        }
+        app.update();
+        app.update();
+        app.update();
+        assert_eq!(
+            &want_end_state,
+            app.world.get_resource::<State<AppStates>>().unwrap().get()
+        );
+    }
+
+    #[test]
+    fn test_escorted_haulers_reaching_the_gate_are_delivered() {
+        // The first level of the objectives run is an escort of Hauler2 and Hauler3
+        let mut app = _minimal_app_for_run_progression_defend_gate_objective();
+        app.insert_resource(SelectedRunResource::Premade("objectives".to_string()));
+        app.world
+            .get_resource_mut::<NextState<AppStates>>()
+            .unwrap()
+            .set(AppStates::InitializeRun);
+        app.world
+            .get_resource_mut::<NextState<GameStates>>()
+            .unwrap()
+            .set(GameStates::Playing);
+        app.update();
+        app.update();
+        for mob_type in [
+            MobType::Ally(AllyMobType::Hauler2),
+            MobType::Ally(AllyMobType::Hauler3),
+        ] {
+            // haulers have no defense interaction, they are still delivered at the gate
+            app.world.send_event(MobReachedBottomGateEvent {
+                defense_interaction: None,
+                mob_type: Some(mob_type),
+                mob_segment_type: None,
+            });
+        }
+        app.update();
+        app.update();
+
+        let run_res = app.world.resource::<CurrentRunProgressResource>();
+        assert!(matches!(
+            run_res
+                .current_level
+                .as_ref()
+                .and_then(|level| level.objective.as_ref()),
+            Some(Objective::Escort(EscortData {
+                delivered: 2,
+                lost: 0,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_run_progress_resumes_from_start_of_current_phase() {
+        let mut app = _minimal_app_for_run_progression_defend_gate_objective();
+        app.world
+            .get_resource_mut::<NextState<AppStates>>()
+            .unwrap()
+            .set(AppStates::InitializeRun);
+        app.world
+            .get_resource_mut::<NextState<GameStates>>()
+            .unwrap()
+            .set(GameStates::Playing);
+        app.update();
+        app.update();
+        app.world.send_event(MobReachedBottomGateEvent {
+            defense_interaction: Some(DefenseInteraction::Damage(10)),
+            mob_type: None,
+            mob_segment_type: None,
+        });
+        for _ in 0..5 {
+            app.update();
+        }
+
+        // This is the main part of the test
+        let mut run_res = app.world.resource::<CurrentRunProgressResource>().clone();
+        run_res
+            .current_level
+            .as_mut()
+            .unwrap()
+            .restart_current_phase();
+        let resumed_run_res = ron::de::from_str::<CurrentRunProgressResource>(
+            &ron::ser::to_string(&run_res).unwrap(),
+        )
+        .unwrap();
+
+        let resumed_level = resumed_run_res.current_level.unwrap();
+        let first_level_key = &premade_runs_from_data().runs["test_run"][0];
+        let first_phase = Level::from(&premade_levels_from_data().levels_data[first_level_key])
+            .queued_phases
+            .pop_front();
+        assert_eq!(
+            format!("{:?}", resumed_level.current_phase),
+            format!("{:?}", first_phase)
+        );
+        // the objective keeps the progress made before the run was saved
+        assert!(matches!(
+            resumed_level.objective,
+            Some(Objective::Defense(DefenseData { defense: 90, .. }))
+        ));
+        assert_eq!(
+            resumed_run_res.queued_levels.len(),
+            run_res.queued_levels.len()
+        );
+    }
+
+    #[test]
+    fn test_generate_premade_unknown_run_key_is_an_error() {
+        let mut run_res = CurrentRunProgressResource::default();
+        assert_eq!(
+            run_res.generate_premade(
+                "not_a_run",
+                &premade_runs_from_data(),
+                &premade_levels_from_data()
+            ),
+            Err(OurRunGenerationError::UnknownRun("not_a_run".to_string()))
+        );
+        assert!(run_res.queued_levels.is_empty());
+    }
+
+    #[test]
+    fn test_generated_run_is_the_same_for_the_same_seed() {
+        let run_generator_res = ron::de::from_bytes::<RunGeneratorResource>(include_bytes!(
+            "../../assets/data/run_generator.ron"
+        ))
+        .unwrap();
+        let formations_res = ron::de::from_bytes::<FormationPoolsResource>(include_bytes!(
+            "../../assets/data/formation_pools.ron"
+        ))
+        .unwrap();
+        let generate = |seed| {
+            let mut run_res = CurrentRunProgressResource {
+                tutorials_on: false,
+                ..default()
+            };
+            run_res
+                .generate_procedural(
+                    seed,
+                    &run_generator_res,
+                    &formations_res,
+                    &premade_levels_from_data(),
+                )
+                .unwrap();
+            run_res.queued_levels
+        };
+
+        let levels = generate(7);
+        assert_eq!(levels.len(), run_generator_res.levels);
+        assert_eq!(format!("{:?}", levels), format!("{:?}", generate(7)));
+    }
 }
@@ -0,0 +1,123 @@
+//! Exposes `PhaseBranch`, which lets a level choose its next phase based on how the players are
+//! doing, instead of always playing its phases in order.
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thetawave_interface::objective::Objective;
+
+/// Something about the state of the level that is checked when a phase is completed
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum PhaseCondition {
+    /// Always met. Useful as the last branch of a phase, to pick a phase when no other branch does.
+    Always,
+    /// The defense objective is below a fraction (0.0 to 1.0) of its maximum
+    DefenseBelow(f32),
+    /// The phase was completed within some seconds. For boss phases, this is how long the players
+    /// took to destroy the boss.
+    CompletedWithin(f32),
+    /// Every player has more than a fraction (0.0 to 1.0) of their maximum health
+    AllPlayersHealthAbove(f32),
+    /// At least one player has less than a fraction (0.0 to 1.0) of their maximum health
+    AnyPlayerHealthBelow(f32),
+    /// Every one of the conditions is met
+    All(Vec<PhaseCondition>),
+    /// At least one of the conditions is met
+    Any(Vec<PhaseCondition>),
+    /// The condition is not met
+    Not(Box<PhaseCondition>),
+}
+
+/// State of the level that phase conditions are checked against
+pub struct PhaseConditionContext<'a> {
+    /// How long the completed phase was played for
+    pub phase_time: Duration,
+    pub objective: Option<&'a Objective>,
+    /// Fraction of maximum health of each player
+    pub player_health_percentages: &'a [f32],
+}
+
+impl PhaseCondition {
+    pub fn is_met(&self, context: &PhaseConditionContext) -> bool {
+        match self {
+            PhaseCondition::Always => true,
+            PhaseCondition::DefenseBelow(threshold) => match context.objective {
+                Some(Objective::Defense(defense_data)) => {
+                    defense_data.get_percentage() < *threshold
+                }
+                _ => false,
+            },
+            PhaseCondition::CompletedWithin(secs) => context.phase_time.as_secs_f32() <= *secs,
+            PhaseCondition::AllPlayersHealthAbove(threshold) => context
+                .player_health_percentages
+                .iter()
+                .all(|health| health > threshold),
+            PhaseCondition::AnyPlayerHealthBelow(threshold) => context
+                .player_health_percentages
+                .iter()
+                .any(|health| health < threshold),
+            PhaseCondition::All(conditions) => conditions.iter().all(|c| c.is_met(context)),
+            PhaseCondition::Any(conditions) => conditions.iter().any(|c| c.is_met(context)),
+            PhaseCondition::Not(condition) => !condition.is_met(context),
+        }
+    }
+}
+
+/// Phase to move to when a condition is met
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PhaseBranch {
+    pub condition: PhaseCondition,
+    /// Id of a later phase in the same level. The phases before it are skipped.
+    pub next_phase: String,
+}
+
+/// Get the id of the phase to branch to from the first branch with a condition that is met
+pub fn choose_branch<'a>(
+    branches: &'a [PhaseBranch],
+    context: &PhaseConditionContext,
+) -> Option<&'a str> {
+    branches
+        .iter()
+        .find(|branch| branch.condition.is_met(context))
+        .map(|branch| branch.next_phase.as_str())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+    use thetawave_interface::objective::{DefenseData, Objective};
+
+    use super::{choose_branch, PhaseBranch, PhaseCondition, PhaseConditionContext};
+
+    #[test]
+    fn test_first_met_branch_is_chosen() {
+        let branches = vec![
+            PhaseBranch {
+                condition: PhaseCondition::All(vec![
+                    PhaseCondition::CompletedWithin(30.0),
+                    PhaseCondition::DefenseBelow(0.5),
+                ]),
+                next_phase: "a".to_string(),
+            },
+            PhaseBranch {
+                condition: PhaseCondition::CompletedWithin(30.0),
+                next_phase: "b".to_string(),
+            },
+            PhaseBranch {
+                condition: PhaseCondition::Always,
+                next_phase: "c".to_string(),
+            },
+        ];
+        let objective = Objective::Defense(DefenseData {
+            defense: 80,
+            max_defense: 100,
+        });
+        let context = |secs| PhaseConditionContext {
+            phase_time: Duration::from_secs(secs),
+            objective: Some(&objective),
+            player_health_percentages: &[1.0],
+        };
+
+        assert_eq!(choose_branch(&branches, &context(20)), Some("b"));
+        assert_eq!(choose_branch(&branches, &context(40)), Some("c"));
+        assert_eq!(choose_branch(&[], &context(20)), None);
+    }
+}
@@ -0,0 +1,298 @@
+//! The shop phase, where the players spend the money they collected on items and consumables.
+//! Bought items and consumables are spawned on the player that bought them, so that they are
+//! applied by the same behaviors as the ones dropped by mobs.
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use thetawave_interface::{
+    audio::{PlaySoundEffectEvent, SoundEffectType},
+    input::PlayerAction,
+    player::{PlayerComponent, PlayerIDComponent, PlayerInventoryComponent, PlayersResource},
+    spawnable::{ConsumableType, ItemType, SpawnItemEvent},
+};
+
+use crate::{
+    game::{GameRngResource, RngStream},
+    spawnable::SpawnConsumableEvent,
+    tools::weighted_rng,
+};
+
+use super::{level_phase::LevelPhaseType, CurrentRunProgressResource};
+
+/// Something that can be bought in the shop
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ShopItem {
+    Item(ItemType),
+    Consumable(ConsumableType),
+}
+
+impl ShopItem {
+    pub fn get_name(&self) -> String {
+        match self {
+            ShopItem::Item(item_type) => item_type.to_string(),
+            ShopItem::Consumable(consumable_type) => consumable_type.to_string(),
+        }
+    }
+}
+
+/// Something the shop can sell, and how likely it is to be offered
+#[derive(Deserialize, Debug, Clone)]
+pub struct ShopOfferData {
+    pub item: ShopItem,
+    /// Money it costs
+    pub price: usize,
+    /// Chance of being picked as one of the offers of a shop, relative to the other offers
+    pub weight: f32,
+}
+
+/// Data describing what the shop can sell
+#[derive(Resource, Deserialize)]
+pub struct ShopDataResource {
+    /// Number of different offers each time the shop is opened
+    pub offer_count: usize,
+    pub offers: Vec<ShopOfferData>,
+}
+
+impl ShopDataResource {
+    /// Pick the offers of a newly opened shop, never offering the same thing twice
+    pub fn roll_offers(&self, rng: &mut impl Rng) -> Vec<ShopOffer> {
+        let mut remaining_offers: Vec<&ShopOfferData> = self.offers.iter().collect();
+        let mut offers = vec![];
+
+        while offers.len() < self.offer_count && !remaining_offers.is_empty() {
+            let offer_idx = weighted_rng(
+                remaining_offers
+                    .iter()
+                    .map(|offer_data| offer_data.weight)
+                    .collect(),
+                rng,
+            );
+            let offer_data = remaining_offers.remove(offer_idx);
+
+            offers.push(ShopOffer {
+                item: offer_data.item.clone(),
+                price: offer_data.price,
+                sold: false,
+            });
+        }
+
+        offers
+    }
+}
+
+/// Something for sale in the open shop. Offers are saved with the run, so that a run saved during a
+/// shop phase resumes with the same offers.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShopOffer {
+    pub item: ShopItem,
+    pub price: usize,
+    /// Offers can only be bought once
+    pub sold: bool,
+}
+
+/// The shop of the shop phase that is being played
+#[derive(Resource, Debug, Default)]
+pub struct ShopResource {
+    /// Offers of the open shop. Empty when the shop is closed.
+    pub offers: Vec<ShopOffer>,
+    /// Index of the offer that each player slot has selected
+    pub selections: Vec<usize>,
+}
+
+impl ShopResource {
+    pub fn is_open(&self) -> bool {
+        !self.offers.is_empty()
+    }
+
+    pub(crate) fn open(&mut self, offers: Vec<ShopOffer>, player_slots: usize) {
+        self.offers = offers;
+        self.selections = vec![0; player_slots];
+    }
+
+    fn close(&mut self) {
+        self.offers.clear();
+        self.selections.clear();
+    }
+
+    /// Move the selection of a player to the next offer that hasn't been sold, wrapping around
+    pub fn select_next(&mut self, player_idx: usize) {
+        let Some(selection) = self.selections.get_mut(player_idx) else {
+            return;
+        };
+
+        let offer_count = self.offers.len();
+        if let Some(next_selection) = (1..=offer_count)
+            .map(|offset| (*selection + offset) % offer_count)
+            .find(|offer_idx| !self.offers[*offer_idx].sold)
+        {
+            *selection = next_selection;
+        }
+    }
+
+    /// Buy the offer a player has selected with their money. Returns what was bought, or None if
+    /// the offer was already sold or the player can't afford it.
+    pub fn buy(&mut self, player_idx: usize, money: &mut usize) -> Option<ShopItem> {
+        let offer = self.offers.get_mut(*self.selections.get(player_idx)?)?;
+
+        if offer.sold || offer.price > *money {
+            return None;
+        }
+
+        *money -= offer.price;
+        offer.sold = true;
+        let item = offer.item.clone();
+
+        // nothing is left to buy at the sold offer, so move on to the next one
+        self.select_next(player_idx);
+
+        Some(item)
+    }
+}
+
+/// Open the shop with newly rolled offers when a shop phase starts, and close it when it ends
+pub(super) fn update_shop_system(
+    run_res: Res<CurrentRunProgressResource>,
+    shop_data: Res<ShopDataResource>,
+    players: Res<PlayersResource>,
+    mut shop: ResMut<ShopResource>,
+    mut game_rng: ResMut<GameRngResource>,
+) {
+    let in_shop_phase = matches!(
+        run_res
+            .current_level
+            .as_ref()
+            .and_then(|level| level.current_phase.as_ref())
+            .map(|phase| &phase.phase_type),
+        Some(LevelPhaseType::Shop { .. })
+    );
+
+    if in_shop_phase && !shop.is_open() {
+        let offers = shop_data.roll_offers(game_rng.stream(RngStream::Shop));
+        shop.open(offers, players.player_data.len());
+        info!("Shop opened");
+    } else if !in_shop_phase && shop.is_open() {
+        shop.close();
+        info!("Shop closed");
+    }
+}
+
+/// Close the shop when the game is left in the middle of a shop phase
+pub(crate) fn close_shop_system(mut shop: ResMut<ShopResource>) {
+    shop.close();
+}
+
+/// Let each player select offers and buy them with their own money, using the input they joined
+/// with
+pub(super) fn shop_input_system(
+    mut shop: ResMut<ShopResource>,
+    mut player_query: Query<
+        (
+            &PlayerIDComponent,
+            &ActionState<PlayerAction>,
+            &mut PlayerInventoryComponent,
+            &Transform,
+        ),
+        With<PlayerComponent>,
+    >,
+    mut spawn_item_event_writer: EventWriter<SpawnItemEvent>,
+    mut spawn_consumable_event_writer: EventWriter<SpawnConsumableEvent>,
+    mut sound_effect_event_writer: EventWriter<PlaySoundEffectEvent>,
+) {
+    if !shop.is_open() {
+        return;
+    }
+
+    for (player_id, action_state, mut player_inventory, transform) in player_query.iter_mut() {
+        let player_idx = usize::from(*player_id);
+        let select = action_state.just_pressed(&PlayerAction::ShopSelect);
+        let buy = action_state.just_pressed(&PlayerAction::ShopBuy);
+
+        if select {
+            shop.select_next(player_idx);
+            sound_effect_event_writer.send(PlaySoundEffectEvent {
+                sound_effect_type: SoundEffectType::ButtonSelect,
+            });
+        }
+
+        if buy {
+            let position = transform.translation.truncate();
+
+            match shop.buy(player_idx, &mut player_inventory.money) {
+                Some(ShopItem::Item(item_type)) => {
+                    spawn_item_event_writer.send(SpawnItemEvent {
+                        item_type,
+                        position,
+                    });
+                }
+                Some(ShopItem::Consumable(consumable_type)) => {
+                    spawn_consumable_event_writer.send(SpawnConsumableEvent {
+                        consumable_type,
+                        position,
+                    });
+                }
+                None => continue,
+            }
+
+            info!("Player {} bought from the shop", player_idx + 1);
+            sound_effect_event_writer.send(PlaySoundEffectEvent {
+                sound_effect_type: SoundEffectType::MenuInputSuccess,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{ShopDataResource, ShopResource};
+
+    fn shop_data() -> ShopDataResource {
+        ron::de::from_bytes::<ShopDataResource>(include_bytes!("../../assets/data/shop.ron"))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_shop_offers_are_not_repeated() {
+        let shop_data = shop_data();
+        let offers = shop_data.roll_offers(&mut StdRng::seed_from_u64(7));
+
+        assert_eq!(
+            offers.len(),
+            shop_data.offer_count.min(shop_data.offers.len())
+        );
+        for (i, offer) in offers.iter().enumerate() {
+            assert!(!offers[i + 1..]
+                .iter()
+                .any(|other_offer| other_offer.item == offer.item));
+        }
+    }
+
+    #[test]
+    fn test_players_buy_with_their_own_money() {
+        let mut shop = ShopResource::default();
+        shop.open(shop_data().roll_offers(&mut StdRng::seed_from_u64(7)), 2);
+        let price = shop.offers[0].price;
+
+        // the first player can't afford the offer, the second player can
+        let mut player_1_money = price - 1;
+        assert_eq!(shop.buy(0, &mut player_1_money), None);
+        assert_eq!(player_1_money, price - 1);
+
+        let mut player_2_money = price;
+        assert_eq!(
+            shop.buy(1, &mut player_2_money),
+            Some(shop.offers[0].item.clone())
+        );
+        assert_eq!(player_2_money, 0);
+        assert!(shop.offers[0].sold);
+        assert_ne!(shop.selections[1], 0);
+
+        // sold offers can't be bought again and are skipped when selecting
+        let mut player_1_money = price;
+        assert_eq!(shop.buy(0, &mut player_1_money), None);
+        shop.select_next(0);
+        assert_ne!(shop.selections[0], 0);
+    }
+}
@@ -0,0 +1,431 @@
+//! Exposes `TimelinePhase`, a level phase that fires hand-authored entries at set times instead of
+//! picking random formations.
+use bevy::{ecs::system::SystemParam, prelude::*};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use thetawave_interface::{
+    audio::ChangeBackgroundMusicEvent,
+    run::IntroTextEvent,
+    spawnable::{ConsumableType, ItemType, MobType, SpawnItemEvent, SpawnMobEvent},
+};
+
+use crate::spawnable::{MobBehaviorSequenceEvent, MobComponent, SpawnConsumableEvent};
+
+use super::{
+    formation::{
+        Formation, FormationPoolsResource, FormationSpawnable, FormationWeightContext,
+        SpawnFormationEvent,
+    },
+    level::BGMusicTransition,
+};
+
+/// Seconds to wait after the last entry before checking for remaining mobs, so that mobs spawned
+/// by the last entry have a chance to appear
+const MOB_SPAWN_GRACE_SECS: f32 = 1.0;
+
+/// Event writers and queries that are only needed by timeline phases
+#[derive(SystemParam)]
+pub struct TimelineParams<'w, 's> {
+    spawn_consumable_event_writer: EventWriter<'w, SpawnConsumableEvent>,
+    spawn_item_event_writer: EventWriter<'w, SpawnItemEvent>,
+    intro_text_event_writer: EventWriter<'w, IntroTextEvent>,
+    behavior_sequence_event_writer: EventWriter<'w, MobBehaviorSequenceEvent>,
+    spawned_mob_query: Query<'w, 's, Entity, Added<MobComponent>>,
+    mob_query: Query<'w, 's, (), With<MobComponent>>,
+}
+
+/// Something that happens at a point in a timeline
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum TimelineEventType {
+    /// Spawn a formation of spawnables
+    Formation(Vec<FormationSpawnable>),
+    /// Spawn a random formation from a pool
+    FormationFromPool(String),
+    /// Spawn a single mob
+    Mob {
+        mob_type: MobType,
+        position: Vec2,
+        #[serde(default)]
+        boss: bool,
+    },
+    /// Spawn a single consumable
+    Consumable {
+        consumable_type: ConsumableType,
+        position: Vec2,
+    },
+    /// Spawn a single item
+    Item { item_type: ItemType, position: Vec2 },
+    /// Show text in the center of the screen
+    IntroText(String),
+    /// Change the background music
+    Music(BGMusicTransition),
+    /// Send a named event to the behavior sequences of all mobs, for their `Event` transitions
+    BehaviorSequenceEvent(String),
+}
+
+/// An event and the time it happens at
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TimelineEntry {
+    /// Seconds after the start of the phase
+    pub time: f32,
+    pub event: TimelineEventType,
+}
+
+/// Data and state for a timeline phase
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TimelinePhase {
+    /// Entries of the timeline, in the order that they happen
+    pub entries: Vec<TimelineEntry>,
+    /// Whether the phase waits until the mobs spawned during it are destroyed after the last entry
+    /// has fired
+    #[serde(default)]
+    pub wait_for_mobs_destroyed: bool,
+    /// Seconds since the start of the phase
+    #[serde(default)]
+    pub elapsed: f32,
+    /// Index of the next entry to fire
+    #[serde(default)]
+    next_entry: usize,
+    /// Mobs spawned during the phase that haven't been destroyed yet
+    #[serde(skip)]
+    spawned_mobs: Vec<Entity>,
+}
+
+impl TimelinePhase {
+    /// Time of the last entry of the timeline
+    fn get_last_entry_time(&self) -> f32 {
+        self.entries.last().map_or(0.0, |entry| entry.time)
+    }
+
+    /// Seconds until the last entry of the timeline fires
+    pub fn get_remaining_secs(&self) -> f32 {
+        (self.get_last_entry_time() - self.elapsed).max(0.0)
+    }
+
+    /// Fraction (0.0 to 1.0) of the timeline that has been played, up to the last entry
+    pub fn get_progress(&self) -> f32 {
+        let last_entry_time = self.get_last_entry_time();
+        if last_entry_time > 0.0 {
+            (self.elapsed / last_entry_time).min(1.0)
+        } else {
+            1.0
+        }
+    }
+
+    /// Fire every entry that has been reached. Returns true when the phase is completed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn tick(
+        &mut self,
+        time: &Time,
+        spawn_formation_event_writer: &mut EventWriter<SpawnFormationEvent>,
+        spawn_mob_event_writer: &mut EventWriter<SpawnMobEvent>,
+        change_bg_music_event_writer: &mut EventWriter<ChangeBackgroundMusicEvent>,
+        timeline_params: &mut TimelineParams,
+        formations_res: &FormationPoolsResource,
+        level_idx: usize,
+        rng: &mut impl Rng,
+    ) -> bool {
+        self.elapsed += time.delta_seconds();
+
+        let weight_context = FormationWeightContext {
+            phase_progress: self.get_progress(),
+            level_idx,
+        };
+
+        while let Some(entry) = self.entries.get(self.next_entry) {
+            if entry.time > self.elapsed {
+                break;
+            }
+
+            match &entry.event {
+                TimelineEventType::Formation(formation_spawnables) => {
+                    spawn_formation_event_writer.send(SpawnFormationEvent {
+                        formation: Formation {
+                            formation_spawnables: formation_spawnables.clone(),
+                            weight: 1.0,
+                            period: 0.0,
+                            weight_curve: None,
+                            mirror_chance: 0.0,
+                            x_jitter: 0.0,
+                        },
+                    });
+                }
+                TimelineEventType::FormationFromPool(pool_key) => {
+                    if let Some(formation) =
+                        formations_res.get_random_formation(pool_key.clone(), &weight_context, rng)
+                    {
+                        spawn_formation_event_writer.send(SpawnFormationEvent { formation });
+                    }
+                }
+                TimelineEventType::Mob {
+                    mob_type,
+                    position,
+                    boss,
+                } => {
+                    spawn_mob_event_writer.send(SpawnMobEvent {
+                        mob_type: mob_type.clone(),
+                        position: *position,
+                        rotation: Quat::default(),
+                        boss: *boss,
+                        parent: None,
+                    });
+                }
+                TimelineEventType::Consumable {
+                    consumable_type,
+                    position,
+                } => {
+                    timeline_params
+                        .spawn_consumable_event_writer
+                        .send(SpawnConsumableEvent {
+                            consumable_type: consumable_type.clone(),
+                            position: *position,
+                        });
+                }
+                TimelineEventType::Item {
+                    item_type,
+                    position,
+                } => {
+                    timeline_params
+                        .spawn_item_event_writer
+                        .send(SpawnItemEvent {
+                            item_type: item_type.clone(),
+                            position: *position,
+                        });
+                }
+                TimelineEventType::IntroText(text) => {
+                    timeline_params
+                        .intro_text_event_writer
+                        .send(IntroTextEvent { text: text.clone() });
+                }
+                TimelineEventType::Music(bg_music_transition) => {
+                    change_bg_music_event_writer
+                        .send(ChangeBackgroundMusicEvent::from(bg_music_transition));
+                }
+                TimelineEventType::BehaviorSequenceEvent(name) => {
+                    timeline_params
+                        .behavior_sequence_event_writer
+                        .send(MobBehaviorSequenceEvent {
+                            name: name.clone(),
+                            entity: None,
+                        });
+                }
+            }
+
+            self.next_entry += 1;
+        }
+
+        // only wait for the mobs spawned during this phase, not mobs left over from earlier phases
+        if self.wait_for_mobs_destroyed {
+            self.spawned_mobs
+                .extend(timeline_params.spawned_mob_query.iter());
+            self.spawned_mobs
+                .retain(|entity| timeline_params.mob_query.contains(*entity));
+        }
+
+        if self.next_entry < self.entries.len() {
+            return false;
+        }
+
+        if self.wait_for_mobs_destroyed {
+            self.elapsed >= self.get_last_entry_time() + MOB_SPAWN_GRACE_SECS
+                && self.spawned_mobs.is_empty()
+        } else {
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::{ecs::system::SystemState, prelude::*};
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::{collections::HashMap, time::Duration};
+    use thetawave_interface::{
+        audio::ChangeBackgroundMusicEvent,
+        run::IntroTextEvent,
+        spawnable::{EnemyMobType, MobType, SpawnItemEvent, SpawnMobEvent},
+    };
+
+    use super::{TimelineEntry, TimelineEventType, TimelineParams, TimelinePhase};
+    use crate::{
+        run::formation::{FormationPoolsResource, SpawnFormationEvent},
+        spawnable::{MobBehaviorSequenceEvent, MobComponent, MobData, SpawnConsumableEvent},
+    };
+
+    type TimelineState = SystemState<(
+        EventWriter<'static, SpawnFormationEvent>,
+        EventWriter<'static, SpawnMobEvent>,
+        EventWriter<'static, ChangeBackgroundMusicEvent>,
+        TimelineParams<'static, 'static>,
+    )>;
+
+    fn timeline_world() -> (World, TimelineState) {
+        let mut world = World::new();
+        world.init_resource::<Events<SpawnFormationEvent>>();
+        world.init_resource::<Events<SpawnMobEvent>>();
+        world.init_resource::<Events<ChangeBackgroundMusicEvent>>();
+        world.init_resource::<Events<SpawnConsumableEvent>>();
+        world.init_resource::<Events<SpawnItemEvent>>();
+        world.init_resource::<Events<IntroTextEvent>>();
+        world.init_resource::<Events<MobBehaviorSequenceEvent>>();
+        let system_state = TimelineState::new(&mut world);
+
+        (world, system_state)
+    }
+
+    /// Tick the timeline phase by one second, returning whether it was completed
+    fn tick_second(
+        timeline_phase: &mut TimelinePhase,
+        world: &mut World,
+        system_state: &mut TimelineState,
+    ) -> bool {
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_secs(1));
+        let formations_res = FormationPoolsResource {
+            formation_pools: HashMap::new(),
+        };
+
+        let (
+            mut spawn_formation_event_writer,
+            mut spawn_mob_event_writer,
+            mut change_bg_music_event_writer,
+            mut timeline_params,
+        ) = system_state.get_mut(world);
+
+        timeline_phase.tick(
+            &time,
+            &mut spawn_formation_event_writer,
+            &mut spawn_mob_event_writer,
+            &mut change_bg_music_event_writer,
+            &mut timeline_params,
+            &formations_res,
+            0,
+            &mut StdRng::seed_from_u64(0),
+        )
+    }
+
+    fn drone() -> MobComponent {
+        let mobs = ron::de::from_bytes::<HashMap<MobType, MobData>>(include_bytes!(
+            "../../assets/data/mobs.ron"
+        ))
+        .unwrap();
+
+        MobComponent::from(&mobs[&MobType::Enemy(EnemyMobType::Drone)])
+    }
+
+    fn timeline_phase(entries: Vec<TimelineEntry>, wait_for_mobs_destroyed: bool) -> TimelinePhase {
+        TimelinePhase {
+            entries,
+            wait_for_mobs_destroyed,
+            elapsed: 0.0,
+            next_entry: 0,
+            spawned_mobs: vec![],
+        }
+    }
+
+    /// Intro text followed by a drone two seconds in
+    fn drone_entries() -> Vec<TimelineEntry> {
+        vec![
+            TimelineEntry {
+                time: 0.0,
+                event: TimelineEventType::IntroText("Incoming".to_string()),
+            },
+            TimelineEntry {
+                time: 2.0,
+                event: TimelineEventType::Mob {
+                    mob_type: MobType::Enemy(EnemyMobType::Drone),
+                    position: Vec2::new(0.0, 500.0),
+                    boss: false,
+                },
+            },
+        ]
+    }
+
+    #[test]
+    fn test_timeline_entries_fire_at_their_time() {
+        let (mut world, mut system_state) = timeline_world();
+        let mut timeline_phase = timeline_phase(drone_entries(), false);
+
+        assert!(!tick_second(
+            &mut timeline_phase,
+            &mut world,
+            &mut system_state
+        ));
+        assert_eq!(world.resource::<Events<IntroTextEvent>>().len(), 1);
+        assert_eq!(world.resource::<Events<SpawnMobEvent>>().len(), 0);
+
+        // the phase is completed as soon as the last entry fires
+        assert!(tick_second(
+            &mut timeline_phase,
+            &mut world,
+            &mut system_state
+        ));
+        assert_eq!(world.resource::<Events<IntroTextEvent>>().len(), 1);
+        assert_eq!(world.resource::<Events<SpawnMobEvent>>().len(), 1);
+    }
+
+    #[test]
+    fn test_timeline_waits_for_its_own_mobs_to_be_destroyed() {
+        let (mut world, mut system_state) = timeline_world();
+        let mut timeline_phase = timeline_phase(drone_entries(), true);
+
+        // a mob left over from an earlier phase, spawned before the timeline started
+        world.spawn(drone());
+        system_state.get_mut(&mut world);
+
+        assert!(!tick_second(
+            &mut timeline_phase,
+            &mut world,
+            &mut system_state
+        ));
+        assert!(!tick_second(
+            &mut timeline_phase,
+            &mut world,
+            &mut system_state
+        ));
+
+        // the mob spawned by the last entry
+        let mob = world.spawn(drone()).id();
+        assert!(!tick_second(
+            &mut timeline_phase,
+            &mut world,
+            &mut system_state
+        ));
+        assert!(!tick_second(
+            &mut timeline_phase,
+            &mut world,
+            &mut system_state
+        ));
+
+        world.despawn(mob);
+        assert!(tick_second(
+            &mut timeline_phase,
+            &mut world,
+            &mut system_state
+        ));
+    }
+
+    #[test]
+    fn test_timeline_sends_behavior_sequence_events_to_all_mobs() {
+        let (mut world, mut system_state) = timeline_world();
+        let mut timeline_phase = timeline_phase(
+            vec![TimelineEntry {
+                time: 1.0,
+                event: TimelineEventType::BehaviorSequenceEvent("enrage".to_string()),
+            }],
+            false,
+        );
+
+        assert!(tick_second(
+            &mut timeline_phase,
+            &mut world,
+            &mut system_state
+        ));
+
+        let events = world.resource::<Events<MobBehaviorSequenceEvent>>();
+        let sent: Vec<_> = events.get_reader().read(events).collect();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].name, "enrage");
+        assert_eq!(sent[0].entity, None);
+    }
+}
@@ -5,8 +5,8 @@ use bevy::log::info;
 use bevy::math::Quat;
 use bevy::prelude::{EventReader, EventWriter, Query, Time, Timer, With};
 use leafwing_input_manager::action_state::ActionState;
-use rand::{thread_rng, Rng};
-use serde::Deserialize;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::ops::Range;
 use thetawave_interface::audio::{PlaySoundEffectEvent, SoundEffectType};
 use thetawave_interface::input::PlayerAction;
@@ -40,7 +40,7 @@ pub(super) fn modify_player_spawn_params_for_lesson_phase(
 
 /// The state of the player's tutorial. Methods update this (and transition to different tutorial
 /// state variants) for each game tick until the tutorial is complete.
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum TutorialLesson {
     Movement {
         up_timer: Timer,
@@ -273,6 +273,7 @@ impl TutorialLesson {
         mob_reached_bottom_event: &mut EventReader<MobReachedBottomGateEvent>,
         mob_segment_destroyed_event: &mut EventReader<MobSegmentDestroyedEvent>,
         play_sound_effect_event_writer: &mut EventWriter<PlaySoundEffectEvent>,
+        rng: &mut impl Rng,
     ) -> bool {
         self.disable_player_actions_for_current_phase();
         // tutorial will only be run for single player
@@ -285,6 +286,7 @@ impl TutorialLesson {
                     mob_reached_bottom_event,
                     mob_segment_destroyed_event,
                     play_sound_effect_event_writer,
+                    rng,
                 ),
                 TutorialLesson::AbilitySlotTwo { .. } => self.ability_tutorial(
                     mob_destroyed_event,
@@ -292,6 +294,7 @@ impl TutorialLesson {
                     spawn_mob_event_writer,
                     mob_reached_bottom_event,
                     play_sound_effect_event_writer,
+                    rng,
                 ),
                 TutorialLesson::Movement { .. } => {
                     self.movement_tutorial(action_state, time, play_sound_effect_event_writer)
@@ -318,6 +321,7 @@ impl TutorialLesson {
         mob_reached_bottom_event: &mut EventReader<MobReachedBottomGateEvent>,
         mob_segment_destroyed_event: &mut EventReader<MobSegmentDestroyedEvent>,
         play_sound_effect_event_writer: &mut EventWriter<PlaySoundEffectEvent>,
+        rng: &mut impl Rng,
     ) -> bool {
         if let TutorialLesson::AbilitySlotOne {
             mobs_to_destroy,
@@ -332,9 +336,10 @@ impl TutorialLesson {
             if initial_spawn_timer.just_finished() {
                 spawn_mob_event_writer.send(SpawnMobEvent {
                     mob_type: MobType::Neutral(NeutralMobType::TutorialDrone),
-                    position: (thread_rng().gen_range(spawn_range_x.clone()), *spawn_y).into(),
+                    position: (rng.gen_range(spawn_range_x.clone()), *spawn_y).into(),
                     rotation: Quat::default(),
                     boss: false,
+                    parent: None,
                 });
             }
 
@@ -351,10 +356,10 @@ impl TutorialLesson {
                     if *mobs_to_destroy != 0 {
                         spawn_mob_event_writer.send(SpawnMobEvent {
                             mob_type: MobType::Neutral(NeutralMobType::TutorialDrone),
-                            position: (thread_rng().gen_range(spawn_range_x.clone()), *spawn_y)
-                                .into(),
+                            position: (rng.gen_range(spawn_range_x.clone()), *spawn_y).into(),
                             rotation: Quat::default(),
                             boss: false,
+                            parent: None,
                         });
                     } else if *mobs_to_protect > 0 {
                         play_sound_effect_event_writer.send(PlaySoundEffectEvent {
@@ -363,10 +368,10 @@ impl TutorialLesson {
 
                         spawn_mob_event_writer.send(SpawnMobEvent {
                             mob_type: MobType::Ally(AllyMobType::TutorialHauler2),
-                            position: (thread_rng().gen_range(spawn_range_x.clone()), *spawn_y)
-                                .into(),
+                            position: (rng.gen_range(spawn_range_x.clone()), *spawn_y).into(),
                             rotation: Quat::default(),
                             boss: false,
+                            parent: None,
                         });
                     }
                 }
@@ -380,9 +385,10 @@ impl TutorialLesson {
                 {
                     spawn_mob_event_writer.send(SpawnMobEvent {
                         mob_type: MobType::Ally(AllyMobType::TutorialHauler2),
-                        position: (thread_rng().gen_range(spawn_range_x.clone()), *spawn_y).into(),
+                        position: (rng.gen_range(spawn_range_x.clone()), *spawn_y).into(),
                         rotation: Quat::default(),
                         boss: false,
+                        parent: None,
                     });
                 }
             }
@@ -400,10 +406,10 @@ impl TutorialLesson {
                         if *mobs_to_protect != 0 {
                             spawn_mob_event_writer.send(SpawnMobEvent {
                                 mob_type: MobType::Ally(AllyMobType::TutorialHauler2),
-                                position: (thread_rng().gen_range(spawn_range_x.clone()), *spawn_y)
-                                    .into(),
+                                position: (rng.gen_range(spawn_range_x.clone()), *spawn_y).into(),
                                 rotation: Quat::default(),
                                 boss: false,
+                                parent: None,
                             });
                         } else {
                             play_sound_effect_event_writer.send(PlaySoundEffectEvent {
@@ -415,10 +421,10 @@ impl TutorialLesson {
                     if matches!(mob_type, MobType::Neutral(NeutralMobType::TutorialDrone)) {
                         spawn_mob_event_writer.send(SpawnMobEvent {
                             mob_type: MobType::Neutral(NeutralMobType::TutorialDrone),
-                            position: (thread_rng().gen_range(spawn_range_x.clone()), *spawn_y)
-                                .into(),
+                            position: (rng.gen_range(spawn_range_x.clone()), *spawn_y).into(),
                             rotation: Quat::default(),
                             boss: false,
+                            parent: None,
                         });
                     }
                 }
@@ -438,6 +444,7 @@ impl TutorialLesson {
         spawn_mob_event_writer: &mut EventWriter<SpawnMobEvent>,
         mob_reached_bottom_event: &mut EventReader<MobReachedBottomGateEvent>,
         play_sound_effect_event_writer: &mut EventWriter<PlaySoundEffectEvent>,
+        rng: &mut impl Rng,
     ) -> bool {
         if let TutorialLesson::AbilitySlotTwo {
             mobs_to_destroy,
@@ -451,9 +458,10 @@ impl TutorialLesson {
             if initial_spawn_timer.just_finished() {
                 spawn_mob_event_writer.send(SpawnMobEvent {
                     mob_type: MobType::Neutral(NeutralMobType::TutorialDrone),
-                    position: (thread_rng().gen_range(spawn_range_x.clone()), *spawn_y).into(),
+                    position: (rng.gen_range(spawn_range_x.clone()), *spawn_y).into(),
                     rotation: Quat::default(),
                     boss: false,
+                    parent: None,
                 });
             }
 
@@ -470,10 +478,10 @@ impl TutorialLesson {
                     if *mobs_to_destroy != 0 {
                         spawn_mob_event_writer.send(SpawnMobEvent {
                             mob_type: MobType::Neutral(NeutralMobType::TutorialDrone),
-                            position: (thread_rng().gen_range(spawn_range_x.clone()), *spawn_y)
-                                .into(),
+                            position: (rng.gen_range(spawn_range_x.clone()), *spawn_y).into(),
                             rotation: Quat::default(),
                             boss: false,
+                            parent: None,
                         });
                     } else {
                         play_sound_effect_event_writer.send(PlaySoundEffectEvent {
@@ -489,10 +497,10 @@ impl TutorialLesson {
                     if matches!(mob_type, MobType::Neutral(NeutralMobType::TutorialDrone)) {
                         spawn_mob_event_writer.send(SpawnMobEvent {
                             mob_type: MobType::Neutral(NeutralMobType::TutorialDrone),
-                            position: (thread_rng().gen_range(spawn_range_x.clone()), *spawn_y)
-                                .into(),
+                            position: (rng.gen_range(spawn_range_x.clone()), *spawn_y).into(),
                             rotation: Quat::default(),
                             boss: false,
+                            parent: None,
                         });
                     }
                 }
@@ -0,0 +1,229 @@
+//! Checks the references between the run, level, run generator and formation pool data files.
+use std::collections::HashSet;
+use thetawave_interface::spawnable::MobType;
+
+use crate::{spawnable::check_spawnable_type, validation::DataValidator};
+
+use super::{
+    formation::{FormationPoolsResource, FormationSpawnable},
+    generator::RunGeneratorResource,
+    level::PremadeLevelsResource,
+    level_phase::LevelPhaseType,
+    shop::ShopDataResource,
+    timeline::TimelineEventType,
+    PremadeRunsResource,
+};
+
+const PREMADE_RUNS_FILE: &str = "premade_runs.ron";
+const PREMADE_LEVELS_FILE: &str = "premade_levels.ron";
+const RUN_GENERATOR_FILE: &str = "run_generator.ron";
+const FORMATION_POOLS_FILE: &str = "formation_pools.ron";
+const SHOP_FILE: &str = "shop.ron";
+
+/// Validate the run data files against each other and the mobs that are defined
+pub(crate) fn validate_data(validator: &mut DataValidator, mob_types: &HashSet<MobType>) {
+    let formation_pools = validator
+        .load::<FormationPoolsResource>(FORMATION_POOLS_FILE)
+        .map(|res| res.formation_pools);
+    let premade_levels = validator.load::<PremadeLevelsResource>(PREMADE_LEVELS_FILE);
+    let premade_runs = validator.load::<PremadeRunsResource>(PREMADE_RUNS_FILE);
+    let run_generator = validator.load::<RunGeneratorResource>(RUN_GENERATOR_FILE);
+
+    // reports a pool key if the formation pools were loaded and don't contain it
+    let check_pool = |validator: &mut DataValidator, file: &str, key: &str, pool_key: &str| {
+        if let Some(formation_pools) = &formation_pools {
+            if !formation_pools.contains_key(pool_key) {
+                validator.report(file, key, format!("unknown formation pool \"{pool_key}\""));
+            }
+        }
+    };
+
+    if let Some(formation_pools) = &formation_pools {
+        for (pool_key, formation_pool) in formation_pools.iter() {
+            for formation in formation_pool.iter() {
+                if let Some(weight_curve) = &formation.weight_curve {
+                    let points = weight_curve.get_points();
+                    if points.windows(2).any(|segment| segment[1].0 < segment[0].0) {
+                        validator.report(
+                            FORMATION_POOLS_FILE,
+                            pool_key,
+                            "weight curve points are not in increasing order",
+                        );
+                    }
+                }
+                check_formation_spawnables(
+                    validator,
+                    FORMATION_POOLS_FILE,
+                    pool_key,
+                    mob_types,
+                    &formation.formation_spawnables,
+                );
+            }
+        }
+    }
+
+    if let Some(premade_levels) = &premade_levels {
+        for (level_key, level_data) in premade_levels.levels_data.iter() {
+            for (phase_idx, phase) in level_data.phases.iter().enumerate() {
+                // phases can only branch forward, to phases that are still queued
+                for branch in phase.branches.iter() {
+                    if !level_data.phases[phase_idx + 1..]
+                        .iter()
+                        .any(|later_phase| later_phase.id.as_ref() == Some(&branch.next_phase))
+                    {
+                        validator.report(
+                            PREMADE_LEVELS_FILE,
+                            level_key,
+                            format!(
+                                "no later phase to branch to with id \"{}\"",
+                                branch.next_phase
+                            ),
+                        );
+                    }
+                }
+
+                match &phase.phase_type {
+                    LevelPhaseType::FormationSpawn { formation_pool, .. } => {
+                        check_pool(validator, PREMADE_LEVELS_FILE, level_key, formation_pool);
+                    }
+                    LevelPhaseType::Boss { mob_type, .. } => {
+                        check_mob_type(
+                            validator,
+                            PREMADE_LEVELS_FILE,
+                            level_key,
+                            mob_types,
+                            mob_type,
+                        );
+                    }
+                    LevelPhaseType::Endless(endless_phase) => {
+                        for pool_key in endless_phase.formation_pools.iter() {
+                            check_pool(validator, PREMADE_LEVELS_FILE, level_key, pool_key);
+                        }
+                        for boss in endless_phase.bosses.iter() {
+                            check_mob_type(
+                                validator,
+                                PREMADE_LEVELS_FILE,
+                                level_key,
+                                mob_types,
+                                &boss.mob_type,
+                            );
+                        }
+                    }
+                    LevelPhaseType::Timeline(timeline_phase) => {
+                        // entries fire in the order they are listed, so a misplaced entry would
+                        // fire late
+                        if timeline_phase
+                            .entries
+                            .windows(2)
+                            .any(|entries| entries[1].time < entries[0].time)
+                        {
+                            validator.report(
+                                PREMADE_LEVELS_FILE,
+                                level_key,
+                                "timeline entries are not in increasing order of time",
+                            );
+                        }
+                        for entry in timeline_phase.entries.iter() {
+                            match &entry.event {
+                                TimelineEventType::Formation(formation_spawnables) => {
+                                    check_formation_spawnables(
+                                        validator,
+                                        PREMADE_LEVELS_FILE,
+                                        level_key,
+                                        mob_types,
+                                        formation_spawnables,
+                                    );
+                                }
+                                TimelineEventType::FormationFromPool(pool_key) => {
+                                    check_pool(validator, PREMADE_LEVELS_FILE, level_key, pool_key);
+                                }
+                                TimelineEventType::Mob { mob_type, .. } => {
+                                    check_mob_type(
+                                        validator,
+                                        PREMADE_LEVELS_FILE,
+                                        level_key,
+                                        mob_types,
+                                        mob_type,
+                                    );
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    LevelPhaseType::Break { .. }
+                    | LevelPhaseType::Shop { .. }
+                    | LevelPhaseType::Tutorial { .. } => {}
+                }
+            }
+        }
+    }
+
+    if let (Some(premade_runs), Some(premade_levels)) = (&premade_runs, &premade_levels) {
+        for (run_key, level_keys) in premade_runs.runs.iter() {
+            for level_key in level_keys.iter() {
+                if !premade_levels.levels_data.contains_key(level_key) {
+                    validator.report(
+                        PREMADE_RUNS_FILE,
+                        run_key,
+                        format!("unknown level \"{level_key}\""),
+                    );
+                }
+            }
+        }
+    }
+
+    if let Some(run_generator) = &run_generator {
+        for pool_key in run_generator.formation_pools.iter() {
+            check_pool(validator, RUN_GENERATOR_FILE, "formation_pools", pool_key);
+        }
+        for boss in run_generator.bosses.iter() {
+            check_mob_type(
+                validator,
+                RUN_GENERATOR_FILE,
+                "bosses",
+                mob_types,
+                &boss.mob_type,
+            );
+        }
+    }
+
+    if let Some(shop_data) = validator.load::<ShopDataResource>(SHOP_FILE) {
+        for offer in shop_data.offers.iter() {
+            if offer.weight <= 0.0 {
+                validator.report(SHOP_FILE, offer.item.get_name(), "weight is not positive");
+            }
+        }
+    }
+}
+
+/// Check that every spawnable in a formation is defined
+fn check_formation_spawnables(
+    validator: &mut DataValidator,
+    file: &str,
+    key: &str,
+    mob_types: &HashSet<MobType>,
+    formation_spawnables: &[FormationSpawnable],
+) {
+    for formation_spawnable in formation_spawnables.iter() {
+        check_spawnable_type(
+            validator,
+            file,
+            key,
+            mob_types,
+            &formation_spawnable.spawnable_type,
+        );
+    }
+}
+
+/// Check that a mob is defined in the mobs file
+fn check_mob_type(
+    validator: &mut DataValidator,
+    file: &str,
+    key: &str,
+    mob_types: &HashSet<MobType>,
+    mob_type: &MobType,
+) {
+    if !mob_types.contains(mob_type) {
+        validator.report(file, key, format!("unknown mob type {mob_type:?}"));
+    }
+}
@@ -0,0 +1,76 @@
+//! Persists save slots as files in the 'saves' directory, for builds without the `storage`
+//! feature.
+use bevy::prelude::*;
+use std::{
+    env::current_dir,
+    fs::{create_dir_all, read_dir, read_to_string, remove_file, write},
+    path::PathBuf,
+};
+use thetawave_interface::game::saved_runs::{SaveSlotChangedEvent, SavedRunsResource};
+
+const SAVES_DIR: &str = "saves";
+
+pub(super) struct SaveFilesPlugin;
+
+impl Plugin for SaveFilesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_save_files_system)
+            .add_systems(Last, flush_changed_save_slots_to_files_system);
+    }
+}
+
+fn get_saves_dir() -> PathBuf {
+    current_dir().unwrap().join(SAVES_DIR)
+}
+
+fn get_save_file_name(slot: usize) -> String {
+    format!("run_{slot}.ron")
+}
+
+/// Read every save slot file in the saves directory
+fn load_save_files_system(mut saved_runs: ResMut<SavedRunsResource>) {
+    let Ok(entries) = read_dir(get_saves_dir()) else {
+        return;
+    };
+
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some(slot) = file_name
+            .strip_prefix("run_")
+            .and_then(|name| name.strip_suffix(".ron"))
+            .and_then(|slot| slot.parse::<usize>().ok())
+        else {
+            continue;
+        };
+
+        match read_to_string(entry.path()) {
+            Ok(run_data) => {
+                saved_runs.insert(slot, run_data);
+            }
+            Err(err) => error!("Failed to read save file {}: {}", file_name, err),
+        }
+    }
+}
+
+/// Write the save slots that changed, deleting the files of the ones that were cleared. Runs last
+/// so that runs saved while the game is closing are written.
+fn flush_changed_save_slots_to_files_system(
+    mut save_slot_changed_event_reader: EventReader<SaveSlotChangedEvent>,
+    saved_runs: Res<SavedRunsResource>,
+) {
+    for event in save_slot_changed_event_reader.read() {
+        let save_file_path = get_saves_dir().join(get_save_file_name(event.slot));
+
+        let result = match saved_runs.get(&event.slot) {
+            Some(run_data) => {
+                create_dir_all(get_saves_dir()).and_then(|_| write(&save_file_path, run_data))
+            }
+            None if save_file_path.exists() => remove_file(&save_file_path),
+            None => Ok(()),
+        };
+
+        if let Err(err) = result {
+            error!("Failed to write save slot {}: {}", event.slot, err);
+        }
+    }
+}
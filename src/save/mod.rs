@@ -0,0 +1,500 @@
+//! Exposes a plugin for saving the run in progress when the players quit, and resuming it from the
+//! main menu. Save slots are persisted by the storage plugin when the `storage` feature is enabled,
+//! and to files otherwise.
+use bevy::{app::AppExit, prelude::*};
+use ron::{de::from_str, ser::to_string};
+use serde::{Deserialize, Serialize};
+use thetawave_interface::{
+    abilities::{AbilityCooldownComponent, AbilitySlotIDComponent},
+    audio::ChangeBackgroundMusicEvent,
+    game::{
+        difficulty::{DifficultyLevel, SelectedDifficultyResource},
+        run_modifiers::SelectedRunModifiersResource,
+        saved_runs::{SaveSlotChangedEvent, SavedRunsResource, DEFAULT_SAVE_SLOT},
+    },
+    health::HealthComponent,
+    player::{PlayerComponent, PlayerIDComponent, PlayerInventoryComponent, PlayersResource},
+    states::AppStates,
+};
+
+use crate::{
+    game::{GameRngResource, GameRngState},
+    run::{
+        close_shop_system, daily_challenge_inactive, shop::ShopOffer, CurrentRunProgressResource,
+        ShopResource,
+    },
+    GameEnterSet,
+};
+
+#[cfg(all(not(feature = "storage"), not(target_arch = "wasm32")))]
+mod file;
+
+/// Everything needed to resume a run
+#[derive(Serialize, Deserialize)]
+struct RunSave {
+    /// Progress through the levels of the run. The current phase is saved in the state it started
+    /// in, so that the run resumes from the start of the phase.
+    run_progress: CurrentRunProgressResource,
+    players: PlayersResource,
+    player_states: Vec<PlayerSave>,
+    difficulty: DifficultyLevel,
+    /// Keys of the run modifiers the run is played with
+    #[serde(default)]
+    run_modifiers: Vec<String>,
+    /// Seed and random number streams of the run, so that it plays out the same after resuming
+    #[serde(default)]
+    rng: Option<GameRngState>,
+    /// Offers of the shop if the run was saved during a shop phase, so that they aren't rolled
+    /// again when resuming
+    #[serde(default)]
+    shop_offers: Vec<ShopOffer>,
+}
+
+/// State of a player's ship when the run was saved
+#[derive(Serialize, Deserialize, Clone)]
+struct PlayerSave {
+    player_id: PlayerIDComponent,
+    health: HealthComponent,
+    inventory: PlayerInventoryComponent,
+    ability_cooldowns: Vec<(AbilitySlotIDComponent, AbilityCooldownComponent)>,
+}
+
+/// States of the players of a resumed run, waiting to be applied once the players are spawned
+#[derive(Resource)]
+struct ResumedPlayersResource(Vec<PlayerSave>);
+
+/// Sent to resume the run saved in a slot
+#[derive(Event)]
+pub(crate) struct ResumeRunEvent {
+    pub slot: usize,
+}
+
+/// Saves the run in progress when the players quit to the main menu or close the game, and
+/// resumes saved runs. There is only one run in progress at a time, so starting a new run or
+/// finishing a run clears the save.
+pub(super) struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SavedRunsResource::default())
+            .add_event::<SaveSlotChangedEvent>()
+            .add_event::<ResumeRunEvent>();
+
+        // the state has already changed when exiting, so this only saves when going to the main
+        // menu from the pause menu. Daily challenges are never saved, quitting them loses them.
+        app.add_systems(
+            OnExit(AppStates::Game),
+            save_run_system
+                .before(close_shop_system)
+                .run_if(in_state(AppStates::MainMenu))
+                .run_if(daily_challenge_inactive),
+        );
+
+        // save before the game closes. Save slots are persisted in `Last`.
+        app.add_systems(
+            PostUpdate,
+            save_run_system
+                .run_if(in_state(AppStates::Game))
+                .run_if(on_event::<AppExit>())
+                .run_if(daily_challenge_inactive),
+        );
+
+        app.add_systems(
+            Update,
+            resume_run_system.run_if(in_state(AppStates::MainMenu)),
+        );
+
+        app.add_systems(
+            OnEnter(AppStates::Game),
+            restore_players_system
+                .after(GameEnterSet::SpawnPlayer)
+                .run_if(resource_exists::<ResumedPlayersResource>),
+        );
+
+        app.add_systems(OnEnter(AppStates::InitializeRun), clear_saved_run_system);
+        app.add_systems(OnEnter(AppStates::Victory), clear_saved_run_system);
+        app.add_systems(
+            OnTransition {
+                from: AppStates::GameOver,
+                to: AppStates::MainMenu,
+            },
+            clear_saved_run_system,
+        );
+
+        #[cfg(all(not(feature = "storage"), not(target_arch = "wasm32")))]
+        app.add_plugins(file::SaveFilesPlugin);
+    }
+}
+
+/// Serialize the run in progress into the default save slot
+#[allow(clippy::too_many_arguments)]
+fn save_run_system(
+    run_res: Res<CurrentRunProgressResource>,
+    players_res: Res<PlayersResource>,
+    selected_difficulty: Res<SelectedDifficultyResource>,
+    selected_run_modifiers: Res<SelectedRunModifiersResource>,
+    player_query: Query<
+        (
+            &PlayerIDComponent,
+            &HealthComponent,
+            &PlayerInventoryComponent,
+            &Children,
+        ),
+        With<PlayerComponent>,
+    >,
+    ability_query: Query<(&AbilitySlotIDComponent, &AbilityCooldownComponent)>,
+    shop: Res<ShopResource>,
+    mut game_rng: ResMut<GameRngResource>,
+    mut saved_runs: ResMut<SavedRunsResource>,
+    mut save_slot_changed_event_writer: EventWriter<SaveSlotChangedEvent>,
+) {
+    let mut run_progress = run_res.clone();
+    if let Some(current_level) = &mut run_progress.current_level {
+        current_level.restart_current_phase();
+    }
+
+    let player_states = player_query
+        .iter()
+        .map(|(player_id, health, inventory, children)| PlayerSave {
+            player_id: *player_id,
+            health: health.clone(),
+            inventory: inventory.clone(),
+            ability_cooldowns: ability_query
+                .iter_many(children.iter())
+                .map(|(slot, cooldown)| (*slot, cooldown.clone()))
+                .collect(),
+        })
+        .collect();
+
+    let run_save = RunSave {
+        run_progress,
+        players: players_res.clone(),
+        player_states,
+        difficulty: selected_difficulty.level,
+        run_modifiers: selected_run_modifiers.modifiers.clone(),
+        rng: Some(game_rng.save_state()),
+        shop_offers: shop.offers.clone(),
+    };
+
+    match to_string(&run_save) {
+        Ok(run_data) => {
+            saved_runs.insert(DEFAULT_SAVE_SLOT, run_data);
+            save_slot_changed_event_writer.send(SaveSlotChangedEvent {
+                slot: DEFAULT_SAVE_SLOT,
+            });
+            info!("Saved run to slot {}", DEFAULT_SAVE_SLOT);
+        }
+        Err(err) => error!("Failed to save run: {}", err),
+    }
+}
+
+/// Load a saved run and go straight to the game, skipping run initialization
+fn resume_run_system(
+    mut commands: Commands,
+    mut resume_run_event_reader: EventReader<ResumeRunEvent>,
+    saved_runs: Res<SavedRunsResource>,
+    mut next_app_state: ResMut<NextState<AppStates>>,
+    mut change_bg_music_event_writer: EventWriter<ChangeBackgroundMusicEvent>,
+) {
+    for event in resume_run_event_reader.read() {
+        let Some(run_data) = saved_runs.get(&event.slot) else {
+            info!("No run saved in slot {}", event.slot);
+            continue;
+        };
+
+        match from_str::<RunSave>(run_data) {
+            Ok(run_save) => {
+                let mut run_progress = run_save.run_progress;
+                if let Some(current_level) = &mut run_progress.current_level {
+                    current_level.init_phase(&mut change_bg_music_event_writer);
+                }
+
+                let mut shop = ShopResource::default();
+                if !run_save.shop_offers.is_empty() {
+                    shop.open(run_save.shop_offers, run_save.players.player_data.len());
+                }
+
+                commands.insert_resource(run_progress);
+                commands.insert_resource(shop);
+                commands.insert_resource(run_save.players);
+                commands.insert_resource(SelectedDifficultyResource {
+                    level: run_save.difficulty,
+                });
+                commands.insert_resource(SelectedRunModifiersResource {
+                    modifiers: run_save.run_modifiers,
+                });
+                if let Some(rng_state) = run_save.rng {
+                    commands.insert_resource(GameRngResource::from(rng_state));
+                }
+                commands.insert_resource(ResumedPlayersResource(run_save.player_states));
+                next_app_state.set(AppStates::Game);
+
+                info!("Resumed run from slot {}", event.slot);
+            }
+            Err(err) => error!("Failed to resume run from slot {}: {}", event.slot, err),
+        }
+    }
+}
+
+/// Put the players of a resumed run back in the state they were saved in
+fn restore_players_system(
+    mut commands: Commands,
+    resumed_players: Res<ResumedPlayersResource>,
+    mut player_query: Query<
+        (
+            &PlayerIDComponent,
+            &mut HealthComponent,
+            &mut PlayerInventoryComponent,
+            &Children,
+        ),
+        With<PlayerComponent>,
+    >,
+    mut ability_query: Query<(&AbilitySlotIDComponent, &mut AbilityCooldownComponent)>,
+) {
+    for (player_id, mut health, mut inventory, children) in player_query.iter_mut() {
+        let Some(player_save) = resumed_players
+            .0
+            .iter()
+            .find(|player_save| player_save.player_id == *player_id)
+        else {
+            continue;
+        };
+
+        *health = player_save.health.clone();
+        *inventory = player_save.inventory.clone();
+
+        let mut abilities = ability_query.iter_many_mut(children.iter());
+        while let Some((slot, mut cooldown)) = abilities.fetch_next() {
+            if let Some((_, saved_cooldown)) = player_save
+                .ability_cooldowns
+                .iter()
+                .find(|(saved_slot, _)| saved_slot == slot)
+            {
+                *cooldown = saved_cooldown.clone();
+            }
+        }
+    }
+
+    commands.remove_resource::<ResumedPlayersResource>();
+}
+
+/// Clear the default save slot, so that a run that was started over or finished can't be resumed
+fn clear_saved_run_system(
+    mut saved_runs: ResMut<SavedRunsResource>,
+    mut save_slot_changed_event_writer: EventWriter<SaveSlotChangedEvent>,
+) {
+    if saved_runs.remove(&DEFAULT_SAVE_SLOT).is_some() {
+        save_slot_changed_event_writer.send(SaveSlotChangedEvent {
+            slot: DEFAULT_SAVE_SLOT,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::{ecs::system::RunSystemOnce, prelude::*};
+    use rand::Rng;
+    use std::time::Duration;
+    use thetawave_interface::{
+        abilities::{AbilityCooldownComponent, AbilitySlotIDComponent},
+        audio::ChangeBackgroundMusicEvent,
+        game::{
+            difficulty::{DifficultyLevel, SelectedDifficultyResource},
+            run_modifiers::SelectedRunModifiersResource,
+            saved_runs::{SaveSlotChangedEvent, SavedRunsResource, DEFAULT_SAVE_SLOT},
+        },
+        health::HealthComponent,
+        player::{PlayerComponent, PlayerIDComponent, PlayerInventoryComponent, PlayersResource},
+        spawnable::{ConsumableType, ItemType},
+        states::AppStates,
+    };
+
+    use super::{
+        restore_players_system, resume_run_system, save_run_system, ResumeRunEvent,
+        ResumedPlayersResource,
+    };
+    use crate::{
+        game::{GameRngResource, RngStream},
+        run::{
+            shop::{ShopItem, ShopOffer},
+            test_run_progress, CurrentRunProgressResource, ShopResource,
+        },
+    };
+
+    /// Spawn a freshly spawned player with an ability on cooldown
+    fn spawn_player(world: &mut World) -> Entity {
+        let ability = world
+            .spawn((
+                AbilitySlotIDComponent::One,
+                AbilityCooldownComponent::new(10.0),
+            ))
+            .id();
+
+        world
+            .spawn((
+                PlayerComponent,
+                PlayerIDComponent::One,
+                HealthComponent::new(100, 0, 1.0),
+                PlayerInventoryComponent { money: 0 },
+            ))
+            .add_child(ability)
+            .id()
+    }
+
+    /// Index of the current level in the run and of the current phase in the level
+    fn get_level_and_phase_idx(run_res: &CurrentRunProgressResource) -> (usize, usize) {
+        (
+            run_res.completed_levels.len(),
+            run_res
+                .current_level
+                .as_ref()
+                .map_or(0, |level| level.completed_phases.len()),
+        )
+    }
+
+    #[test]
+    fn test_saved_run_is_resumed() {
+        let mut world = World::new();
+        world.init_resource::<Events<SaveSlotChangedEvent>>();
+        world.init_resource::<Events<ResumeRunEvent>>();
+        world.init_resource::<Events<ChangeBackgroundMusicEvent>>();
+        world.init_resource::<NextState<AppStates>>();
+        world.insert_resource(SavedRunsResource::default());
+        world.insert_resource(PlayersResource::default());
+        world.insert_resource(SelectedRunModifiersResource::default());
+        world.insert_resource(SelectedDifficultyResource {
+            level: DifficultyLevel::Hard,
+        });
+        world.insert_resource(test_run_progress());
+        world.insert_resource(ShopResource::default());
+        world.insert_resource(GameRngResource::new(7));
+        world
+            .resource_mut::<GameRngResource>()
+            .stream(RngStream::Loot)
+            .gen::<u64>();
+
+        // play a bit of the run, then save it
+        let player = spawn_player(&mut world);
+        world
+            .get_mut::<HealthComponent>(player)
+            .unwrap()
+            .take_damage(30);
+        world
+            .get_mut::<PlayerInventoryComponent>(player)
+            .unwrap()
+            .money = 12;
+        let ability = world.get::<Children>(player).unwrap()[0];
+        world
+            .get_mut::<AbilityCooldownComponent>(ability)
+            .unwrap()
+            .cooldown_timer
+            .tick(Duration::from_secs(4));
+        let saved_position =
+            get_level_and_phase_idx(world.resource::<CurrentRunProgressResource>());
+        assert_eq!(saved_position, (1, 1));
+
+        world.run_system_once(save_run_system);
+        assert!(world
+            .resource::<SavedRunsResource>()
+            .contains_key(&DEFAULT_SAVE_SLOT));
+        let next_roll: u64 = world
+            .resource_mut::<GameRngResource>()
+            .stream(RngStream::Loot)
+            .gen();
+
+        // start over as if the game was restarted, then resume the run
+        world.despawn(ability);
+        world.despawn(player);
+        world.insert_resource(CurrentRunProgressResource::default());
+        world.insert_resource(SelectedDifficultyResource::default());
+        world.insert_resource(GameRngResource::new(0));
+        world.send_event(ResumeRunEvent {
+            slot: DEFAULT_SAVE_SLOT,
+        });
+        world.run_system_once(resume_run_system);
+
+        assert_eq!(
+            world.resource::<NextState<AppStates>>().0,
+            Some(AppStates::Game)
+        );
+        assert_eq!(
+            get_level_and_phase_idx(world.resource::<CurrentRunProgressResource>()),
+            saved_position
+        );
+        assert_eq!(
+            world.resource::<SelectedDifficultyResource>().level,
+            DifficultyLevel::Hard
+        );
+        let resumed_roll: u64 = world
+            .resource_mut::<GameRngResource>()
+            .stream(RngStream::Loot)
+            .gen();
+        assert_eq!(resumed_roll, next_roll);
+
+        // the players are spawned again when entering the game, then restored
+        let player = spawn_player(&mut world);
+        world.run_system_once(restore_players_system);
+
+        assert_eq!(
+            world.get::<HealthComponent>(player).unwrap().get_health(),
+            70
+        );
+        assert_eq!(
+            world.get::<PlayerInventoryComponent>(player).unwrap().money,
+            12
+        );
+        let ability = world.get::<Children>(player).unwrap()[0];
+        assert_eq!(
+            world
+                .get::<AbilityCooldownComponent>(ability)
+                .unwrap()
+                .cooldown_timer
+                .elapsed_secs(),
+            4.0
+        );
+        assert!(!world.contains_resource::<ResumedPlayersResource>());
+    }
+
+    #[test]
+    fn test_shop_offers_are_kept_when_resuming() {
+        let mut world = World::new();
+        world.init_resource::<Events<SaveSlotChangedEvent>>();
+        world.init_resource::<Events<ResumeRunEvent>>();
+        world.init_resource::<Events<ChangeBackgroundMusicEvent>>();
+        world.init_resource::<NextState<AppStates>>();
+        world.insert_resource(SavedRunsResource::default());
+        world.insert_resource(PlayersResource::default());
+        world.insert_resource(SelectedRunModifiersResource::default());
+        world.insert_resource(SelectedDifficultyResource::default());
+        world.insert_resource(test_run_progress());
+        world.insert_resource(GameRngResource::new(7));
+
+        // one of the offers was already bought when the run was saved
+        let offers = vec![
+            ShopOffer {
+                item: ShopItem::Item(ItemType::EnhancedPlating),
+                price: 10,
+                sold: true,
+            },
+            ShopOffer {
+                item: ShopItem::Consumable(ConsumableType::Armor),
+                price: 5,
+                sold: false,
+            },
+        ];
+        let mut shop = ShopResource::default();
+        shop.open(offers.clone(), 1);
+        world.insert_resource(shop);
+
+        world.run_system_once(save_run_system);
+
+        world.insert_resource(ShopResource::default());
+        world.send_event(ResumeRunEvent {
+            slot: DEFAULT_SAVE_SLOT,
+        });
+        world.run_system_once(resume_run_system);
+
+        assert!(world.resource::<ShopResource>().is_open());
+        assert_eq!(world.resource::<ShopResource>().offers, offers);
+    }
+}
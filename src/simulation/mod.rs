@@ -0,0 +1,474 @@
+//! Exposes a headless runner that plays a run faster than real time with scripted players, and
+//! reports how every phase went. Designers can use it to compare the difficulty of the data files
+//! before and after a change without playing by hand.
+use bevy::{
+    app::{App, PluginGroup, PluginGroupBuilder},
+    asset::{AssetApp, AssetPlugin},
+    hierarchy::HierarchyPlugin,
+    input::InputPlugin,
+    log::{Level, LogPlugin},
+    prelude::*,
+    sprite::TextureAtlasLayout,
+    text::{Font, FontLoader},
+    time::TimeUpdateStrategy,
+    transform::TransformPlugin,
+    MinimalPlugins,
+};
+use leafwing_input_manager::{action_state::ActionState, plugin::InputManagerSystem};
+use std::{fmt::Display, time::Duration};
+use strum_macros::EnumString;
+use thetawave_interface::{
+    audio::{ChangeBackgroundMusicEvent, PlaySoundEffectEvent},
+    character::CharacterType,
+    game::options::GameOptions,
+    input::PlayerAction,
+    objective::Objective,
+    player::{PlayerComponent, PlayerData, PlayerInput, PlayersResource},
+    run::CyclePhaseEvent,
+    spawnable::MobDestroyedEvent,
+    states::{AppStates, GameStates},
+};
+use thiserror::Error;
+
+use crate::{
+    animation::SpriteAnimationPlugin,
+    audio::ThetawaveAudioPlugin,
+    background::BackgroundPlugin,
+    build_app,
+    options::{get_inputs_resource, GameInitCLIOptions, OptionsPlugin},
+    our_game_plugins,
+    run::CurrentRunProgressResource,
+    spawnable::MobComponent,
+    ui::UiPlugin,
+};
+
+/// Game time advanced by every update, and by every step of the fixed timestep
+const SIMULATION_TIMESTEP: f64 = 1.0 / 60.0;
+/// Game time after which a simulated run is stopped if it didn't end
+const DEFAULT_TIME_LIMIT_SECS: u64 = 1800;
+/// Updates to wait for the assets to load before giving up
+const MAX_LOADING_UPDATES: usize = 10_000;
+/// Seconds the sweeping players take to move across the arena
+const SWEEP_SECS: f32 = 3.0;
+
+/// How the players of a simulated run play
+#[derive(Resource, EnumString, Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[strum(serialize_all = "kebab-case", ascii_case_insensitive)]
+pub enum SimulationPolicy {
+    /// Don't move or use abilities, to measure how much the defenses hold without the players
+    #[default]
+    StandStill,
+    /// Stay at the spawn position and keep using the slot one ability
+    Fire,
+    /// Move from one side of the arena to the other while using the slot one ability
+    Sweep,
+}
+
+impl SimulationPolicy {
+    /// Actions the players hold after some seconds of game time
+    fn get_actions(&self, elapsed_secs: f32) -> Vec<PlayerAction> {
+        match self {
+            SimulationPolicy::StandStill => vec![],
+            SimulationPolicy::Fire => vec![PlayerAction::SlotOneAbility],
+            SimulationPolicy::Sweep => {
+                // start by moving from the center to the left side, then go back and forth
+                let movement = if (elapsed_secs / SWEEP_SECS + 0.5) % 2.0 < 1.0 {
+                    PlayerAction::MoveLeft
+                } else {
+                    PlayerAction::MoveRight
+                };
+                vec![movement, PlayerAction::SlotOneAbility]
+            }
+        }
+    }
+}
+
+/// What happened during one phase of a simulated run
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(super) struct PhaseStats {
+    pub level: String,
+    pub phase: String,
+    pub mobs_spawned: usize,
+    pub mobs_killed: usize,
+    /// Damage dealt to the defense objective, scaled by the difficulty
+    pub gate_damage: usize,
+    /// Game time the phase was played for
+    pub duration: Duration,
+}
+
+/// How a simulated run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum SimulationOutcome {
+    Victory,
+    GameOver,
+    TimeLimitReached,
+}
+
+/// Stats of every phase played in a simulated run
+pub(super) struct SimulationReport {
+    pub outcome: SimulationOutcome,
+    pub phases: Vec<PhaseStats>,
+    /// Game time the run was played for
+    pub duration: Duration,
+}
+
+impl Display for SimulationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<32} {:<24} {:>8} {:>8} {:>12} {:>10}",
+            "level", "phase", "spawned", "killed", "gate damage", "seconds"
+        )?;
+        for phase in self.phases.iter() {
+            writeln!(
+                f,
+                "{:<32} {:<24} {:>8} {:>8} {:>12} {:>10.1}",
+                phase.level,
+                phase.phase,
+                phase.mobs_spawned,
+                phase.mobs_killed,
+                phase.gate_damage,
+                phase.duration.as_secs_f32()
+            )?;
+        }
+        write!(
+            f,
+            "Outcome: {:?} after {:.1} seconds",
+            self.outcome,
+            self.duration.as_secs_f32()
+        )
+    }
+}
+
+#[derive(Error, Debug)]
+pub(super) enum SimulationError {
+    #[error("Assets did not finish loading after {0} updates")]
+    AssetsNotLoaded(usize),
+    #[error("The run could not be started, see the log for why")]
+    RunNotStarted,
+}
+
+/// Records the stats of the phase being played
+#[derive(Resource, Default)]
+struct SimulationStatsResource {
+    completed_phases: Vec<PhaseStats>,
+    current_phase: Option<PhaseStats>,
+    /// Game time when the current phase started
+    phase_start: Duration,
+    /// Index of the level being played and the defense it had left at the last update, if the
+    /// level has a defense objective
+    defense: Option<(usize, usize)>,
+}
+
+impl SimulationStatsResource {
+    /// Finish recording the current phase and start recording a new one
+    fn start_phase(&mut self, level: String, phase: String, now: Duration) {
+        self.finish_phase(now);
+        self.current_phase = Some(PhaseStats {
+            level,
+            phase,
+            ..default()
+        });
+        self.phase_start = now;
+    }
+
+    fn finish_phase(&mut self, now: Duration) {
+        if let Some(mut phase) = self.current_phase.take() {
+            phase.duration = now.saturating_sub(self.phase_start);
+            self.completed_phases.push(phase);
+        }
+    }
+}
+
+/// Drives the players with a policy and records the stats of every phase
+struct SimulationPlugin {
+    policy: SimulationPolicy,
+}
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.policy)
+            .insert_resource(SimulationStatsResource::default());
+
+        // press actions after the input manager has released the ones without any input
+        app.add_systems(
+            PreUpdate,
+            scripted_players_system
+                .in_set(InputManagerSystem::ManualControl)
+                .run_if(in_state(AppStates::Game)),
+        );
+
+        app.add_systems(Update, (record_phase_system, record_mobs_system).chain());
+    }
+}
+
+/// Hold the actions of the policy for every player
+fn scripted_players_system(
+    policy: Res<SimulationPolicy>,
+    time: Res<Time>,
+    mut player_query: Query<&mut ActionState<PlayerAction>, With<PlayerComponent>>,
+) {
+    let actions = policy.get_actions(time.elapsed_seconds());
+
+    for mut action_state in player_query.iter_mut() {
+        for action in actions.iter() {
+            action_state.press(action);
+        }
+    }
+}
+
+/// Start recording a new phase whenever the run moves to another phase
+fn record_phase_system(
+    mut cycle_phase_event_reader: EventReader<CyclePhaseEvent>,
+    run_res: Res<CurrentRunProgressResource>,
+    time: Res<Time>,
+    mut stats: ResMut<SimulationStatsResource>,
+) {
+    // phases can be cycled more than once in an update, only the last phase is still being played
+    if cycle_phase_event_reader.read().count() == 0 {
+        return;
+    }
+
+    if let Some(current_level) = &run_res.current_level {
+        let phase = current_level
+            .current_phase
+            .as_ref()
+            .map(|phase| phase.phase_type.get_name())
+            .unwrap_or_default();
+        stats.start_phase(current_level.name.clone(), phase, time.elapsed());
+    }
+}
+
+/// Count the mobs spawned and killed, and the damage dealt to the defense objective
+fn record_mobs_system(
+    mob_query: Query<(), Added<MobComponent>>,
+    mut mob_destroyed_event_reader: EventReader<MobDestroyedEvent>,
+    run_res: Res<CurrentRunProgressResource>,
+    mut stats: ResMut<SimulationStatsResource>,
+) {
+    // read the defense from the objective, which has already scaled the damage by the difficulty
+    let defense = match run_res
+        .current_level
+        .as_ref()
+        .and_then(|level| level.objective.as_ref())
+    {
+        Some(Objective::Defense(defense_data)) => {
+            Some((run_res.completed_levels.len(), defense_data.defense))
+        }
+        _ => None,
+    };
+    let previous_defense = std::mem::replace(&mut stats.defense, defense);
+
+    let Some(phase) = &mut stats.current_phase else {
+        return;
+    };
+
+    phase.mobs_spawned += mob_query.iter().count();
+    phase.mobs_killed += mob_destroyed_event_reader.read().count();
+
+    // every level starts with a new objective, so only compare the defense within a level
+    if let (Some((previous_level_idx, previous_defense)), Some((level_idx, defense))) =
+        (previous_defense, defense)
+    {
+        if previous_level_idx == level_idx {
+            phase.gate_damage += previous_defense.saturating_sub(defense);
+        }
+    }
+}
+
+/// The engine plugins needed to play without a window, a renderer or audio output
+fn simulation_base_plugins() -> PluginGroupBuilder {
+    MinimalPlugins
+        .build()
+        .add(LogPlugin {
+            level: Level::WARN,
+            ..default()
+        })
+        .add(AssetPlugin::default())
+        .add(ImagePlugin::default())
+        .add(InputPlugin)
+        .add(TransformPlugin)
+        .add(HierarchyPlugin)
+}
+
+/// Play the run selected by the options until it ends or reaches the time limit
+pub(super) fn run_simulation(
+    opts: &GameInitCLIOptions,
+) -> Result<SimulationReport, SimulationError> {
+    let time_limit = Duration::from_secs(
+        opts.simulation_time_limit
+            .unwrap_or(DEFAULT_TIME_LIMIT_SECS),
+    );
+
+    // drop the plugins that draw, play sounds or only handle menus
+    let game_plugins = our_game_plugins(opts)
+        .disable::<UiPlugin>()
+        .disable::<OptionsPlugin>()
+        .disable::<ThetawaveAudioPlugin>()
+        .disable::<SpriteAnimationPlugin>()
+        .disable::<BackgroundPlugin>();
+
+    let mut app = build_app(simulation_base_plugins(), game_plugins);
+
+    // assets and resources that are otherwise added by the rendering, text and options plugins
+    app.init_asset::<TextureAtlasLayout>()
+        .init_asset::<Font>()
+        .init_asset_loader::<FontLoader>()
+        .add_event::<ChangeBackgroundMusicEvent>()
+        .add_event::<PlaySoundEffectEvent>()
+        .insert_resource(GameOptions::default())
+        .insert_resource(get_inputs_resource());
+
+    // advance game time by exactly one fixed timestep per update, as fast as the updates can run
+    app.insert_resource(Time::<Fixed>::from_seconds(SIMULATION_TIMESTEP))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            SIMULATION_TIMESTEP,
+        )))
+        .add_plugins(SimulationPlugin {
+            policy: opts.simulation_policy.unwrap_or_default(),
+        });
+
+    app.finish();
+    app.cleanup();
+
+    // the loading state goes to the main menu once every asset is loaded
+    let mut loading_updates = 0;
+    while get_app_state(&app) != AppStates::MainMenu {
+        if loading_updates == MAX_LOADING_UPDATES {
+            return Err(SimulationError::AssetsNotLoaded(MAX_LOADING_UPDATES));
+        }
+        app.update();
+        loading_updates += 1;
+    }
+
+    // a single player starts the run without tutorials, as if chosen on the character selection
+    // screen
+    let mut players = PlayersResource::default();
+    players.player_data[0] = Some(PlayerData {
+        character: CharacterType::Captain,
+        input: PlayerInput::Keyboard,
+    });
+    app.insert_resource(players);
+    app.world
+        .resource_mut::<CurrentRunProgressResource>()
+        .tutorials_on = false;
+    app.world
+        .resource_mut::<NextState<AppStates>>()
+        .set(AppStates::InitializeRun);
+    app.world
+        .resource_mut::<NextState<GameStates>>()
+        .set(GameStates::Playing);
+
+    let run_start = app.world.resource::<Time>().elapsed();
+    let outcome = loop {
+        app.update();
+
+        match get_app_state(&app) {
+            AppStates::Victory => break SimulationOutcome::Victory,
+            AppStates::GameOver => break SimulationOutcome::GameOver,
+            // the run goes back to the main menu if it can't be generated
+            AppStates::MainMenu => return Err(SimulationError::RunNotStarted),
+            _ => {}
+        }
+
+        if app.world.resource::<Time>().elapsed() - run_start >= time_limit {
+            break SimulationOutcome::TimeLimitReached;
+        }
+    };
+
+    let now = app.world.resource::<Time>().elapsed();
+    let mut stats = app.world.resource_mut::<SimulationStatsResource>();
+    stats.finish_phase(now);
+
+    Ok(SimulationReport {
+        outcome,
+        phases: std::mem::take(&mut stats.completed_phases),
+        duration: now - run_start,
+    })
+}
+
+/// The current state of the app, copied so that the app can be updated
+fn get_app_state(app: &App) -> AppStates {
+    app.world.resource::<State<AppStates>>().get().clone()
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{
+        run_simulation, PhaseStats, SimulationOutcome, SimulationPolicy, SimulationStatsResource,
+    };
+    use crate::options::GameInitCLIOptions;
+
+    #[test]
+    fn test_phase_stats_are_split_at_phase_changes() {
+        let mut stats = SimulationStatsResource::default();
+
+        stats.start_phase("Level 1".to_string(), "Break".to_string(), Duration::ZERO);
+        stats.current_phase.as_mut().unwrap().mobs_spawned += 3;
+        stats.start_phase(
+            "Level 1".to_string(),
+            "Formation".to_string(),
+            Duration::from_secs(5),
+        );
+        stats.current_phase.as_mut().unwrap().mobs_killed += 2;
+        stats.finish_phase(Duration::from_secs(12));
+
+        assert_eq!(
+            stats.completed_phases,
+            vec![
+                PhaseStats {
+                    level: "Level 1".to_string(),
+                    phase: "Break".to_string(),
+                    mobs_spawned: 3,
+                    duration: Duration::from_secs(5),
+                    ..Default::default()
+                },
+                PhaseStats {
+                    level: "Level 1".to_string(),
+                    phase: "Formation".to_string(),
+                    mobs_killed: 2,
+                    duration: Duration::from_secs(7),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_policy_is_parsed_from_kebab_case() {
+        assert_eq!(
+            "stand-still".parse::<SimulationPolicy>(),
+            Ok(SimulationPolicy::StandStill)
+        );
+        assert_eq!(
+            "Sweep".parse::<SimulationPolicy>(),
+            Ok(SimulationPolicy::Sweep)
+        );
+    }
+
+    #[test]
+    fn test_simulation_records_the_phases_played() {
+        let opts = GameInitCLIOptions {
+            run: Some("test_run".to_string()),
+            seed: Some(0),
+            simulate: true,
+            simulation_policy: Some(SimulationPolicy::Fire),
+            simulation_time_limit: Some(30),
+            ..Default::default()
+        };
+
+        let report = run_simulation(&opts).unwrap();
+
+        // the first phase of the first level is a 20 second formation phase
+        assert_ne!(report.outcome, SimulationOutcome::Victory);
+        assert!(report.phases.len() >= 2);
+        let first_phase = &report.phases[0];
+        assert_eq!(first_phase.level, "Test Level I");
+        assert_eq!(first_phase.phase, "Formation Invasion");
+        assert!(first_phase.mobs_spawned > 0);
+        assert!((first_phase.duration.as_secs_f32() - 20.0).abs() < 0.1);
+        assert!(report.duration <= Duration::from_secs_f32(30.1));
+    }
+}
@@ -1,16 +1,21 @@
 use crate::{
-    collision::SortedCollisionEvent, game::GameParametersResource, spawnable::SpawnableComponent,
+    collision::SortedCollisionEvent,
+    game::{GameParametersResource, GameRngResource, RngStream},
+    spawnable::{
+        movement::{DashData, OrbitData, SineWaveData, SpawnableParentComponent, WaypointPathData},
+        MobComponent, SpawnableComponent, TargetCandidate, TargetingComponent,
+    },
+    status_effect::{is_stunned, StatusEffectsComponent},
     tools::signed_modulo,
 };
-use bevy::prelude::{Entity, EventReader, Query, Res, Transform, Vec2, Vec3Swizzles, With};
+use bevy::prelude::{
+    Entity, EventReader, Query, Res, ResMut, Time, Transform, Vec2, Vec3Swizzles, With,
+};
 use bevy_rapier2d::prelude::Velocity;
 use serde::Deserialize;
 use thetawave_interface::player::PlayerAttractionComponent;
 use thetawave_interface::spawnable::AttractToClosestPlayerComponent;
-use thetawave_interface::{
-    player::PlayerComponent,
-    spawnable::{EnemyMobType, MobType, SpawnableType},
-};
+use thetawave_interface::{health::HealthComponent, player::PlayerComponent, spawnable::MobType};
 
 /// Types of behaviors that can be performed by spawnables
 #[derive(Deserialize, Clone, PartialEq)]
@@ -24,12 +29,38 @@ pub enum SpawnableBehavior {
     ChangeHorizontalDirectionOnImpact,
     MoveToPosition(Vec2),
     AttractToPlayer,
+    SineWave(SineWaveData),
+    WaypointPath(WaypointPathData),
+    Orbit(OrbitData),
+    Dash(DashData),
 }
 
-/// Manages excuting behaviors of spawnables
+impl SpawnableBehavior {
+    /// Returns true if any of the behaviors need a target to rotate or dash toward
+    pub fn needs_target(behaviors: &[SpawnableBehavior]) -> bool {
+        behaviors.iter().any(|behavior| {
+            matches!(
+                behavior,
+                SpawnableBehavior::RotateToTarget(_) | SpawnableBehavior::Dash(_)
+            )
+        })
+    }
+}
+
+/// Manages excuting behaviors of spawnables. Stunned spawnables don't perform their behaviors.
 pub fn spawnable_execute_behavior_system(
     game_parameters: Res<GameParametersResource>,
-    mut spawnable_query: Query<(Entity, &mut SpawnableComponent, &mut Velocity, &Transform)>,
+    time: Res<Time>,
+    mut spawnable_query: Query<(
+        Entity,
+        &mut SpawnableComponent,
+        &mut Velocity,
+        &Transform,
+        Option<&TargetingComponent>,
+        Option<&SpawnableParentComponent>,
+        Option<&StatusEffectsComponent>,
+    )>,
+    parent_query: Query<&Transform>,
     mut collision_events: EventReader<SortedCollisionEvent>,
 ) {
     let mut collision_events_vec = vec![];
@@ -37,12 +68,26 @@ pub fn spawnable_execute_behavior_system(
         collision_events_vec.push(collision_event);
     }
 
+    let delta = time.delta_seconds();
+
     // Iterate through all spawnable entities and execute their behavior
-    for (spawnable_entity, mut spawnable_component, mut rb_vel, spawnable_transform) in
-        spawnable_query.iter_mut()
+    for (
+        spawnable_entity,
+        mut spawnable_component,
+        mut rb_vel,
+        spawnable_transform,
+        targeting,
+        parent,
+        status_effects,
+    ) in spawnable_query.iter_mut()
     {
+        if is_stunned(status_effects) {
+            continue;
+        }
+
+        let position = spawnable_transform.translation.xy();
         let behaviors = spawnable_component.behaviors.clone();
-        for behavior in behaviors {
+        for (behavior_idx, behavior) in behaviors.into_iter().enumerate() {
             match behavior {
                 SpawnableBehavior::MoveDown => {
                     move_down(&spawnable_component, &mut rb_vel);
@@ -77,62 +122,91 @@ pub fn spawnable_execute_behavior_system(
                         &mut spawnable_component,
                     );
                 }
+                // the behaviors below keep their state between frames, so it is stored back in
+                // the spawnable after each update
+                SpawnableBehavior::SineWave(mut sine_wave) => {
+                    rb_vel.linvel += sine_wave.update(rb_vel.linvel, delta);
+                    spawnable_component.behaviors[behavior_idx] =
+                        SpawnableBehavior::SineWave(sine_wave);
+                }
+                SpawnableBehavior::WaypointPath(mut waypoint_path) => {
+                    if let Some(target) = waypoint_path.get_target(position) {
+                        move_to_position(
+                            spawnable_transform,
+                            &spawnable_component,
+                            &mut rb_vel,
+                            target,
+                        );
+                    }
+                    spawnable_component.behaviors[behavior_idx] =
+                        SpawnableBehavior::WaypointPath(waypoint_path);
+                }
+                SpawnableBehavior::Orbit(mut orbit) => {
+                    let parent_position = parent
+                        .and_then(|parent| parent_query.get(parent.0).ok())
+                        .map(|parent_transform| parent_transform.translation.xy());
+                    if let Some(velocity) = orbit.get_velocity(
+                        position,
+                        parent_position,
+                        delta,
+                        spawnable_component.speed.max_element(),
+                    ) {
+                        rb_vel.linvel = velocity;
+                    }
+                    spawnable_component.behaviors[behavior_idx] = SpawnableBehavior::Orbit(orbit);
+                }
+                SpawnableBehavior::Dash(mut dash) => {
+                    let target = targeting.and_then(|targeting| targeting.target_position);
+                    if let Some(velocity) = dash.update(position, target, delta) {
+                        rb_vel.linvel = velocity;
+                    }
+                    spawnable_component.behaviors[behavior_idx] = SpawnableBehavior::Dash(dash);
+                }
                 _ => {}
             }
         }
     }
 }
 
-/// Manages setting targeting of spawnables
+/// Picks the targets of spawnables with their targeting strategies, and points their
+/// `RotateToTarget` behaviors at them
 pub fn spawnable_set_target_behavior_system(
-    player_query: Query<&Transform, With<PlayerComponent>>,
-    mut spawnable_query: Query<(&mut SpawnableComponent, &Transform)>,
+    player_query: Query<(Entity, &Transform, &HealthComponent), With<PlayerComponent>>,
+    mob_query: Query<(Entity, &Transform, &MobComponent, &HealthComponent)>,
+    mut spawnable_query: Query<(&mut SpawnableComponent, &mut TargetingComponent, &Transform)>,
+    mut game_rng: ResMut<GameRngResource>,
 ) {
-    // Sets targetting to None
-    for (mut spawnable_component, _) in spawnable_query.iter_mut() {
-        for behavior in spawnable_component.behaviors.iter_mut() {
-            if let SpawnableBehavior::RotateToTarget(_) = behavior {
-                *behavior = SpawnableBehavior::RotateToTarget(None);
-            }
-        }
-    }
+    let players: Vec<TargetCandidate> = player_query
+        .iter()
+        .map(|(entity, transform, health)| TargetCandidate {
+            entity,
+            position: transform.translation.xy(),
+            health: health.get_health(),
+        })
+        .collect();
 
-    // Recalculates what the target should be
-    for player_transform in player_query.iter() {
-        for (mut spawnable_component, spawnable_transform) in spawnable_query.iter_mut() {
-            match &spawnable_component.spawnable_type {
-                SpawnableType::Mob(mob_type) => match mob_type {
-                    MobType::Enemy(enemy_type) => match enemy_type {
-                        EnemyMobType::Missile => {
-                            // set target to closest player
-                            for behavior in spawnable_component.behaviors.iter_mut() {
-                                *behavior = match behavior {
-                                    SpawnableBehavior::RotateToTarget(target) => {
-                                        let spawnable_position_vec2: Vec2 =
-                                            spawnable_transform.translation.xy();
-                                        let player_position_vec2: Vec2 =
-                                            player_transform.translation.xy();
-                                        if target.is_none()
-                                            || spawnable_position_vec2
-                                                .distance(player_position_vec2)
-                                                < spawnable_position_vec2.distance(target.unwrap())
-                                        {
-                                            SpawnableBehavior::RotateToTarget(Some(
-                                                player_position_vec2,
-                                            ))
-                                        } else {
-                                            behavior.clone()
-                                        }
-                                    }
-                                    _ => behavior.clone(),
-                                }
-                            }
-                        }
-                        _ => {}
-                    },
-                    _ => {}
-                },
-                _ => {}
+    let ally_mobs: Vec<TargetCandidate> = mob_query
+        .iter()
+        .filter(|(_, _, mob, _)| matches!(mob.mob_type, MobType::Ally(_)))
+        .map(|(entity, transform, _, health)| TargetCandidate {
+            entity,
+            position: transform.translation.xy(),
+            health: health.get_health(),
+        })
+        .collect();
+
+    for (mut spawnable_component, mut targeting, spawnable_transform) in spawnable_query.iter_mut()
+    {
+        targeting.update_target(
+            spawnable_transform.translation.xy(),
+            &players,
+            &ally_mobs,
+            game_rng.stream(RngStream::Targeting),
+        );
+
+        for behavior in spawnable_component.behaviors.iter_mut() {
+            if let SpawnableBehavior::RotateToTarget(target) = behavior {
+                *target = targeting.target_position;
             }
         }
     }
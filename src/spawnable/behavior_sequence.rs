@@ -1,23 +1,119 @@
+//! Behavior sequences, steps of behaviors that mobs go through. Steps move on when their time runs
+//! out, or earlier when one of their transition conditions is met, so any mob can be given stages
+//! like a boss's from data alone.
 use super::{
-    mob::BehaviorSequenceTracker, MobBehavior, MobComponent, MobSegmentComponent,
-    MobSegmentControlBehavior, SpawnableBehavior, SpawnableComponent,
+    MobBehavior, MobComponent, MobSegmentComponent, MobSegmentControlBehavior, MobsResource,
+    SpawnableBehavior, SpawnableComponent, TargetingComponent,
 };
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 use serde::Deserialize;
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+use thetawave_interface::{health::HealthComponent, player::PlayerComponent};
+
+/// Key of a behavior sequence in `behavior_sequences.ron`
+pub type MobBehaviorSequenceType = String;
 
 #[derive(Deserialize, Clone)]
 pub struct MobBehaviorSequence {
     pub behaviors: Vec<MobBehaviorSequenceElement>,
 }
 
+impl MobBehaviorSequence {
+    /// Index of the step that follows the step at `index` when its time runs out, wrapping
+    /// around and skipping one-shot steps that were already performed
+    pub fn get_next_index(&self, index: usize, performed_once: &HashSet<usize>) -> usize {
+        let len = self.behaviors.len();
+        (1..=len)
+            .map(|offset| (index + offset) % len)
+            .find(|next_index| {
+                !(self.behaviors[*next_index].once && performed_once.contains(next_index))
+            })
+            .unwrap_or(index)
+    }
+}
+
+/// A step of a behavior sequence
 #[derive(Deserialize, Clone)]
 pub struct MobBehaviorSequenceElement {
     pub spawnable_behaviors: Vec<SpawnableBehavior>,
     pub mob_behaviors: Vec<MobBehavior>,
     pub control_behaviors: Vec<MobSegmentControlBehavior>,
-    pub time: f32,
+    /// Seconds before moving on to the next step. Steps without a time only move on through
+    /// their transitions.
+    #[serde(default)]
+    pub time: Option<f32>,
+    /// Steps to go to when a condition is met, checked in order before the time runs out
+    #[serde(default)]
+    pub transitions: Vec<BehaviorSequenceTransition>,
+    /// Step is only performed the first time it is reached by time, and skipped afterwards
+    #[serde(default)]
+    pub once: bool,
+    /// Sequence stays at this step for good once it is reached
+    #[serde(default)]
+    pub terminal: bool,
+}
+
+impl MobBehaviorSequenceElement {
+    fn get_update_event(&self, entity: Entity) -> MobBehaviorUpdateEvent {
+        MobBehaviorUpdateEvent {
+            mob_behaviors: self.mob_behaviors.clone(),
+            control_behaviors: self.control_behaviors.clone(),
+            spawnable_behaviors: self.spawnable_behaviors.clone(),
+            entity,
+        }
+    }
+}
+
+/// Move to another step of the sequence when a condition is met
+#[derive(Deserialize, Clone)]
+pub struct BehaviorSequenceTransition {
+    pub condition: BehaviorSequenceCondition,
+    /// Index of the step to go to
+    pub step: usize,
+}
+
+/// Conditions checked by behavior sequence transitions
+#[derive(Deserialize, Clone)]
+pub enum BehaviorSequenceCondition {
+    /// Health of the mob is below a fraction (0.0 to 1.0) of its maximum health
+    HealthBelow(f32),
+    /// At least this many segments were lost by the mob since the sequence started
+    SegmentsDestroyed(usize),
+    /// A player is within this distance of the mob
+    PlayerInRange(f32),
+    /// A `MobBehaviorSequenceEvent` with this name was sent to the mob
+    Event(String),
+}
+
+/// What behavior sequence conditions are checked against
+pub struct BehaviorSequenceContext<'a> {
+    pub health_percentage: f32,
+    pub segments_destroyed: usize,
+    /// Distance to the closest player, None if there are no players
+    pub closest_player_distance: Option<f32>,
+    /// Names of the events sent to the mob this frame
+    pub events: &'a [&'a str],
+}
+
+impl BehaviorSequenceCondition {
+    pub fn is_met(&self, context: &BehaviorSequenceContext) -> bool {
+        match self {
+            BehaviorSequenceCondition::HealthBelow(fraction) => {
+                context.health_percentage < *fraction
+            }
+            BehaviorSequenceCondition::SegmentsDestroyed(count) => {
+                context.segments_destroyed >= *count
+            }
+            BehaviorSequenceCondition::PlayerInRange(range) => context
+                .closest_player_distance
+                .is_some_and(|distance| distance <= *range),
+            BehaviorSequenceCondition::Event(name) => context.events.contains(&name.as_str()),
+        }
+    }
 }
 
 #[derive(Deserialize, Resource)]
@@ -25,77 +121,174 @@ pub struct BehaviorSequenceResource {
     pub sequences: HashMap<MobBehaviorSequenceType, MobBehaviorSequence>,
 }
 
-#[derive(Deserialize, Debug, Hash, PartialEq, Eq, Clone)]
-pub enum MobBehaviorSequenceType {
-    Repeater,
+/// Named event for behavior sequence transitions. Sent to a single mob, or to all mobs when there
+/// is no entity. Timeline phases send these to all mobs with their `BehaviorSequenceEvent`
+/// entries.
+#[derive(Event, Clone)]
+pub struct MobBehaviorSequenceEvent {
+    pub name: String,
+    pub entity: Option<Entity>,
+}
+
+/// Tracks the progress of a mob through its behavior sequence
+pub struct BehaviorSequenceTracker {
+    /// Time left in the current step, None for steps without a time
+    pub timer: Option<Timer>,
+    pub index: usize,
+    /// One-shot steps that were already performed
+    pub performed_once: HashSet<usize>,
+    /// Most segments that were attached to the mob at once since the sequence started
+    pub max_segment_count: usize,
 }
 
+impl BehaviorSequenceTracker {
+    /// Start a sequence at its first step
+    fn new(sequence: &MobBehaviorSequence, segment_count: usize) -> Self {
+        let mut tracker = BehaviorSequenceTracker {
+            timer: None,
+            index: 0,
+            performed_once: HashSet::new(),
+            max_segment_count: segment_count,
+        };
+        tracker.enter_step(sequence, 0);
+        tracker
+    }
+
+    /// Start the step at `index`. Steps that don't exist are ignored, since the validation of the
+    /// data only reports transitions to them.
+    fn enter_step(&mut self, sequence: &MobBehaviorSequence, index: usize) {
+        let Some(step) = sequence.behaviors.get(index) else {
+            return;
+        };
+        self.index = index;
+        self.timer = step
+            .time
+            .map(|time| Timer::from_seconds(time, TimerMode::Once));
+        if step.once {
+            self.performed_once.insert(index);
+        }
+    }
+
+    /// Index of the step to move on to, if a transition condition is met or the time of the
+    /// current step ran out. Transitions to steps that don't exist are skipped.
+    fn get_next_index(
+        &mut self,
+        sequence: &MobBehaviorSequence,
+        context: &BehaviorSequenceContext,
+        delta: Duration,
+    ) -> Option<usize> {
+        let step = sequence.behaviors.get(self.index)?;
+        if step.terminal {
+            return None;
+        }
+
+        if let Some(transition) = step.transitions.iter().find(|transition| {
+            transition.step < sequence.behaviors.len() && transition.condition.is_met(context)
+        }) {
+            return Some(transition.step);
+        }
+
+        let timer = self.timer.as_mut()?;
+        timer.tick(delta);
+        timer
+            .just_finished()
+            .then(|| sequence.get_next_index(self.index, &self.performed_once))
+    }
+}
+
+/// Number of mob segments jointed to each mob, directly or through other mob segments
+fn count_mob_segments(joints: &HashMap<Entity, Entity>) -> HashMap<Entity, usize> {
+    let mut counts = HashMap::new();
+
+    for segment in joints.keys() {
+        let mut root = *segment;
+        // the number of joints bounds the length of a chain, in case joints form a loop
+        for _ in 0..joints.len() {
+            match joints.get(&root) {
+                Some(parent) => root = *parent,
+                None => break,
+            }
+        }
+        *counts.entry(root).or_insert(0) += 1;
+    }
+
+    counts
+}
+
+/// Moves mobs through the steps of their behavior sequences, and updates their behaviors when
+/// they start a step
 pub fn mob_behavior_sequence_tracker_system(
     behavior_sequence_resource: Res<BehaviorSequenceResource>,
     time: Res<Time>,
     mut behavior_update_event_writer: EventWriter<MobBehaviorUpdateEvent>,
-    mut mob_query: Query<(Entity, &mut MobComponent)>,
+    mut sequence_event_reader: EventReader<MobBehaviorSequenceEvent>,
+    mut mob_query: Query<(Entity, &mut MobComponent, &Transform, &HealthComponent)>,
+    mob_segment_query: Query<(Entity, &ImpulseJoint), With<MobSegmentComponent>>,
+    player_query: Query<&Transform, With<PlayerComponent>>,
 ) {
-    for (entity, mut mob_component) in mob_query.iter_mut() {
-        if let Some(behavior_sequence_type) = mob_component.behavior_sequence.clone() {
-            // get behavior sequence from resource
-            let behavior_sequence =
-                behavior_sequence_resource.sequences[&behavior_sequence_type].clone();
-            if let Some(behavior_sequence_tracker) = &mut mob_component.behavior_sequence_tracker {
-                // tick the timer
-                behavior_sequence_tracker.timer.tick(time.delta());
-
-                // if timer just finished update the behaviors
-                if behavior_sequence_tracker.timer.just_finished() {
-                    // update the index
-                    behavior_sequence_tracker.index = if behavior_sequence_tracker.index
-                        == behavior_sequence.behaviors.len() - 1
-                    {
-                        0
-                    } else {
-                        behavior_sequence_tracker.index + 1
-                    };
-
-                    // reset timer
-                    behavior_sequence_tracker
-                        .timer
-                        .set_duration(Duration::from_secs_f32(
-                            behavior_sequence.behaviors[behavior_sequence_tracker.index].time,
-                        ));
-                    behavior_sequence_tracker.timer.reset();
-
-                    // update behaviors
-                    behavior_update_event_writer.send(MobBehaviorUpdateEvent {
-                        mob_behaviors: behavior_sequence.behaviors[behavior_sequence_tracker.index]
-                            .mob_behaviors
-                            .clone(),
-                        control_behaviors: behavior_sequence.behaviors
-                            [behavior_sequence_tracker.index]
-                            .control_behaviors
-                            .clone(),
-                        spawnable_behaviors: behavior_sequence.behaviors
-                            [behavior_sequence_tracker.index]
-                            .spawnable_behaviors
-                            .clone(),
-                        entity,
-                    });
-                }
-            } else {
-                // initialize behavior sequence tracker
-                mob_component.behavior_sequence_tracker = Some(BehaviorSequenceTracker {
-                    timer: Timer::from_seconds(
-                        behavior_sequence.behaviors[0].time,
-                        TimerMode::Once,
-                    ),
-                    index: 0,
-                });
-
-                behavior_update_event_writer.send(MobBehaviorUpdateEvent {
-                    mob_behaviors: behavior_sequence.behaviors[0].mob_behaviors.clone(),
-                    control_behaviors: behavior_sequence.behaviors[0].control_behaviors.clone(),
-                    spawnable_behaviors: behavior_sequence.behaviors[0].spawnable_behaviors.clone(),
-                    entity,
-                });
+    let sequence_events: Vec<MobBehaviorSequenceEvent> =
+        sequence_event_reader.read().cloned().collect();
+
+    let segment_counts = count_mob_segments(
+        &mob_segment_query
+            .iter()
+            .map(|(entity, joint)| (entity, joint.parent))
+            .collect(),
+    );
+
+    for (entity, mut mob_component, transform, health) in mob_query.iter_mut() {
+        let Some(behavior_sequence) = mob_component
+            .behavior_sequence
+            .as_ref()
+            .and_then(|sequence_type| behavior_sequence_resource.sequences.get(sequence_type))
+        else {
+            continue;
+        };
+
+        if behavior_sequence.behaviors.is_empty() {
+            continue;
+        }
+
+        let segment_count = segment_counts.get(&entity).copied().unwrap_or(0);
+
+        let Some(behavior_sequence_tracker) = &mut mob_component.behavior_sequence_tracker else {
+            // initialize behavior sequence tracker
+            mob_component.behavior_sequence_tracker = Some(BehaviorSequenceTracker::new(
+                behavior_sequence,
+                segment_count,
+            ));
+            behavior_update_event_writer
+                .send(behavior_sequence.behaviors[0].get_update_event(entity));
+            continue;
+        };
+
+        behavior_sequence_tracker.max_segment_count = behavior_sequence_tracker
+            .max_segment_count
+            .max(segment_count);
+
+        let position = transform.translation.xy();
+        let events: Vec<&str> = sequence_events
+            .iter()
+            .filter(|event| event.entity.is_none() || event.entity == Some(entity))
+            .map(|event| event.name.as_str())
+            .collect();
+
+        let context = BehaviorSequenceContext {
+            health_percentage: health.get_health_percentage(),
+            segments_destroyed: behavior_sequence_tracker.max_segment_count - segment_count,
+            closest_player_distance: player_query
+                .iter()
+                .map(|player_transform| player_transform.translation.xy().distance(position))
+                .reduce(f32::min),
+            events: &events,
+        };
+
+        if let Some(next_index) =
+            behavior_sequence_tracker.get_next_index(behavior_sequence, &context, time.delta())
+        {
+            behavior_sequence_tracker.enter_step(behavior_sequence, next_index);
+            if let Some(step) = behavior_sequence.behaviors.get(next_index) {
+                behavior_update_event_writer.send(step.get_update_event(entity));
             }
         }
     }
@@ -110,17 +303,36 @@ pub struct MobBehaviorUpdateEvent {
 }
 
 pub fn mob_behavior_sequence_update_system(
+    mut commands: Commands,
     mut behavior_update_event_reader: EventReader<MobBehaviorUpdateEvent>,
-    mut mob_query: Query<(Entity, &mut MobComponent, &mut SpawnableComponent)>,
+    mut mob_query: Query<(
+        Entity,
+        &mut MobComponent,
+        &mut SpawnableComponent,
+        Has<TargetingComponent>,
+    )>,
     mut mob_segment_query: Query<(Entity, &mut MobSegmentComponent, &ImpulseJoint)>,
+    mobs_resource: Res<MobsResource>,
 ) {
     for event in behavior_update_event_reader.read() {
-        for (entity, mut mob_component, mut spawnable_component) in mob_query.iter_mut() {
+        for (entity, mut mob_component, mut spawnable_component, has_targeting) in
+            mob_query.iter_mut()
+        {
             if entity == event.entity {
                 mob_component.behaviors = event.mob_behaviors.clone();
                 mob_component.control_behaviors = event.control_behaviors.clone();
                 spawnable_component.behaviors = event.spawnable_behaviors.clone();
 
+                // mobs are only given a targeting component at spawn if their first behaviors
+                // need one, so give it to them once a later step rotates or dashes toward a target
+                if !has_targeting && SpawnableBehavior::needs_target(&event.spawnable_behaviors) {
+                    if let Some(mob_data) = mobs_resource.mobs.get(&mob_component.mob_type) {
+                        commands
+                            .entity(entity)
+                            .insert(TargetingComponent::from(mob_data.targeting.clone()));
+                    }
+                }
+
                 let mut entity_pairs = vec![];
                 // set behaviors of attached mob segments
 
@@ -197,3 +409,140 @@ pub struct EntityPair {
     pub parent: Entity,
     pub entity: Entity,
 }
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashSet, time::Duration};
+
+    use ron::de::from_bytes;
+
+    use super::{
+        BehaviorSequenceCondition, BehaviorSequenceContext, BehaviorSequenceResource,
+        BehaviorSequenceTracker, BehaviorSequenceTransition, MobBehaviorSequence,
+        MobBehaviorSequenceElement,
+    };
+
+    /// Step without behaviors that moves on after `time` seconds
+    fn step(time: Option<f32>) -> MobBehaviorSequenceElement {
+        MobBehaviorSequenceElement {
+            spawnable_behaviors: vec![],
+            mob_behaviors: vec![],
+            control_behaviors: vec![],
+            time,
+            transitions: vec![],
+            once: false,
+            terminal: false,
+        }
+    }
+
+    fn sequence() -> MobBehaviorSequence {
+        MobBehaviorSequence {
+            behaviors: vec![
+                MobBehaviorSequenceElement {
+                    once: true,
+                    ..step(Some(1.0))
+                },
+                MobBehaviorSequenceElement {
+                    transitions: vec![BehaviorSequenceTransition {
+                        condition: BehaviorSequenceCondition::HealthBelow(0.5),
+                        step: 2,
+                    }],
+                    ..step(Some(1.0))
+                },
+                MobBehaviorSequenceElement {
+                    terminal: true,
+                    ..step(None)
+                },
+            ],
+        }
+    }
+
+    fn context(health_percentage: f32) -> BehaviorSequenceContext<'static> {
+        BehaviorSequenceContext {
+            health_percentage,
+            segments_destroyed: 0,
+            closest_player_distance: None,
+            events: &[],
+        }
+    }
+
+    #[test]
+    fn test_behavior_sequences_parse() {
+        from_bytes::<BehaviorSequenceResource>(include_bytes!(
+            "../../assets/data/behavior_sequences.ron"
+        ))
+        .unwrap();
+    }
+
+    #[test]
+    fn test_sequence_steps() {
+        let sequence = sequence();
+        let mut tracker = BehaviorSequenceTracker::new(&sequence, 0);
+        let second = Duration::from_secs(1);
+
+        // the one-shot first step is skipped once performed
+        assert_eq!(
+            tracker.get_next_index(&sequence, &context(1.0), second),
+            Some(1)
+        );
+        tracker.enter_step(&sequence, 1);
+        assert_eq!(sequence.get_next_index(2, &tracker.performed_once), 1);
+        assert_eq!(sequence.get_next_index(2, &HashSet::new()), 0);
+
+        // transitions happen before the time runs out, and terminal steps are never left
+        assert_eq!(
+            tracker.get_next_index(&sequence, &context(1.0), Duration::ZERO),
+            None
+        );
+        assert_eq!(
+            tracker.get_next_index(&sequence, &context(0.4), Duration::ZERO),
+            Some(2)
+        );
+        tracker.enter_step(&sequence, 2);
+        assert_eq!(
+            tracker.get_next_index(&sequence, &context(0.0), second),
+            None
+        );
+    }
+
+    #[test]
+    fn test_transitions_to_unknown_steps_are_skipped() {
+        let mut sequence = sequence();
+        sequence.behaviors[1].transitions.insert(
+            0,
+            BehaviorSequenceTransition {
+                condition: BehaviorSequenceCondition::HealthBelow(0.5),
+                step: 7,
+            },
+        );
+        let mut tracker = BehaviorSequenceTracker::new(&sequence, 0);
+        tracker.enter_step(&sequence, 1);
+
+        assert_eq!(
+            tracker.get_next_index(&sequence, &context(0.4), Duration::ZERO),
+            Some(2)
+        );
+        tracker.enter_step(&sequence, 7);
+        assert_eq!(tracker.index, 1);
+    }
+
+    #[test]
+    fn test_sequence_conditions() {
+        let events = ["enrage"];
+        let context = BehaviorSequenceContext {
+            health_percentage: 0.5,
+            segments_destroyed: 2,
+            closest_player_distance: Some(100.0),
+            events: &events,
+        };
+
+        assert!(BehaviorSequenceCondition::HealthBelow(0.6).is_met(&context));
+        assert!(!BehaviorSequenceCondition::HealthBelow(0.5).is_met(&context));
+        assert!(BehaviorSequenceCondition::SegmentsDestroyed(2).is_met(&context));
+        assert!(!BehaviorSequenceCondition::SegmentsDestroyed(3).is_met(&context));
+        assert!(BehaviorSequenceCondition::PlayerInRange(150.0).is_met(&context));
+        assert!(!BehaviorSequenceCondition::PlayerInRange(50.0).is_met(&context));
+        assert!(BehaviorSequenceCondition::Event("enrage".to_string()).is_met(&context));
+        assert!(!BehaviorSequenceCondition::Event("retreat".to_string()).is_met(&context));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
     collision::SortedCollisionEvent, game::GameParametersResource, spawnable::SpawnEffectEvent,
+    status_effect::ApplyStatusEffectEvent,
 };
 use bevy::prelude::*;
 use serde::Deserialize;
@@ -37,6 +38,7 @@ pub fn consumable_execute_behavior_system(
     mut spawn_effect_event_writer: EventWriter<SpawnEffectEvent>,
     mut sound_effect_event_writer: EventWriter<PlaySoundEffectEvent>,
     game_parameters_res: Res<GameParametersResource>,
+    mut apply_status_effect_event_writer: EventWriter<ApplyStatusEffectEvent>,
 ) {
     // put all collision events in a vector first (so that they can be looked at multiple times)
     let mut collision_events_vec = vec![];
@@ -61,6 +63,7 @@ pub fn consumable_execute_behavior_system(
                         &game_parameters_res,
                         consumable_component.consumable_type.clone(),
                         &mut sound_effect_event_writer,
+                        &mut apply_status_effect_event_writer,
                     );
                 }
             }
@@ -90,6 +93,7 @@ fn apply_effects_on_impact(
     game_parameters_res: &GameParametersResource,
     consumable_type: ConsumableType,
     sound_effect_event_writer: &mut EventWriter<PlaySoundEffectEvent>,
+    apply_status_effect_event_writer: &mut EventWriter<ApplyStatusEffectEvent>,
 ) {
     for collision_event in collision_events.iter() {
         if let SortedCollisionEvent::PlayerToConsumableIntersection {
@@ -159,6 +163,12 @@ fn apply_effects_on_impact(
                                 ConsumableEffect::GainProjectiles(projectile) => {
                                     player_damage.projectile_count += *projectile;
                                 }
+                                ConsumableEffect::ApplyStatusEffect(status_effect) => {
+                                    apply_status_effect_event_writer.send(ApplyStatusEffectEvent {
+                                        target: player_entity_q,
+                                        status_effect: status_effect.clone(),
+                                    });
+                                }
                             }
                         }
                     }
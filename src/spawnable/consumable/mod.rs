@@ -1,24 +1,25 @@
 use bevy::{
     prelude::{
-        Commands, Component, Event, EventReader, Name, Res, Resource, Sprite, SpriteSheetBundle,
-        Timer, TimerMode, Transform, Vec2, Vec3,
+        Commands, Component, Event, EventReader, Name, Res, ResMut, Resource, Sprite,
+        SpriteSheetBundle, Timer, TimerMode, Transform, Vec2, Vec3,
     },
     render::color::Color,
 };
-use bevy_rapier2d::prelude::{ActiveEvents, Collider, LockedAxes, RigidBody, Sensor, Velocity};
+use bevy_rapier2d::prelude::{ActiveEvents, Collider, LockedAxes, RigidBody, Sensor};
 use serde::Deserialize;
 use std::collections::HashMap;
 use thetawave_interface::{
     game::options::GameOptions,
-    spawnable::{ConsumableType, SpawnableType},
+    spawnable::{ConsumableType, SpawnableType, TargetingStrategy},
     states::GameCleanup,
 };
 
 use crate::{
     animation::{AnimationComponent, AnimationData},
     assets::ConsumableAssets,
-    game::GameParametersResource,
-    spawnable::{SpawnableBehavior, SpawnableComponent},
+    game::{GameParametersResource, GameRngResource, RngStream},
+    spawnable::{SpawnableBehavior, SpawnableComponent, TargetingComponent},
+    status_effect::StatusEffectData,
 };
 
 mod behavior;
@@ -36,6 +37,8 @@ pub enum ConsumableEffect {
     GainArmor(usize),
     GainMoney(usize),
     GainProjectiles(usize),
+    /// Apply a status effect to the player that collects the consumable
+    ApplyStatusEffect(StatusEffectData),
 }
 
 /// Core component for a consumable
@@ -66,6 +69,7 @@ pub fn spawn_consumable_system(
     consumable_assets: Res<ConsumableAssets>,
     game_parameters: Res<GameParametersResource>,
     game_options: Res<GameOptions>,
+    mut game_rng: ResMut<GameRngResource>,
 ) {
     for event in event_reader.read() {
         spawn_consumable(
@@ -76,6 +80,7 @@ pub fn spawn_consumable_system(
             &mut commands,
             &game_parameters,
             &game_options,
+            &mut game_rng,
         );
     }
 }
@@ -89,6 +94,9 @@ pub struct ConsumableData {
     pub collider_dimensions: Vec2,
     /// Spawnable generic behaviors
     pub spawnable_behaviors: Vec<SpawnableBehavior>,
+    /// How the consumable picks what to dash toward
+    #[serde(default)]
+    pub targeting: TargetingStrategy,
     /// Texture of the consumable
     pub animation: AnimationData,
     /// Initial motion of the consuimable
@@ -128,6 +136,7 @@ pub struct ConsumableResource {
 }
 
 /// Spawn a consumable by type
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_consumable(
     consumable_type: &ConsumableType,
     consumable_resource: &ConsumableResource,
@@ -136,6 +145,7 @@ pub fn spawn_consumable(
     commands: &mut Commands,
     game_parameters: &GameParametersResource,
     game_options: &GameOptions,
+    game_rng: &mut GameRngResource,
 ) {
     //Get data from the consumable resource
     let consumable_data = &consumable_resource.consumables[consumable_type];
@@ -154,6 +164,10 @@ pub fn spawn_consumable(
         consumable.insert(AttractToClosestPlayerComponent);
     }
 
+    if SpawnableBehavior::needs_target(&consumable_data.spawnable_behaviors) {
+        consumable.insert(TargetingComponent::from(consumable_data.targeting.clone()));
+    }
+
     // spawn the consumable
     consumable
         .insert(SpriteSheetBundle {
@@ -176,7 +190,11 @@ pub fn spawn_consumable(
         })
         .insert(RigidBody::Dynamic)
         .insert(LockedAxes::ROTATION_LOCKED)
-        .insert(Velocity::from(consumable_data.initial_motion.clone()))
+        .insert(
+            consumable_data
+                .initial_motion
+                .get_velocity(game_rng.stream(RngStream::Motion)),
+        )
         .insert(Transform {
             translation: position.extend(consumable_data.z_level),
             scale: Vec3::new(
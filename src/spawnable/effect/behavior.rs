@@ -1,4 +1,5 @@
 use crate::animation::AnimationComponent;
+use crate::spawnable::RecycleOrDespawnExt;
 use crate::GameUpdateSet;
 use bevy::app::{App, Plugin, Update};
 use bevy::asset::{Assets, Handle};
@@ -76,7 +77,7 @@ impl From<EffectBehaviorData> for EffectBehavior {
 }
 
 /// Checks if each effect entity has a `DespawnAfterAnimation` behavior.
-/// Recursively despawns the effect entities with this behavior after
+/// Recycles the effect entities with this behavior after
 /// its last animation frame is complete.
 fn despawn_after_animation_effect_behavior_system(
     mut commands: Commands,
@@ -105,7 +106,7 @@ fn despawn_after_animation_effect_behavior_system(
                 if texture_atlas.index == texture_atlas_layout.textures.len() - 1
                     && animation.timer.just_finished()
                 {
-                    commands.entity(entity).despawn_recursive();
+                    commands.recycle_or_despawn(entity);
                 }
             }
         }
@@ -148,7 +149,7 @@ fn fade_out_text_effect_behavior_system(
 }
 
 /// Checks if each effect entity with a `TextureAtlasSprite` component has a `FadeOutMs` behavior.
-/// Recursively despawns the effect entities with this behavior after
+/// Recycles the effect entities with this behavior after
 /// the timer is complete, while also fading out linearly based on the percent of time left in the timer.
 fn fade_out_sprite_effect_behavior_system(
     mut commands: Commands,
@@ -165,9 +166,9 @@ fn fade_out_sprite_effect_behavior_system(
         }) {
             timer.tick(time.delta());
 
-            // if the timer just completed, recursively despawn the effect entity, otherwise change the alpha
+            // if the timer just completed, recycle the effect entity, otherwise change the alpha
             if timer.just_finished() {
-                commands.entity(entity).despawn_recursive();
+                commands.recycle_or_despawn(entity);
             } else {
                 sprite.color.set_a(timer.fraction_remaining());
             }
@@ -176,7 +177,7 @@ fn fade_out_sprite_effect_behavior_system(
 }
 
 /// Checks if each effect entity has a `FadeOutAndDespawnAfterAnimation` behavior.
-/// Recursively despawns the effect entities with this behavior after
+/// Recycles the effect entities with this behavior after
 /// the animation is complete, while also fading out along an exponential decay curve.
 fn fade_out_despawn_after_animation_effect_behavior_system(
     mut commands: Commands,
@@ -209,7 +210,7 @@ fn fade_out_despawn_after_animation_effect_behavior_system(
         ) {
             // Despawn if the animation is completed, otherwise continue fading out
             if animation_completed_events.iter().any(|e| e.0 == entity) {
-                commands.entity(entity).despawn_recursive();
+                commands.recycle_or_despawn(entity);
             } else {
                 stopwatch.tick(time.delta());
 
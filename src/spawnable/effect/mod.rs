@@ -1,6 +1,7 @@
 use self::behavior::EffectBehaviorData;
 
 use crate::animation::AnimationData;
+use crate::data_reload::ReloadableDataAppExt;
 use crate::spawnable::effect::behavior::EffectBehaviorPlugin;
 use crate::spawnable::effect::spawn::EffectSpawnPlugin;
 use bevy::{
@@ -41,7 +42,8 @@ impl Plugin for EffectPlugin {
                     include_bytes!("../../../assets/data/text_effects.ron"),
                 )
                 .expect("Failed to parse TextEffectsResource from 'text_effects.ron'"),
-            });
+            })
+            .register_reloadable_data("effects.ron", |effects| EffectsResource { effects });
     }
 }
 
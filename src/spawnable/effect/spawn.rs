@@ -1,13 +1,17 @@
 use crate::animation::AnimationComponent;
 use crate::assets::EffectAssets;
+use crate::game::{GameRngResource, RngStream};
 use crate::spawnable::effect::{EffectComponent, TextEffectData, TextEffectsResource};
-use crate::spawnable::{EffectsResource, InitialMotion, SpawnEffectEvent, SpawnableComponent};
+use crate::spawnable::{
+    EffectsResource, InitialMotion, PoolType, PooledComponent, SpawnEffectEvent,
+    SpawnableComponent, SpawnablePoolResource,
+};
 use bevy::prelude::{
     in_state, App, AssetServer, Commands, EventReader, IntoSystemConfigs, Name, Plugin, Res,
-    Sprite, SpriteSheetBundle, Text, Text2dBundle, TextStyle, Timer, TimerMode, Transform, Update,
-    Vec3,
+    ResMut, Sprite, SpriteSheetBundle, Text, Text2dBundle, TextStyle, Timer, TimerMode, Transform,
+    Update, Vec3,
 };
-use bevy_rapier2d::prelude::{LockedAxes, RigidBody, Velocity};
+use bevy_rapier2d::prelude::{LockedAxes, RigidBody};
 use rand::Rng;
 use thetawave_interface::game::options::GameOptions;
 use thetawave_interface::spawnable::{EffectType, SpawnableType, TextEffectType};
@@ -39,6 +43,8 @@ fn spawn_effect_system(
     effects_resource: Res<EffectsResource>,
     effect_assets: Res<EffectAssets>,
     game_options: Res<GameOptions>,
+    mut spawnable_pool: ResMut<SpawnablePoolResource>,
+    mut game_rng: ResMut<GameRngResource>,
 ) {
     for event in event_reader.read() {
         if !matches!(event.effect_type, EffectType::Text(..)) {
@@ -50,6 +56,8 @@ fn spawn_effect_system(
                 event.initial_motion.clone(),
                 &mut commands,
                 &game_options,
+                &mut spawnable_pool,
+                &mut game_rng,
             );
         }
     }
@@ -147,7 +155,8 @@ fn spawn_text_effect(
 /// Creates and spawns a non-text effect entity based on the provided parameters.
 ///
 /// This function constructs a non-text effect entity with the specified effect type, transform, and initial motion,
-/// and adds it to the ECS world.
+/// and adds it to the ECS world, reusing a recycled effect entity if there is one.
+#[allow(clippy::too_many_arguments)]
 fn spawn_effect(
     effect_type: &EffectType,
     effects_resource: &EffectsResource,
@@ -156,12 +165,14 @@ fn spawn_effect(
     initial_motion: InitialMotion,
     commands: &mut Commands,
     game_options: &GameOptions,
+    spawnable_pool: &mut SpawnablePoolResource,
+    game_rng: &mut GameRngResource,
 ) {
     // Get data from effect resource
     let effect_data = &effects_resource.effects[effect_type];
 
     // spawn the effect
-    let mut effect = commands.spawn_empty();
+    let mut effect = spawnable_pool.spawn_pooled(commands, PoolType::Effect);
 
     let mut effect_transform = transform;
     effect_transform.translation.z = effect_data.z_level;
@@ -190,8 +201,9 @@ fn spawn_effect(
         })
         .insert(LockedAxes::default())
         .insert(RigidBody::KinematicVelocityBased)
-        .insert(Velocity::from(initial_motion))
+        .insert(initial_motion.get_velocity(game_rng.stream(RngStream::Motion)))
         .insert(effect_transform)
         .insert(GameCleanup)
+        .insert(PooledComponent::from(PoolType::Effect))
         .insert(Name::new(effect_data.effect_type.to_string()));
 }
@@ -1,4 +1,4 @@
-//! Items are like consumables but have a permanent effect. These will also be purchasable.
+//! Items are like consumables but have a permanent effect. They can also be bought in the shop.
 use std::collections::HashMap;
 
 use bevy::prelude::*;
@@ -6,7 +6,7 @@ use ron::de::from_bytes;
 use serde::Deserialize;
 use thetawave_interface::spawnable::{ItemComponent, ItemType, SpawnItemEvent, SpawnableType};
 
-use crate::animation::AnimationData;
+use crate::{animation::AnimationData, data_reload::ReloadableDataAppExt};
 
 use self::{
     behavior::{ItemBehavior, ItemBehaviorPlugin},
@@ -29,7 +29,8 @@ impl Plugin for ItemPlugin {
                     "../../../assets/data/items.ron"
                 ))
                 .expect("Failed to parse ItemsResource from 'items.ron'"),
-            });
+            })
+            .register_reloadable_data("items.ron", |items| ItemResource { items });
     }
 }
 
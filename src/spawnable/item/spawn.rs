@@ -1,9 +1,9 @@
 use bevy::prelude::{
-    in_state, App, Commands, EventReader, IntoSystemConfigs, Name, Plugin, Res, SpriteSheetBundle,
-    Timer, TimerMode, Transform, Update, Vec2, Vec3,
+    in_state, App, Commands, EventReader, IntoSystemConfigs, Name, Plugin, Res, ResMut,
+    SpriteSheetBundle, Timer, TimerMode, Transform, Update, Vec2, Vec3,
 };
 use bevy::utils::default;
-use bevy_rapier2d::prelude::{ActiveEvents, Collider, LockedAxes, RigidBody, Sensor, Velocity};
+use bevy_rapier2d::prelude::{ActiveEvents, Collider, LockedAxes, RigidBody, Sensor};
 use thetawave_interface::spawnable::{ItemComponent, SpawnItemEvent};
 use thetawave_interface::{
     spawnable::ItemType,
@@ -12,7 +12,9 @@ use thetawave_interface::{
 
 use crate::spawnable::SpawnableBehavior;
 use crate::{
-    animation::AnimationComponent, assets::ItemAssets, game::GameParametersResource,
+    animation::AnimationComponent,
+    assets::ItemAssets,
+    game::{GameParametersResource, GameRngResource, RngStream},
     spawnable::SpawnableComponent,
 };
 use thetawave_interface::spawnable::AttractToClosestPlayerComponent;
@@ -41,6 +43,7 @@ pub fn spawn_item_system(
     item_resource: Res<ItemResource>,
     item_assets: Res<ItemAssets>,
     game_parameters: Res<GameParametersResource>,
+    mut game_rng: ResMut<GameRngResource>,
 ) {
     for event in event_reader.read() {
         spawn_item(
@@ -50,6 +53,7 @@ pub fn spawn_item_system(
             &event.item_type,
             event.position,
             &game_parameters,
+            &mut game_rng,
         );
     }
 }
@@ -61,6 +65,7 @@ pub fn spawn_item(
     item_type: &ItemType,
     position: Vec2,
     game_parameters: &GameParametersResource,
+    game_rng: &mut GameRngResource,
 ) {
     //Get data from the item resource
     let item_data = &item_resource.items[item_type];
@@ -91,8 +96,11 @@ pub fn spawn_item(
     });
 
     // Movement components
-    item.insert(LockedAxes::ROTATION_LOCKED)
-        .insert(Velocity::from(item_data.initial_motion.clone()));
+    item.insert(LockedAxes::ROTATION_LOCKED).insert(
+        item_data
+            .initial_motion
+            .get_velocity(game_rng.stream(RngStream::Motion)),
+    );
 
     // Position components
     item.insert(Transform {
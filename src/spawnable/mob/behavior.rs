@@ -10,9 +10,10 @@ use thetawave_interface::{
 use super::{BossComponent, MobComponent};
 use crate::{
     collision::SortedCollisionEvent,
-    game::GameParametersResource,
+    game::{GameParametersResource, GameRngResource, RngStream, RunModifierParams},
     loot::LootDropsResource,
     spawnable::{SpawnConsumableEvent, SpawnEffectEvent},
+    status_effect::{is_stunned, StatusEffectsComponent},
 };
 
 /// Types of behaviors that can be performed by mobs
@@ -25,12 +26,21 @@ pub enum MobBehavior {
     DieAtZeroHealth,
 }
 
-#[derive(Deserialize, Hash, PartialEq, Eq, Clone)]
-pub enum MobSegmentControlBehavior {
-    RepeaterProtectHead,
-    RepeaterAttack,
+impl MobBehavior {
+    /// Whether the behavior is still performed while the mob is stunned. Only taking damage and
+    /// dying are, so that stunned mobs can still be destroyed.
+    fn is_performed_while_stunned(&self) -> bool {
+        matches!(
+            self,
+            MobBehavior::ReceiveDamageOnImpact | MobBehavior::DieAtZeroHealth
+        )
+    }
 }
 
+/// Name of a mode that a mob controls its segments in, like "ProtectHead" or "Attack". Each mob
+/// maps the control behaviors it uses to the behaviors of its segments.
+pub type MobSegmentControlBehavior = String;
+
 #[allow(clippy::too_many_arguments)]
 /// Manages excuteing behaviors of mobs
 pub fn mob_execute_behavior_system(
@@ -43,6 +53,7 @@ pub fn mob_execute_behavior_system(
         &Transform,
         &HealthComponent,
         Option<&BossComponent>,
+        Option<&StatusEffectsComponent>,
     )>,
     player_query: Query<(Entity, &PlayerIncomingDamageComponent)>,
     mut spawn_effect_event_writer: EventWriter<SpawnEffectEvent>,
@@ -54,6 +65,8 @@ pub fn mob_execute_behavior_system(
     loot_drops_resource: Res<LootDropsResource>,
     mut sound_effect_event_writer: EventWriter<PlaySoundEffectEvent>,
     game_parameters: Res<GameParametersResource>,
+    mut game_rng: ResMut<GameRngResource>,
+    run_modifiers: RunModifierParams,
 ) {
     // Get all contact events first (can't be read more than once within a system)
     let mut collision_events_vec = vec![];
@@ -61,10 +74,20 @@ pub fn mob_execute_behavior_system(
         collision_events_vec.push(collision_event);
     }
 
+    let run_modifier_effects = run_modifiers.get_effects();
+
     // Iterate through all spawnable entities and execute their behavior
-    for (entity, mut mob_component, mob_transform, mob_health, boss_tag) in mob_query.iter_mut() {
+    for (entity, mut mob_component, mob_transform, mob_health, boss_tag, status_effects) in
+        mob_query.iter_mut()
+    {
+        let stunned = is_stunned(status_effects);
         let behaviors = mob_component.behaviors.clone();
         for behavior in behaviors {
+            // stunned mobs don't spawn mobs or hurt players, and their spawners don't tick
+            if stunned && !behavior.is_performed_while_stunned() {
+                continue;
+            }
+
             match behavior {
                 MobBehavior::SpawnMob(mob_spawner_key) => {
                     // get data
@@ -97,6 +120,7 @@ pub fn mob_execute_behavior_system(
                                 position,
                                 rotation: mob_transform.rotation, // passed rotation of the parent mob
                                 boss: false,
+                                parent: Some(entity),
                             });
                         }
                     }
@@ -155,6 +179,8 @@ pub fn mob_execute_behavior_system(
                             &mut spawn_consumable_event_writer,
                             &mut spawn_item_event_writer,
                             mob_transform.translation.xy(),
+                            game_rng.stream(RngStream::Loot),
+                            &run_modifier_effects,
                         );
 
                         // despawn mob
@@ -0,0 +1,206 @@
+//! Health-threshold stages of bosses. Each stage changes how the boss behaves once its health
+//! drops low enough.
+use bevy::prelude::*;
+use serde::Deserialize;
+use thetawave_interface::{
+    audio::ChangeBackgroundMusicEvent,
+    camera::ScreenShakeEvent,
+    health::HealthComponent,
+    spawnable::{MobType, SpawnMobEvent, SpawnPosition},
+};
+
+use crate::{
+    run::BGMusicTransition,
+    spawnable::{
+        behavior_sequence::{MobBehaviorSequenceType, MobBehaviorUpdateEvent},
+        SpawnableBehavior,
+    },
+};
+
+use super::{MobBehavior, MobComponent, MobSegmentControlBehavior};
+
+/// A stage of a boss, started when the boss's health drops to a fraction of its maximum
+#[derive(Deserialize, Clone)]
+pub struct BossStageData {
+    /// Fraction (0.0 to 1.0) of maximum health at or below which the stage starts
+    pub health_threshold: f32,
+    /// Behavior sequence to switch to. Takes priority over `behaviors`.
+    #[serde(default)]
+    pub behavior_sequence: Option<MobBehaviorSequenceType>,
+    /// Set of behaviors to switch to, stopping the current behavior sequence
+    #[serde(default)]
+    pub behaviors: Option<BossStageBehaviors>,
+    /// Background music to change to
+    #[serde(default)]
+    pub music_transition: Option<BGMusicTransition>,
+    /// Mobs spawned when the stage starts. Local positions are relative to the boss.
+    #[serde(default)]
+    pub reinforcements: Vec<BossReinforcementData>,
+    /// Trauma of the screen shake when the stage starts, 0.0 for no screen shake
+    #[serde(default)]
+    pub screen_shake: f32,
+}
+
+/// Behaviors a boss performs in a stage, like an element of a behavior sequence without a time
+#[derive(Deserialize, Clone, Default)]
+pub struct BossStageBehaviors {
+    #[serde(default)]
+    pub spawnable_behaviors: Vec<SpawnableBehavior>,
+    #[serde(default)]
+    pub mob_behaviors: Vec<MobBehavior>,
+    #[serde(default)]
+    pub control_behaviors: Vec<MobSegmentControlBehavior>,
+}
+
+/// Mob spawned when a boss stage starts
+#[derive(Deserialize, Clone)]
+pub struct BossReinforcementData {
+    pub mob_type: MobType,
+    pub position: SpawnPosition,
+}
+
+/// Tracks the stages of a boss
+#[derive(Component)]
+pub struct BossStagesComponent {
+    /// Stages sorted from highest to lowest health threshold
+    pub stages: Vec<BossStageData>,
+    /// Index of the next stage to start
+    pub next_stage: usize,
+}
+
+impl BossStagesComponent {
+    pub fn new(mut stages: Vec<BossStageData>) -> Self {
+        stages.sort_by(|a, b| b.health_threshold.total_cmp(&a.health_threshold));
+        BossStagesComponent {
+            stages,
+            next_stage: 0,
+        }
+    }
+
+    /// Get the stages that the boss has reached at a fraction of its health, and mark them as
+    /// started. More than one stage can be reached at once by a large hit.
+    pub fn take_reached_stages(&mut self, health_percentage: f32) -> &[BossStageData] {
+        let start = self.next_stage;
+        while self
+            .stages
+            .get(self.next_stage)
+            .is_some_and(|stage| health_percentage <= stage.health_threshold)
+        {
+            self.next_stage += 1;
+        }
+        &self.stages[start..self.next_stage]
+    }
+
+    /// Get the health thresholds of the stages, used to mark the stage boundaries on the boss
+    /// health bar
+    pub fn get_thresholds(&self) -> impl Iterator<Item = f32> + '_ {
+        self.stages.iter().map(|stage| stage.health_threshold)
+    }
+}
+
+/// Start the stages of bosses whose health dropped below the stages' thresholds
+pub fn boss_stage_system(
+    mut boss_query: Query<(
+        Entity,
+        &HealthComponent,
+        &Transform,
+        &mut MobComponent,
+        &mut BossStagesComponent,
+    )>,
+    mut behavior_update_event_writer: EventWriter<MobBehaviorUpdateEvent>,
+    mut change_bg_music_event_writer: EventWriter<ChangeBackgroundMusicEvent>,
+    mut spawn_mob_event_writer: EventWriter<SpawnMobEvent>,
+    mut screen_shake_event_writer: EventWriter<ScreenShakeEvent>,
+) {
+    for (entity, health, transform, mut mob_component, mut boss_stages) in boss_query.iter_mut() {
+        // the boss is destroyed at zero health, so don't start its stages
+        if health.is_dead() {
+            continue;
+        }
+
+        for stage in boss_stages.take_reached_stages(health.get_health_percentage()) {
+            info!(
+                "Boss {} reached stage at {:.0}% health",
+                mob_component.mob_type,
+                stage.health_threshold * 100.0
+            );
+
+            if let Some(behavior_sequence) = &stage.behavior_sequence {
+                // the tracker is reinitialized with the first behaviors of the new sequence
+                mob_component.behavior_sequence = Some(behavior_sequence.clone());
+                mob_component.behavior_sequence_tracker = None;
+            } else if let Some(behaviors) = &stage.behaviors {
+                mob_component.behavior_sequence = None;
+                mob_component.behavior_sequence_tracker = None;
+                behavior_update_event_writer.send(MobBehaviorUpdateEvent {
+                    mob_behaviors: behaviors.mob_behaviors.clone(),
+                    control_behaviors: behaviors.control_behaviors.clone(),
+                    spawnable_behaviors: behaviors.spawnable_behaviors.clone(),
+                    entity,
+                });
+            }
+
+            if let Some(music_transition) = &stage.music_transition {
+                change_bg_music_event_writer.send(music_transition.into());
+            }
+
+            for reinforcement in stage.reinforcements.iter() {
+                let position = match reinforcement.position {
+                    SpawnPosition::Global(coords) => coords,
+                    SpawnPosition::Local(coords) => {
+                        transform.translation.xy()
+                            + transform.local_x().xy() * coords.x
+                            + transform.local_y().xy() * coords.y
+                    }
+                };
+
+                spawn_mob_event_writer.send(SpawnMobEvent {
+                    mob_type: reinforcement.mob_type.clone(),
+                    position,
+                    rotation: Quat::default(),
+                    boss: false,
+                    parent: Some(entity),
+                });
+            }
+
+            if stage.screen_shake > 0.0 {
+                screen_shake_event_writer.send(ScreenShakeEvent {
+                    trauma: stage.screen_shake,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BossStageData, BossStagesComponent};
+
+    #[test]
+    fn test_stages_are_reached_once_in_order() {
+        let stage = |health_threshold, screen_shake| BossStageData {
+            health_threshold,
+            behavior_sequence: None,
+            behaviors: None,
+            music_transition: None,
+            reinforcements: vec![],
+            screen_shake,
+        };
+        let stages = vec![stage(0.25, 0.5), stage(0.75, 0.0), stage(0.5, 0.0)];
+        let mut boss_stages = BossStagesComponent::new(stages);
+
+        assert_eq!(
+            boss_stages.get_thresholds().collect::<Vec<_>>(),
+            vec![0.75, 0.5, 0.25]
+        );
+        assert!(boss_stages.take_reached_stages(0.9).is_empty());
+        assert_eq!(boss_stages.take_reached_stages(0.7).len(), 1);
+        assert!(boss_stages.take_reached_stages(0.6).is_empty());
+
+        // a large hit can go through several thresholds at once
+        let reached = boss_stages.take_reached_stages(0.2);
+        assert_eq!(reached.len(), 2);
+        assert!(reached[1].screen_shake > 0.0);
+        assert!(boss_stages.take_reached_stages(0.1).is_empty());
+    }
+}
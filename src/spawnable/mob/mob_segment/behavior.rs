@@ -1,6 +1,8 @@
+use std::f32::consts::TAU;
+
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::{ImpulseJoint, JointAxis};
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use serde::Deserialize;
 use thetawave_interface::{
     audio::{PlaySoundEffectEvent, SoundEffectType},
@@ -13,11 +15,12 @@ use thetawave_interface::{
 
 use crate::{
     collision::SortedCollisionEvent,
-    game::GameParametersResource,
+    game::{GameParametersResource, GameRngResource, RngStream, RunModifierParams},
     loot::LootDropsResource,
     spawnable::{
         behavior_sequence::EntityPair, SpawnConsumableEvent, SpawnEffectEvent, SpawnMobEvent,
     },
+    status_effect::{is_stunned, StatusEffectsComponent},
 };
 
 use super::{MobSegmentComponent, MobSegmentsResource};
@@ -29,23 +32,83 @@ pub enum MobSegmentBehavior {
     ReceiveDamageOnImpact,
     DieAtZeroHealth,
     RandomRotation(RandomRotationData),
-    RepeaterProtectHead(RepeaterSegmentProtectHeadData), // takes in angle to protect head
-    RepeaterAttack(RepeaterSegmentAttackData),
+    /// Drive the joint to the parent of the segment toward a fixed angle
+    SetJointMotor(JointMotorData),
+    /// Sweep the joint back and forth between two angles at a steady rate
+    SweepJointMotor(JointSweepData),
+    /// Swing the joint around an angle along a sine wave
+    OscillateJointMotor(JointOscillateData),
     SpawnMob(String),
 }
 
+impl MobSegmentBehavior {
+    /// Whether the behavior is still performed while the mob segment is stunned. Only taking
+    /// damage and dying are, so that stunned mob segments can still be destroyed.
+    fn is_performed_while_stunned(&self) -> bool {
+        matches!(
+            self,
+            MobSegmentBehavior::ReceiveDamageOnImpact | MobSegmentBehavior::DieAtZeroHealth
+        )
+    }
+}
+
 #[derive(Deserialize, Clone)]
-pub struct RepeaterSegmentProtectHeadData {
+pub struct JointMotorData {
     pub angle: f32,
     pub damping: f32,
     pub stiffness: f32,
 }
 
 #[derive(Deserialize, Clone)]
-pub struct RepeaterSegmentAttackData {
-    pub angle: f32,
+pub struct JointSweepData {
+    pub start_angle: f32,
+    pub end_angle: f32,
+    /// Seconds to sweep from one angle to the other
+    pub sweep_time: f32,
+    pub damping: f32,
+    pub stiffness: f32,
+    #[serde(skip)]
+    elapsed: f32,
+}
+
+impl JointSweepData {
+    /// Advance the sweep by `delta` seconds, returning the angle the joint is driven toward
+    pub fn get_angle(&mut self, delta: f32) -> f32 {
+        self.elapsed = (self.elapsed + delta).rem_euclid(2.0 * self.sweep_time);
+
+        // sweeps toward the end angle in the first half of the cycle, and back in the second
+        let progress = self.elapsed / self.sweep_time;
+        let progress = if progress > 1.0 {
+            2.0 - progress
+        } else {
+            progress
+        };
+
+        self.start_angle + (self.end_angle - self.start_angle) * progress
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct JointOscillateData {
+    pub center_angle: f32,
+    /// Largest angle away from the center angle
+    pub amplitude: f32,
+    /// Number of full swings per second
+    pub frequency: f32,
     pub damping: f32,
     pub stiffness: f32,
+    /// Fraction of the current swing that was completed
+    #[serde(skip)]
+    phase: f32,
+}
+
+impl JointOscillateData {
+    /// Advance the swing by `delta` seconds, returning the angle the joint is driven toward
+    pub fn get_angle(&mut self, delta: f32) -> f32 {
+        self.phase = (self.phase + self.frequency * delta).fract();
+
+        self.center_angle + self.amplitude * (TAU * self.phase).sin()
+    }
 }
 
 #[derive(Deserialize, Clone)]
@@ -67,10 +130,12 @@ pub fn mob_segment_execute_behavior_system(
         &Transform,
         &mut ImpulseJoint,
         &HealthComponent,
+        Option<&StatusEffectsComponent>,
     )>,
     mut spawn_effect_event_writer: EventWriter<SpawnEffectEvent>,
     player_query: Query<(Entity, &PlayerIncomingDamageComponent)>,
-    loot_drops_resource: Res<LootDropsResource>,
+    // grouped to stay within bevy's limit on the number of system parameters
+    (loot_drops_resource, run_modifiers): (Res<LootDropsResource>, RunModifierParams),
     mut spawn_consumable_event_writer: EventWriter<SpawnConsumableEvent>,
     mut spawn_item_event_writer: EventWriter<SpawnItemEvent>,
     mut sound_effect_event_writer: EventWriter<PlaySoundEffectEvent>,
@@ -79,17 +144,33 @@ pub fn mob_segment_execute_behavior_system(
     mut mob_segment_destroyed_event_writer: EventWriter<MobSegmentDestroyedEvent>,
     game_parameters: Res<GameParametersResource>,
     mut damage_dealt_event_writer: EventWriter<DamageDealtEvent>,
+    mut game_rng: ResMut<GameRngResource>,
 ) {
     let mut collision_events_vec = vec![];
     for collision_event in collision_events.read() {
         collision_events_vec.push(collision_event);
     }
 
-    for (entity, mut mob_segment_component, mob_segment_transform, mut joint, mob_seg_health) in
-        mob_segment_query.iter_mut()
+    let run_modifier_effects = run_modifiers.get_effects();
+
+    for (
+        entity,
+        mut mob_segment_component,
+        mob_segment_transform,
+        mut joint,
+        mob_seg_health,
+        status_effects,
+    ) in mob_segment_query.iter_mut()
     {
+        let stunned = is_stunned(status_effects);
         let behaviors = mob_segment_component.behaviors.clone();
-        for behavior in behaviors {
+        for (behavior_idx, behavior) in behaviors.into_iter().enumerate() {
+            // stunned mob segments hold their joints where they are and don't spawn mobs or hurt
+            // players
+            if stunned && !behavior.is_performed_while_stunned() {
+                continue;
+            }
+
             match behavior {
                 MobSegmentBehavior::DealDamageToPlayerOnImpact => {
                     deal_damage_to_player_on_impact(
@@ -134,6 +215,8 @@ pub fn mob_segment_execute_behavior_system(
                             &mut spawn_consumable_event_writer,
                             &mut spawn_item_event_writer,
                             mob_segment_transform.translation.xy(),
+                            game_rng.stream(RngStream::Loot),
+                            &run_modifier_effects,
                         );
 
                         // despawn mob
@@ -146,7 +229,9 @@ pub fn mob_segment_execute_behavior_system(
                     }
                 }
                 MobSegmentBehavior::RandomRotation(data) => {
-                    let rand_ang = thread_rng().gen_range(data.low_angle..=data.high_angle);
+                    let rand_ang = game_rng
+                        .stream(RngStream::MobSegments)
+                        .gen_range(data.low_angle..=data.high_angle);
 
                     joint.data.set_motor_position(
                         JointAxis::AngX,
@@ -156,7 +241,7 @@ pub fn mob_segment_execute_behavior_system(
                     );
                 }
 
-                MobSegmentBehavior::RepeaterProtectHead(data) => {
+                MobSegmentBehavior::SetJointMotor(data) => {
                     joint.data.set_motor_position(
                         JointAxis::AngX,
                         data.angle,
@@ -165,13 +250,26 @@ pub fn mob_segment_execute_behavior_system(
                     );
                 }
 
-                MobSegmentBehavior::RepeaterAttack(data) => {
+                MobSegmentBehavior::SweepJointMotor(mut data) => {
                     joint.data.set_motor_position(
                         JointAxis::AngX,
-                        data.angle,
+                        data.get_angle(time.delta_seconds()),
+                        data.stiffness,
+                        data.damping,
+                    );
+                    mob_segment_component.behaviors[behavior_idx] =
+                        MobSegmentBehavior::SweepJointMotor(data);
+                }
+
+                MobSegmentBehavior::OscillateJointMotor(mut data) => {
+                    joint.data.set_motor_position(
+                        JointAxis::AngX,
+                        data.get_angle(time.delta_seconds()),
                         data.stiffness,
                         data.damping,
                     );
+                    mob_segment_component.behaviors[behavior_idx] =
+                        MobSegmentBehavior::OscillateJointMotor(data);
                 }
 
                 MobSegmentBehavior::SpawnMob(mob_spawner_key) => {
@@ -201,6 +299,7 @@ pub fn mob_segment_execute_behavior_system(
                                 position,
                                 rotation: mob_segment_transform.rotation, // passed rotation of the parent mob
                                 boss: false,
+                                parent: Some(entity),
                             });
                         }
                     }
@@ -371,3 +470,42 @@ fn receive_damage_on_impact(
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{JointOscillateData, JointSweepData};
+
+    #[test]
+    fn test_joint_sweep_goes_back_and_forth() {
+        let mut sweep = JointSweepData {
+            start_angle: -1.0,
+            end_angle: 1.0,
+            sweep_time: 2.0,
+            damping: 50.0,
+            stiffness: 30.0,
+            elapsed: 0.0,
+        };
+
+        // sweeps go from the start angle to the end angle and back
+        for expected_angle in [0.0, 1.0, 0.0, -1.0, 0.0] {
+            assert!((sweep.get_angle(1.0) - expected_angle).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn test_joint_oscillation_swings_around_center() {
+        let mut oscillate = JointOscillateData {
+            center_angle: 0.5,
+            amplitude: 0.25,
+            frequency: 0.5,
+            damping: 50.0,
+            stiffness: 30.0,
+            phase: 0.0,
+        };
+
+        // oscillations swing to either side of the center angle
+        for expected_angle in [0.75, 0.5, 0.25, 0.5] {
+            assert!((oscillate.get_angle(0.5) - expected_angle).abs() < 0.001);
+        }
+    }
+}
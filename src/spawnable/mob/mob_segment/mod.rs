@@ -6,7 +6,7 @@ use thetawave_interface::{
     audio::CollisionSoundType,
     health::HealthComponent,
     objective::DefenseInteraction,
-    spawnable::{MobSegmentType, SpawnableType},
+    spawnable::{MobSegmentType, SpawnMobSegmentEvent, SpawnableType},
     states::GameCleanup,
 };
 
@@ -16,7 +16,7 @@ use crate::collision::{
 use crate::{
     animation::{AnimationComponent, AnimationData},
     assets::MobAssets,
-    game::GameParametersResource,
+    game::{scale_usize, GameParametersResource},
     loot::DropListType,
     spawnable::SpawnableComponent,
 };
@@ -26,7 +26,7 @@ pub use self::behavior::*;
 
 use super::{
     ColliderData, CompoundColliderData, JointType, MobSegmentAnchorPointData, MobSpawner,
-    MobSpawnerData,
+    MobSpawnerData, MobStatMultipliersResource,
 };
 
 #[derive(Resource, Deserialize)]
@@ -103,18 +103,18 @@ impl From<&MobSegmentData> for HealthComponent {
         HealthComponent::new(mob_segment_data.health, 0, 0.0)
     }
 }
-/// Spawn a mob segment
+/// Spawn a mob segment, jointed to a parent entity if a joint is given
 #[allow(clippy::too_many_arguments)]
 pub fn spawn_mob_segment(
     mob_segment_type: &MobSegmentType,
-    joint_parent_entity: Entity,
-    joint: &RevoluteJointBuilder,
+    joint: Option<(Entity, &RevoluteJointBuilder)>,
     mob_segments_resource: &MobSegmentsResource,
     mob_assets: &MobAssets,
     position: Vec2,
     parent_anchor_point: Vec2,
     commands: &mut Commands,
     game_parameters: &GameParametersResource,
+    mob_stat_multipliers: &MobStatMultipliersResource,
 ) {
     let mob_segment_data = &mob_segments_resource.mob_segments[mob_segment_type];
 
@@ -125,8 +125,11 @@ pub fn spawn_mob_segment(
         position.y + parent_anchor_point.y - mob_segment_data.anchor_point.y,
     );
 
+    if let Some((joint_parent_entity, joint)) = joint {
+        mob_segment.insert(ImpulseJoint::new(joint_parent_entity, *joint));
+    }
+
     mob_segment
-        .insert(ImpulseJoint::new(joint_parent_entity, *joint))
         .insert(SpriteSheetBundle {
             atlas: mob_assets
                 .get_mob_segment_texture_atlas_layout(mob_segment_type)
@@ -167,8 +170,18 @@ pub fn spawn_mob_segment(
             memberships: SPAWNABLE_COLLIDER_GROUP | MOB_COLLIDER_GROUP,
             filters: Group::ALL ^ HORIZONTAL_BARRIER_COLLIDER_GROUP,
         })
-        .insert(MobSegmentComponent::from(mob_segment_data))
-        .insert(HealthComponent::from(mob_segment_data))
+        .insert(MobSegmentComponent {
+            collision_damage: scale_usize(
+                mob_segment_data.collision_damage,
+                mob_stat_multipliers.collision_damage,
+            ),
+            ..MobSegmentComponent::from(mob_segment_data)
+        })
+        .insert(HealthComponent::new(
+            scale_usize(mob_segment_data.health, mob_stat_multipliers.health),
+            0,
+            0.0,
+        ))
         .insert(SpawnableComponent::new(SpawnableType::MobSegment(
             mob_segment_type.clone(),
         )))
@@ -197,15 +210,40 @@ pub fn spawn_mob_segment(
 
             spawn_mob_segment(
                 &new_mob_segment_data.mob_segment_type,
-                mob_segment_entity,
-                &joint,
+                Some((mob_segment_entity, &joint)),
                 mob_segments_resource,
                 mob_assets,
                 new_position,
                 mob_segment_anchor_point.position,
                 commands,
                 game_parameters,
+                mob_stat_multipliers,
             )
         }
     }
 }
+
+/// Spawns mob segments on their own from events. The anchor point of the mob segment is placed at
+/// the position of the event.
+pub fn spawn_mob_segment_system(
+    mut commands: Commands,
+    mut event_reader: EventReader<SpawnMobSegmentEvent>,
+    mob_segments_resource: Res<MobSegmentsResource>,
+    mob_assets: Res<MobAssets>,
+    game_parameters: Res<GameParametersResource>,
+    mob_stat_multipliers: Res<MobStatMultipliersResource>,
+) {
+    for event in event_reader.read() {
+        spawn_mob_segment(
+            &event.mob_segment_type,
+            None,
+            &mob_segments_resource,
+            &mob_assets,
+            event.position,
+            Vec2::ZERO,
+            &mut commands,
+            &game_parameters,
+            &mob_stat_multipliers,
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::{
     ActiveEvents, CoefficientCombineRule, Collider, CollisionGroups, Friction, Group, LockedAxes,
-    Restitution, RevoluteJointBuilder, RigidBody, Velocity,
+    Restitution, RevoluteJointBuilder, RigidBody,
 };
 use serde::Deserialize;
 use std::collections::{hash_map::Entry, HashMap};
@@ -9,16 +9,22 @@ use std::collections::{hash_map::Entry, HashMap};
 use crate::{
     animation::{AnimationComponent, AnimationData},
     assets::MobAssets,
-    game::GameParametersResource,
+    game::{scale_usize, GameParametersResource, GameRngResource, RngStream},
     loot::DropListType,
-    spawnable::{SpawnableBehavior, SpawnableComponent},
+    spawnable::{
+        SpawnableBehavior, SpawnableComponent, SpawnableParentComponent, TargetingComponent,
+    },
 };
 
 mod behavior;
+mod boss_stage;
 mod mob_segment;
-pub(crate) use self::{behavior::*, mob_segment::*};
+pub(crate) use self::{behavior::*, boss_stage::*, mob_segment::*};
 
-use super::{behavior_sequence::MobBehaviorSequenceType, InitialMotion};
+use super::{
+    behavior_sequence::{BehaviorSequenceTracker, MobBehaviorSequenceType},
+    InitialMotion,
+};
 use crate::collision::{
     HORIZONTAL_BARRIER_COLLIDER_GROUP, MOB_COLLIDER_GROUP, SPAWNABLE_COLLIDER_GROUP,
 };
@@ -27,7 +33,9 @@ use thetawave_interface::{
     game::options::GameOptions,
     health::HealthComponent,
     objective::DefenseInteraction,
-    spawnable::{MobDestroyedEvent, MobSegmentType, MobType, SpawnMobEvent, SpawnPosition},
+    spawnable::{
+        MobDestroyedEvent, MobSegmentType, MobType, SpawnMobEvent, SpawnPosition, TargetingStrategy,
+    },
     states::GameCleanup,
     weapon::{WeaponComponent, WeaponData},
 };
@@ -96,6 +104,27 @@ impl From<&MobData> for MobComponent {
 #[derive(Component)]
 pub struct BossComponent;
 
+/// Multipliers applied to the stats of mobs as they are spawned. Used to apply the selected
+/// difficulty and run modifiers, and to make mobs tougher as a run goes on.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct MobStatMultipliersResource {
+    pub health: f32,
+    pub collision_damage: f32,
+    pub projectile_damage: f32,
+    pub projectile_speed: f32,
+}
+
+impl Default for MobStatMultipliersResource {
+    fn default() -> Self {
+        MobStatMultipliersResource {
+            health: 1.0,
+            collision_damage: 1.0,
+            projectile_damage: 1.0,
+            projectile_speed: 1.0,
+        }
+    }
+}
+
 #[derive(Deserialize, Clone, Debug)]
 pub struct MobSpawner {
     pub mob_type: MobType,
@@ -120,11 +149,6 @@ pub struct MobSpawnerData {
     pub position: SpawnPosition,
 }
 
-pub struct BehaviorSequenceTracker {
-    pub timer: Timer,
-    pub index: usize,
-}
-
 /// Data about mob entities that can be stored in data ron file
 #[derive(Deserialize)]
 pub struct MobData {
@@ -133,6 +157,9 @@ pub struct MobData {
     /// List of spawnable behaviors that are performed
     #[serde(default)]
     pub spawnable_behaviors: Vec<SpawnableBehavior>,
+    /// How the mob picks what to rotate toward, dash toward and fire aimed weapons at
+    #[serde(default)]
+    pub targeting: TargetingStrategy,
     /// Behavior sequence type
     pub behavior_sequence_type: Option<MobBehaviorSequenceType>,
     /// List of mob behaviors that are performed
@@ -141,7 +168,8 @@ pub struct MobData {
     /// behaviors used to control attached mob segments
     #[serde(default)]
     pub control_behaviors: Vec<MobSegmentControlBehavior>,
-    /// behaviors that mob segments attached to the mob will perform, given the mobs current behavior
+    /// behaviors that mob segments attached to the mob will perform, for each of the control
+    /// behaviors the mob can use
     pub mob_segment_behaviors: Option<
         HashMap<MobSegmentControlBehavior, HashMap<MobSegmentType, Vec<MobSegmentBehavior>>>,
     >,
@@ -198,6 +226,9 @@ pub struct MobData {
     /// projectile spawners that the mob can use
     #[serde(default)]
     pub weapon: Option<WeaponData>,
+    /// stages the mob goes through as its health drops, when spawned as a boss
+    #[serde(default)]
+    pub boss_stages: Vec<BossStageData>,
 }
 impl From<&MobData> for HealthComponent {
     fn from(mob_data: &MobData) -> Self {
@@ -209,6 +240,15 @@ impl MobData {
     pub fn get_weapon_component(&self) -> Option<WeaponComponent> {
         self.weapon.clone().map(WeaponComponent::from)
     }
+
+    /// Returns true if the mob rotates toward a target or fires an aimed weapon
+    pub fn needs_target(&self) -> bool {
+        SpawnableBehavior::needs_target(&self.spawnable_behaviors)
+            || self
+                .weapon
+                .as_ref()
+                .is_some_and(|weapon| weapon.projectile_data.aimed)
+    }
 }
 
 #[derive(Deserialize, Clone)]
@@ -246,6 +286,7 @@ pub enum JointType {
 }
 
 /// Spawns mobs from events
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_mob_system(
     mut commands: Commands,
     mut event_reader: EventReader<SpawnMobEvent>,
@@ -254,9 +295,11 @@ pub fn spawn_mob_system(
     mob_assets: Res<MobAssets>,
     game_parameters: Res<GameParametersResource>,
     game_options: Res<GameOptions>,
+    mob_stat_multipliers: Res<MobStatMultipliersResource>,
+    mut game_rng: ResMut<GameRngResource>,
 ) {
     for event in event_reader.read() {
-        spawn_mob(
+        let mob_entity = spawn_mob(
             &event.mob_type,
             &mob_resource,
             &mob_segments_resource,
@@ -267,7 +310,15 @@ pub fn spawn_mob_system(
             &mut commands,
             &game_parameters,
             &game_options,
+            &mob_stat_multipliers,
+            &mut game_rng,
         );
+
+        if let Some(parent) = event.parent {
+            commands
+                .entity(mob_entity)
+                .insert(SpawnableParentComponent(parent));
+        }
     }
 }
 
@@ -299,7 +350,7 @@ pub struct MobsResource {
     pub mobs: HashMap<MobType, MobData>,
 }
 
-/// Spawn a mob entity
+/// Spawn a mob entity, returning the entity of the mob
 #[allow(clippy::too_many_arguments)]
 pub fn spawn_mob(
     mob_type: &MobType,
@@ -312,7 +363,9 @@ pub fn spawn_mob(
     commands: &mut Commands,
     game_parameters: &GameParametersResource,
     game_options: &GameOptions,
-) {
+    mob_stat_multipliers: &MobStatMultipliersResource,
+    game_rng: &mut GameRngResource,
+) -> Entity {
     // Get data from mob resource
     let mob_data = &mob_resource.mobs[mob_type];
 
@@ -338,7 +391,11 @@ pub fn spawn_mob(
         direction: mob_data.animation.direction.clone(),
     })
     .insert(RigidBody::Dynamic)
-    .insert(Velocity::from(mob_data.initial_motion.clone()))
+    .insert(
+        mob_data
+            .initial_motion
+            .get_velocity(game_rng.stream(RngStream::Motion)),
+    )
     .insert(Collider::compound(
         mob_data
             .colliders
@@ -355,8 +412,18 @@ pub fn spawn_mob(
         memberships: SPAWNABLE_COLLIDER_GROUP | MOB_COLLIDER_GROUP,
         filters: Group::ALL ^ HORIZONTAL_BARRIER_COLLIDER_GROUP,
     })
-    .insert(MobComponent::from(mob_data))
-    .insert(HealthComponent::from(mob_data))
+    .insert(MobComponent {
+        collision_damage: scale_usize(
+            mob_data.collision_damage,
+            mob_stat_multipliers.collision_damage,
+        ),
+        ..MobComponent::from(mob_data)
+    })
+    .insert(HealthComponent::new(
+        scale_usize(mob_data.health, mob_stat_multipliers.health),
+        0,
+        0.0,
+    ))
     .insert(SpawnableComponent::from(mob_data))
     .insert(ActiveEvents::COLLISION_EVENTS)
     .insert(GameCleanup)
@@ -364,13 +431,26 @@ pub fn spawn_mob(
 
     if boss {
         mob.insert(BossComponent);
+
+        if !mob_data.boss_stages.is_empty() {
+            mob.insert(BossStagesComponent::new(mob_data.boss_stages.clone()));
+        }
+    }
+
+    if mob_data.needs_target() {
+        mob.insert(TargetingComponent::from(mob_data.targeting.clone()));
     }
 
     if !mob_data.can_rotate {
         mob.insert(LockedAxes::ROTATION_LOCKED);
     }
 
-    if let Some(weapon_component) = mob_data.get_weapon_component() {
+    if let Some(mut weapon_component) = mob_data.get_weapon_component() {
+        weapon_component.projectile_data.damage = scale_usize(
+            weapon_component.projectile_data.damage,
+            mob_stat_multipliers.projectile_damage,
+        );
+        weapon_component.projectile_data.speed *= mob_stat_multipliers.projectile_speed;
         mob.insert(weapon_component);
     }
 
@@ -423,16 +503,18 @@ pub fn spawn_mob(
 
         spawn_mob_segment(
             &mob_segment_data.mob_segment_type,
-            mob_entity,
-            &joint,
+            Some((mob_entity, &joint)),
             mob_segments_resource,
             mob_assets,
             position,
             anchor_point.position,
             commands,
             game_parameters,
+            mob_stat_multipliers,
         )
     }
+
+    mob_entity
 }
 
 #[derive(Event)]
@@ -1,16 +1,17 @@
 //! Exposes a plugin with systems determining how spawnables (e.x. consumables, items, mobs,
 //! players, etc.) behave.
+use crate::data_reload::ReloadableDataAppExt;
 use crate::spawnable::effect::EffectPlugin;
 use crate::GameUpdateSet;
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::Velocity;
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use ron::de::from_bytes;
 use serde::Deserialize;
 use std::collections::HashMap;
 use thetawave_interface::spawnable::{ConsumableType, MobType, ProjectileType};
 use thetawave_interface::spawnable::{
-    MobDestroyedEvent, MobSegmentDestroyedEvent, SpawnMobEvent, SpawnableType,
+    MobDestroyedEvent, MobSegmentDestroyedEvent, SpawnMobEvent, SpawnMobSegmentEvent, SpawnableType,
 };
 use thetawave_interface::states;
 mod behavior;
@@ -19,25 +20,39 @@ mod consumable;
 mod effect;
 mod item;
 mod mob;
+mod movement;
+mod pool;
 mod projectile;
+mod targeting;
+mod validation;
 use self::behavior::attract_to_player_system;
 pub use self::behavior::SpawnableBehavior;
-pub use self::behavior_sequence::{BehaviorSequenceResource, MobBehaviorUpdateEvent};
+pub use self::behavior_sequence::{
+    BehaviorSequenceResource, MobBehaviorSequenceEvent, MobBehaviorUpdateEvent,
+};
 pub use self::consumable::{
-    ConsumableComponent, ConsumableData, ConsumableResource, SpawnConsumableEvent,
+    ConsumableComponent, ConsumableData, ConsumableEffect, ConsumableResource, SpawnConsumableEvent,
 };
 pub use self::effect::{EffectsResource, SpawnEffectEvent};
 use self::item::ItemPlugin;
 pub use self::mob::*;
+pub use self::movement::{
+    DashData, OrbitCenter, OrbitData, PathEnd, SineWaveData, SpawnableParentComponent,
+    WaypointPathData,
+};
+pub use self::pool::{PoolType, PooledComponent, RecycleOrDespawnExt, SpawnablePoolResource};
 pub use self::projectile::{
-    FireWeaponEvent, ProjectileComponent, ProjectileData, ProjectileResource,
+    FireWeaponEvent, ProjectileBehavior, ProjectileComponent, ProjectileData, ProjectileResource,
 };
+pub use self::targeting::{TargetCandidate, TargetingComponent};
+pub(crate) use self::validation::{check_spawnable_type, validate_data};
 use self::{
     behavior::{spawnable_execute_behavior_system, spawnable_set_target_behavior_system},
     behavior_sequence::{
         mob_behavior_sequence_tracker_system, mob_behavior_sequence_update_system,
     },
     consumable::{consumable_execute_behavior_system, spawn_consumable_system},
+    pool::clear_spawnable_pool_system,
     projectile::{projectile_execute_behavior_system, spawn_projectile_system},
 };
 
@@ -74,12 +89,21 @@ impl Plugin for SpawnablePlugin {
                 "../../assets/data/consumables.ron"
             ))
             .expect("Failed to parse ConsumableResource from 'consumables.ron'"),
-        });
+        })
+        .insert_resource(MobStatMultipliersResource::default())
+        .init_resource::<SpawnablePoolResource>();
+
+        app.register_reloadable_data("mobs.ron", |mobs| MobsResource { mobs })
+            .register_reloadable_data("projectiles.ron", |projectiles| ProjectileResource {
+                projectiles,
+            });
 
         app.add_event::<SpawnConsumableEvent>()
             .add_event::<FireWeaponEvent>()
             .add_event::<SpawnMobEvent>()
+            .add_event::<SpawnMobSegmentEvent>()
             .add_event::<MobBehaviorUpdateEvent>()
+            .add_event::<MobBehaviorSequenceEvent>()
             .add_event::<MobDestroyedEvent>()
             .add_event::<MobSegmentDestroyedEvent>()
             .add_event::<BossesDestroyedEvent>();
@@ -103,12 +127,16 @@ impl Plugin for SpawnablePlugin {
                 spawn_projectile_system,
                 spawn_consumable_system, // event generated in mob execute behavior
                 spawn_mob_system,        // event generated in mob execute behavior
+                spawn_mob_segment_system,
                 check_boss_mobs_system.after(spawn_mob_system),
+                boss_stage_system.before(mob_behavior_sequence_tracker_system),
                 attract_to_player_system,
             )
                 .run_if(in_state(states::AppStates::Game))
                 .run_if(in_state(states::GameStates::Playing)),
         );
+
+        app.add_systems(OnExit(states::AppStates::Game), clear_spawnable_pool_system);
     }
 }
 
@@ -172,25 +200,27 @@ pub struct InitialMotion {
     pub random_linvel: Option<(Vec2, Vec2)>,
 }
 
-impl From<InitialMotion> for Velocity {
-    fn from(im: InitialMotion) -> Self {
-        let random_linvel = if let Some((lower, upper)) = im.random_linvel {
-            let x = thread_rng().gen_range(lower.x..=upper.x);
-            let y = thread_rng().gen_range(lower.y..=upper.y);
+impl InitialMotion {
+    /// Velocity to spawn an entity with, drawing the random parts from the given rng so that
+    /// seeded runs spawn entities with the same motion
+    pub fn get_velocity(&self, rng: &mut impl Rng) -> Velocity {
+        let random_linvel = if let Some((lower, upper)) = self.random_linvel {
+            let x = rng.gen_range(lower.x..=upper.x);
+            let y = rng.gen_range(lower.y..=upper.y);
             Vec2::new(x, y)
         } else {
             Vec2::ZERO
         };
 
-        let random_angvel = if let Some((lower, upper)) = im.random_angvel {
-            thread_rng().gen_range(lower..=upper)
+        let random_angvel = if let Some((lower, upper)) = self.random_angvel {
+            rng.gen_range(lower..=upper)
         } else {
             0.0
         };
 
         Velocity {
-            linvel: im.linvel.unwrap_or_default() + random_linvel,
-            angvel: im.angvel.unwrap_or_default() + random_angvel,
+            linvel: self.linvel.unwrap_or_default() + random_linvel,
+            angvel: self.angvel.unwrap_or_default() + random_angvel,
         }
     }
 }
@@ -0,0 +1,454 @@
+//! Data and state of the spawnable behaviors that move along shaped paths: sine waves, waypoint
+//! paths, orbits and dashes. Their state is kept in the behaviors themselves, so each spawnable
+//! follows its own path.
+use std::f32::consts::TAU;
+
+use bevy::prelude::{Component, Entity, Vec2};
+use serde::Deserialize;
+
+/// Number of points sampled between two waypoints of a spline
+const SPLINE_SAMPLES: usize = 8;
+
+/// Entity that spawned a spawnable, like the mob that spawned a mob or the entity that fired a
+/// projectile. Used by spawnables that orbit their parent.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct SpawnableParentComponent(pub Entity);
+
+/// Weaving from side to side across the direction the spawnable moves in
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub struct SineWaveData {
+    /// Farthest distance from the center of the wave
+    pub amplitude: f32,
+    /// Number of full waves per second
+    pub frequency: f32,
+    #[serde(skip)]
+    elapsed: f32,
+    /// Axis to weave along, perpendicular to the motion of the spawnable when it starts weaving
+    #[serde(skip)]
+    axis: Option<Vec2>,
+    /// Weaving speed added to the velocity on the last update
+    #[serde(skip)]
+    weave_speed: f32,
+}
+
+impl SineWaveData {
+    /// Advance the wave by `delta` seconds, returning the change of velocity along the weaving axis
+    pub fn update(&mut self, velocity: Vec2, delta: f32) -> Vec2 {
+        let axis = *self.axis.get_or_insert_with(|| {
+            velocity
+                .try_normalize()
+                .map(|direction| direction.perp())
+                .unwrap_or(Vec2::X)
+        });
+
+        self.elapsed += delta;
+
+        let angular_frequency = TAU * self.frequency;
+        let weave_speed =
+            self.amplitude * angular_frequency * (angular_frequency * self.elapsed).cos();
+        let change = weave_speed - self.weave_speed;
+        self.weave_speed = weave_speed;
+
+        axis * change
+    }
+}
+
+/// What a spawnable does after reaching the last waypoint of its path
+#[derive(Deserialize, Clone, PartialEq, Debug, Default)]
+pub enum PathEnd {
+    /// Stay at the last waypoint
+    #[default]
+    Stop,
+    /// Go back to the first waypoint
+    Loop,
+    /// Follow the path back to the first waypoint, then forward again
+    PingPong,
+}
+
+fn default_waypoint_radius() -> f32 {
+    10.0
+}
+
+/// Following a path through waypoints
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub struct WaypointPathData {
+    pub waypoints: Vec<Vec2>,
+    #[serde(default)]
+    pub end: PathEnd,
+    /// Follow a smooth spline through the waypoints instead of straight lines between them
+    #[serde(default)]
+    pub spline: bool,
+    /// Distance at which a waypoint counts as reached
+    #[serde(default = "default_waypoint_radius")]
+    pub radius: f32,
+    /// Points that are actually followed, sampled from the spline when there is one
+    #[serde(skip)]
+    points: Vec<Vec2>,
+    /// Index of the point being moved toward
+    #[serde(skip)]
+    current: usize,
+    /// Whether a ping-pong path is being followed backward
+    #[serde(skip)]
+    reversed: bool,
+}
+
+impl WaypointPathData {
+    /// Point of the path to move toward from `position`, moving on to the next point when the
+    /// current one is reached. Returns None if the path has no waypoints.
+    pub fn get_target(&mut self, position: Vec2) -> Option<Vec2> {
+        if self.points.is_empty() {
+            self.points = if self.spline {
+                sample_catmull_rom(&self.waypoints, self.end == PathEnd::Loop)
+            } else {
+                self.waypoints.clone()
+            };
+        }
+
+        let last = self.points.len().checked_sub(1)?;
+
+        if position.distance(self.points[self.current]) <= self.radius {
+            self.current = match (&self.end, self.reversed) {
+                (_, false) if self.current < last => self.current + 1,
+                (PathEnd::Loop, _) => 0,
+                (PathEnd::PingPong, false) => {
+                    self.reversed = true;
+                    last.saturating_sub(1)
+                }
+                (PathEnd::PingPong, true) if self.current > 0 => self.current - 1,
+                (PathEnd::PingPong, true) => {
+                    self.reversed = false;
+                    1.min(last)
+                }
+                _ => self.current,
+            };
+        }
+
+        Some(self.points[self.current])
+    }
+}
+
+/// Points along a Catmull-Rom spline passing through all the waypoints, closed back to the first
+/// waypoint if `closed`
+fn sample_catmull_rom(waypoints: &[Vec2], closed: bool) -> Vec<Vec2> {
+    if waypoints.len() < 3 {
+        return waypoints.to_vec();
+    }
+
+    let count = waypoints.len();
+    let get = |idx: isize| {
+        if closed {
+            waypoints[idx.rem_euclid(count as isize) as usize]
+        } else {
+            waypoints[idx.clamp(0, count as isize - 1) as usize]
+        }
+    };
+
+    let segments = if closed { count } else { count - 1 };
+    let mut points = vec![];
+
+    for segment in 0..segments as isize {
+        let (p0, p1, p2, p3) = (
+            get(segment - 1),
+            get(segment),
+            get(segment + 1),
+            get(segment + 2),
+        );
+
+        for sample in 0..SPLINE_SAMPLES {
+            let t = sample as f32 / SPLINE_SAMPLES as f32;
+            let t2 = t * t;
+            let t3 = t2 * t;
+
+            points.push(
+                0.5 * ((2.0 * p1)
+                    + (p2 - p0) * t
+                    + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+                    + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3),
+            );
+        }
+    }
+
+    if !closed {
+        points.push(waypoints[count - 1]);
+    }
+
+    points
+}
+
+/// Center of an orbit
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub enum OrbitCenter {
+    Point(Vec2),
+    /// The entity that spawned the spawnable. Without one, the spawnable orbits the position
+    /// where it started orbiting.
+    Parent,
+}
+
+/// Circling around a center
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub struct OrbitData {
+    pub center: OrbitCenter,
+    pub radius: f32,
+    /// Radians per second, counterclockwise when positive
+    pub angular_speed: f32,
+    /// Current angle of the spawnable around the center
+    #[serde(skip)]
+    angle: Option<f32>,
+    /// Last known center, used when the parent is gone
+    #[serde(skip)]
+    last_center: Option<Vec2>,
+}
+
+impl OrbitData {
+    /// Velocity that brings a spawnable at `position` to its next position on the orbit in `delta`
+    /// seconds, no faster than `max_speed`. `parent_position` is the position of the parent of the
+    /// spawnable, if it still exists.
+    pub fn get_velocity(
+        &mut self,
+        position: Vec2,
+        parent_position: Option<Vec2>,
+        delta: f32,
+        max_speed: f32,
+    ) -> Option<Vec2> {
+        if delta <= 0.0 {
+            return None;
+        }
+
+        let center = match &self.center {
+            OrbitCenter::Point(point) => *point,
+            OrbitCenter::Parent => parent_position.or(self.last_center).unwrap_or(position),
+        };
+        self.last_center = Some(center);
+
+        let offset = position - center;
+        let angle = self.angle.get_or_insert(offset.y.atan2(offset.x));
+        *angle += self.angular_speed * delta;
+
+        let next_position = center + self.radius * Vec2::from_angle(*angle);
+
+        Some(((next_position - position) / delta).clamp_length_max(max_speed))
+    }
+}
+
+/// Dashing toward the target of the spawnable at regular intervals
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub struct DashData {
+    /// Seconds between the start of two dashes
+    pub period: f32,
+    /// Seconds a dash lasts
+    pub duration: f32,
+    pub speed: f32,
+    #[serde(skip)]
+    elapsed: f32,
+    /// Direction of the dash in progress
+    #[serde(skip)]
+    direction: Option<Vec2>,
+}
+
+impl DashData {
+    /// Advance the dash timing by `delta` seconds. Returns the velocity of the spawnable while it is
+    /// dashing, and None between dashes. Dashes only start if there is a target to dash toward.
+    pub fn update(&mut self, position: Vec2, target: Option<Vec2>, delta: f32) -> Option<Vec2> {
+        self.elapsed += delta;
+
+        if self.elapsed >= self.period {
+            self.elapsed -= self.period;
+            self.direction = target.and_then(|target| (target - position).try_normalize());
+        }
+
+        if self.elapsed >= self.duration {
+            self.direction = None;
+        }
+
+        self.direction.map(|direction| direction * self.speed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::Vec2;
+
+    use super::{DashData, OrbitCenter, OrbitData, PathEnd, SineWaveData, WaypointPathData};
+    use crate::spawnable::SpawnableBehavior;
+
+    fn path(end: PathEnd) -> WaypointPathData {
+        WaypointPathData {
+            waypoints: vec![Vec2::ZERO, Vec2::new(100.0, 0.0), Vec2::new(100.0, 100.0)],
+            end,
+            spline: false,
+            radius: 1.0,
+            points: vec![],
+            current: 0,
+            reversed: false,
+        }
+    }
+
+    fn sine_wave() -> SineWaveData {
+        SineWaveData {
+            amplitude: 20.0,
+            frequency: 0.5,
+            elapsed: 0.0,
+            axis: None,
+            weave_speed: 0.0,
+        }
+    }
+
+    fn orbit(center: OrbitCenter) -> OrbitData {
+        OrbitData {
+            center,
+            radius: 40.0,
+            angular_speed: 3.0,
+            angle: None,
+            last_center: None,
+        }
+    }
+
+    fn dash() -> DashData {
+        DashData {
+            period: 2.0,
+            duration: 0.25,
+            speed: 500.0,
+            elapsed: 0.0,
+            direction: None,
+        }
+    }
+
+    /// Points targeted by a spawnable that reaches each of its targets in turn
+    fn follow(path: &mut WaypointPathData, steps: usize) -> Vec<Vec2> {
+        let mut position = Vec2::new(-50.0, 0.0);
+        (0..steps)
+            .map(|_| {
+                position = path.get_target(position).unwrap();
+                position
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_waypoint_path_ends() {
+        let waypoints = path(PathEnd::Stop).waypoints;
+        let [a, b, c] = [waypoints[0], waypoints[1], waypoints[2]];
+
+        assert_eq!(follow(&mut path(PathEnd::Stop), 5), vec![a, b, c, c, c]);
+        assert_eq!(follow(&mut path(PathEnd::Loop), 5), vec![a, b, c, a, b]);
+        assert_eq!(
+            follow(&mut path(PathEnd::PingPong), 7),
+            vec![a, b, c, b, a, b, c]
+        );
+    }
+
+    #[test]
+    fn test_spline_passes_through_waypoints() {
+        let mut path = WaypointPathData {
+            spline: true,
+            ..path(PathEnd::Stop)
+        };
+        path.get_target(Vec2::new(-50.0, 0.0));
+
+        for waypoint in path.waypoints.iter() {
+            assert!(path
+                .points
+                .iter()
+                .any(|point| point.distance(*waypoint) < 0.001));
+        }
+        assert_eq!(path.points.last(), path.waypoints.last());
+    }
+
+    #[test]
+    fn test_sine_wave_weaves_across_motion() {
+        let mut sine_wave = sine_wave();
+        let delta = 0.001;
+
+        // moving down, the spawnable weaves from side to side, reaching the amplitude a quarter of
+        // a wave in and coming back to the center after half a wave
+        let mut weave_velocity = Vec2::ZERO;
+        let mut offset = Vec2::ZERO;
+        let mut offsets = vec![];
+        for _ in 0..1000 {
+            let change = sine_wave.update(Vec2::new(0.0, -100.0) + weave_velocity, delta);
+            assert_eq!(change.y, 0.0);
+            weave_velocity += change;
+            offset += weave_velocity * delta;
+            offsets.push(offset.x);
+        }
+
+        assert!((offsets[499] - 20.0).abs() < 0.1);
+        assert!(offsets[999].abs() < 0.1);
+        assert!(offsets.iter().all(|offset| offset.abs() < 20.1));
+    }
+
+    #[test]
+    fn test_sine_wave_without_motion_weaves_horizontally() {
+        let mut sine_wave = sine_wave();
+
+        let change = sine_wave.update(Vec2::ZERO, 0.1);
+        assert_eq!(change.y, 0.0);
+        assert!(change.x > 0.0);
+    }
+
+    #[test]
+    fn test_orbit_stays_at_radius() {
+        let mut orbit = orbit(OrbitCenter::Parent);
+        let center = Vec2::new(10.0, 10.0);
+
+        // orbits start from the current angle around the center
+        let mut position = center + Vec2::new(40.0, 0.0);
+        for _ in 0..10 {
+            let velocity = orbit
+                .get_velocity(position, Some(center), 0.1, 1000.0)
+                .unwrap();
+            position += velocity * 0.1;
+            assert!((position.distance(center) - 40.0).abs() < 0.01);
+        }
+        assert_eq!(
+            orbit.get_velocity(position, Some(center), 0.0, 1000.0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_orbit_keeps_last_center_without_parent() {
+        let mut orbit = orbit(OrbitCenter::Parent);
+        let center = Vec2::new(10.0, 10.0);
+        let mut position = center + Vec2::new(40.0, 0.0);
+
+        let velocity = orbit
+            .get_velocity(position, Some(center), 0.1, 1000.0)
+            .unwrap();
+        position += velocity * 0.1;
+
+        // the parent is gone, so the spawnable keeps circling where it was
+        let velocity = orbit.get_velocity(position, None, 0.1, 1000.0).unwrap();
+        position += velocity * 0.1;
+        assert!((position.distance(center) - 40.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_dash_at_end_of_each_period() {
+        let mut dash = dash();
+        let target = Some(Vec2::new(0.0, -100.0));
+
+        assert_eq!(dash.update(Vec2::ZERO, target, 1.0), None);
+        assert_eq!(
+            dash.update(Vec2::ZERO, target, 1.0),
+            Some(Vec2::new(0.0, -500.0))
+        );
+        assert_eq!(dash.update(Vec2::ZERO, target, 0.5), None);
+
+        // without a target, there is nothing to dash toward
+        assert_eq!(dash.update(Vec2::ZERO, None, 1.5), None);
+    }
+
+    #[test]
+    fn test_dash_needs_target() {
+        assert!(SpawnableBehavior::needs_target(&[
+            SpawnableBehavior::SineWave(sine_wave()),
+            SpawnableBehavior::Dash(dash()),
+        ]));
+        assert!(!SpawnableBehavior::needs_target(&[
+            SpawnableBehavior::SineWave(sine_wave()),
+            SpawnableBehavior::WaypointPath(path(PathEnd::Loop)),
+            SpawnableBehavior::Orbit(orbit(OrbitCenter::Point(Vec2::ZERO))),
+        ]));
+    }
+}
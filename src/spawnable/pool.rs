@@ -0,0 +1,462 @@
+//! Recycles projectile and effect entities instead of despawning them, so that bullet-heavy moments
+//! don't spawn and despawn whole entities every frame. Recycled entities are hidden and stripped
+//! of their colliders and behaviors, then get all their components inserted again when reused.
+//! Collision systems skip recycled entities, since rapier still reports the end of the contacts
+//! of their removed colliders.
+use bevy::{
+    ecs::system::{Command, EntityCommands},
+    hierarchy::despawn_with_children_recursive,
+    prelude::*,
+};
+use bevy_rapier2d::prelude::{ActiveEvents, Collider, RigidBody, Sensor, Velocity};
+
+use super::{effect::EffectComponent, ProjectileComponent, SpawnableComponent};
+
+/// Most inactive entities kept in each pool, entities recycled past this are despawned
+const MAX_POOL_SIZE: usize = 1000;
+
+/// Kinds of entities that are pooled separately, since they are made of different components
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolType {
+    Projectile,
+    Effect,
+}
+
+/// Marks an entity that is returned to a pool instead of being despawned
+#[derive(Component, Debug)]
+pub struct PooledComponent {
+    pool_type: PoolType,
+    /// Whether the entity is in use, rather than waiting in its pool
+    active: bool,
+}
+
+impl PooledComponent {
+    /// Whether the entity is in use, rather than waiting in its pool
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+}
+
+impl From<PoolType> for PooledComponent {
+    fn from(pool_type: PoolType) -> Self {
+        PooledComponent {
+            pool_type,
+            active: true,
+        }
+    }
+}
+
+/// Inactive entities waiting to be reused
+#[derive(Resource)]
+pub struct SpawnablePoolResource {
+    projectiles: Vec<Entity>,
+    effects: Vec<Entity>,
+    /// Most inactive entities kept in each pool
+    max_size: usize,
+}
+
+impl Default for SpawnablePoolResource {
+    fn default() -> Self {
+        SpawnablePoolResource::with_max_size(MAX_POOL_SIZE)
+    }
+}
+
+impl SpawnablePoolResource {
+    /// Empty pools that keep up to `max_size` entities each. With a `max_size` of 0 every
+    /// recycled entity is despawned.
+    fn with_max_size(max_size: usize) -> Self {
+        SpawnablePoolResource {
+            projectiles: vec![],
+            effects: vec![],
+            max_size,
+        }
+    }
+
+    fn get_pool(&mut self, pool_type: PoolType) -> &mut Vec<Entity> {
+        match pool_type {
+            PoolType::Projectile => &mut self.projectiles,
+            PoolType::Effect => &mut self.effects,
+        }
+    }
+
+    /// Reuse an inactive entity of the pool, or spawn a new one if the pool is empty. Either way
+    /// the caller inserts all the components of the entity, replacing the old ones.
+    pub fn spawn_pooled<'a>(
+        &mut self,
+        commands: &'a mut Commands,
+        pool_type: PoolType,
+    ) -> EntityCommands<'a> {
+        while let Some(entity) = self.get_pool(pool_type).pop() {
+            // pooled entities are despawned along with everything else when the game is left
+            if commands.get_entity(entity).is_some() {
+                return commands.entity(entity);
+            }
+        }
+
+        commands.spawn_empty()
+    }
+
+    /// Add an inactive entity to its pool. Returns false if the pool is full.
+    fn release(&mut self, pool_type: PoolType, entity: Entity) -> bool {
+        let max_size = self.max_size;
+        let pool = self.get_pool(pool_type);
+
+        if pool.len() >= max_size {
+            return false;
+        }
+
+        pool.push(entity);
+        true
+    }
+}
+
+/// Returns a pooled entity to its pool, or despawns it if it isn't pooled
+pub struct RecycleOrDespawnCommand(pub Entity);
+
+impl Command for RecycleOrDespawnCommand {
+    fn apply(self, world: &mut World) {
+        let entity = self.0;
+
+        let pool_type = match world.get::<PooledComponent>(entity) {
+            Some(pooled) if pooled.active => pooled.pool_type,
+            // already recycled, like a projectile that exploded and timed out on the same frame
+            Some(_) => return,
+            None => {
+                despawn_with_children_recursive(world, entity);
+                return;
+            }
+        };
+
+        let released = world
+            .get_resource_mut::<SpawnablePoolResource>()
+            .is_some_and(|mut pool| pool.release(pool_type, entity));
+
+        if !released {
+            despawn_with_children_recursive(world, entity);
+            return;
+        }
+
+        let mut entity_mut = world.entity_mut(entity);
+
+        // remove the collider so that the entity stops colliding, and deactivate the rest of the
+        // entity by changing its components in place
+        entity_mut.remove::<(Collider, ActiveEvents, Sensor)>();
+        if let Some(mut pooled) = entity_mut.get_mut::<PooledComponent>() {
+            pooled.active = false;
+        }
+        if let Some(mut visibility) = entity_mut.get_mut::<Visibility>() {
+            *visibility = Visibility::Hidden;
+        }
+        if let Some(mut velocity) = entity_mut.get_mut::<Velocity>() {
+            *velocity = Velocity::zero();
+        }
+        if let Some(mut rigid_body) = entity_mut.get_mut::<RigidBody>() {
+            *rigid_body = RigidBody::Fixed;
+        }
+        if let Some(mut spawnable) = entity_mut.get_mut::<SpawnableComponent>() {
+            spawnable.behaviors.clear();
+        }
+        if let Some(mut projectile) = entity_mut.get_mut::<ProjectileComponent>() {
+            projectile.behaviors.clear();
+        }
+        if let Some(mut effect) = entity_mut.get_mut::<EffectComponent>() {
+            effect.behaviors.clear();
+        }
+    }
+}
+
+/// Recycling of spawnables through `Commands`
+pub trait RecycleOrDespawnExt {
+    /// Return a pooled entity to its pool, or despawn it recursively if it isn't pooled
+    fn recycle_or_despawn(&mut self, entity: Entity);
+}
+
+impl RecycleOrDespawnExt for Commands<'_, '_> {
+    fn recycle_or_despawn(&mut self, entity: Entity) {
+        self.add(RecycleOrDespawnCommand(entity));
+    }
+}
+
+/// Forget the pooled entities when the game is left, since they are despawned with the rest of
+/// the game
+pub(super) fn clear_spawnable_pool_system(mut spawnable_pool: ResMut<SpawnablePoolResource>) {
+    *spawnable_pool = SpawnablePoolResource::default();
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashMap,
+        f32::consts::FRAC_PI_2,
+        time::{Duration, Instant},
+    };
+
+    use bevy::{ecs::system::Command, prelude::*, time::TimeUpdateStrategy};
+    use bevy_rapier2d::prelude::{
+        Collider, CollisionGroups, NoUserData, RapierConfiguration, RapierPhysicsPlugin, RigidBody,
+        TimestepMode, Velocity,
+    };
+    use ron::de::from_bytes;
+    use thetawave_interface::{
+        audio::{PlaySoundEffectEvent, SoundEffectType},
+        game::options::GameOptions,
+        health::DamageDealtEvent,
+        spawnable::{Faction, ProjectileType, SpawnPosition},
+        weapon::{ArcPatternData, SpreadPattern, WeaponProjectileData},
+    };
+
+    use super::{
+        PoolType, PooledComponent, RecycleOrDespawnCommand, RecycleOrDespawnExt,
+        SpawnablePoolResource,
+    };
+    use crate::{
+        assets::ProjectileAssets,
+        collision::SortedCollisionEvent,
+        game::{GameParametersResource, GameRngResource},
+        spawnable::{
+            projectile::{projectile_execute_behavior_system, spawn_projectile_system},
+            FireWeaponEvent, InitialMotion, ProjectileComponent, ProjectileData,
+            ProjectileResource, SpawnEffectEvent, SpawnableComponent,
+        },
+        status_effect::ApplyStatusEffectEvent,
+        PHYSICS_PIXELS_PER_METER,
+    };
+
+    /// Frames a test bullet exists before it is recycled
+    #[derive(Component)]
+    struct Lifetime(u32);
+
+    const BULLETS_PER_FRAME: usize = 200;
+    const BULLET_LIFETIME: u32 = 30;
+
+    fn fire_bullets_system(
+        mut commands: Commands,
+        mut spawnable_pool: ResMut<SpawnablePoolResource>,
+    ) {
+        for _ in 0..BULLETS_PER_FRAME {
+            spawnable_pool
+                .spawn_pooled(&mut commands, PoolType::Projectile)
+                .insert((
+                    SpriteBundle::default(),
+                    RigidBody::Dynamic,
+                    Velocity::linear(Vec2::new(0.0, 500.0)),
+                    Collider::cuboid(2.0, 2.0),
+                    CollisionGroups::default(),
+                    SpawnableComponent::default(),
+                    PooledComponent::from(PoolType::Projectile),
+                    Lifetime(0),
+                ));
+        }
+    }
+
+    fn expire_bullets_system(mut commands: Commands, mut query: Query<(Entity, &mut Lifetime)>) {
+        for (entity, mut lifetime) in query.iter_mut() {
+            lifetime.0 += 1;
+            if lifetime.0 == BULLET_LIFETIME {
+                commands.recycle_or_despawn(entity);
+            }
+        }
+    }
+
+    #[test]
+    fn test_recycled_entities_are_reused() {
+        let mut world = World::new();
+        world.init_resource::<SpawnablePoolResource>();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems((fire_bullets_system, expire_bullets_system).chain());
+
+        for _ in 0..BULLET_LIFETIME * 3 {
+            schedule.run(&mut world);
+        }
+
+        // once the first bullets expire, new bullets reuse them instead of adding entities
+        let bullet_count = world.query::<&Lifetime>().iter(&world).count();
+        assert_eq!(bullet_count, BULLETS_PER_FRAME * BULLET_LIFETIME as usize);
+
+        // recycling an entity twice only returns it to the pool once
+        let entity = world
+            .query::<(Entity, &PooledComponent)>()
+            .iter(&world)
+            .find(|(_, pooled)| pooled.active)
+            .map(|(entity, _)| entity)
+            .unwrap();
+        let pool_len = world.resource::<SpawnablePoolResource>().projectiles.len();
+        RecycleOrDespawnCommand(entity).apply(&mut world);
+        RecycleOrDespawnCommand(entity).apply(&mut world);
+
+        assert_eq!(
+            world.resource::<SpawnablePoolResource>().projectiles.len(),
+            pool_len + 1
+        );
+        assert_eq!(world.get::<Visibility>(entity), Some(&Visibility::Hidden));
+        assert!(world.get::<Collider>(entity).is_none());
+        assert!(!world.get::<PooledComponent>(entity).unwrap().is_active());
+    }
+
+    /// Length of a frame of the benchmark, the same as the game's physics timestep
+    const FRAME_TIME: f32 = 1.0 / 60.0;
+
+    /// Fires a row of ally bullets up the arena every frame, each bullet timing out after
+    /// `BULLET_LIFETIME` frames
+    fn fire_weapons_system(mut fire_weapon_event_writer: EventWriter<FireWeaponEvent>) {
+        for i in 0..BULLETS_PER_FRAME {
+            fire_weapon_event_writer.send(FireWeaponEvent {
+                weapon_projectile_data: WeaponProjectileData {
+                    ammunition: ProjectileType::Bullet(Faction::Ally),
+                    damage: 1,
+                    // spread the bullets out so that rapier doesn't pair up all their aabbs
+                    position: SpawnPosition::Global(Vec2::new(
+                        (i as f32 - BULLETS_PER_FRAME as f32 / 2.0) * 4.0,
+                        -300.0,
+                    )),
+                    speed: 500.0,
+                    direction: FRAC_PI_2,
+                    aimed: false,
+                    despawn_time: (BULLET_LIFETIME as f32 - 0.5) * FRAME_TIME,
+                    count: 1,
+                    spread_pattern: SpreadPattern::Arc(ArcPatternData {
+                        spread_weights: Vec2::ONE,
+                        max_spread: 0.0,
+                        projectile_gap: 0.0,
+                    }),
+                    size: 1.0,
+                    sound: SoundEffectType::PlayerFireBlast,
+                },
+                source_transform: Transform::default(),
+                source_entity: Entity::PLACEHOLDER,
+                initial_motion: InitialMotion {
+                    linvel: Some(Vec2::ZERO),
+                    ..default()
+                },
+            });
+        }
+    }
+
+    /// Headless app that fires and times out projectiles with the game's own systems and physics.
+    /// The projectiles are despawned instead of pooled when `use_pool` is false.
+    fn new_benchmark_app(use_pool: bool) -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            TransformPlugin,
+            HierarchyPlugin,
+            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(PHYSICS_PIXELS_PER_METER)
+                .in_fixed_schedule(),
+        ))
+        .init_asset::<TextureAtlasLayout>();
+
+        // step physics exactly once per update, as fast as the updates can run
+        app.insert_resource(Time::<Fixed>::from_seconds(FRAME_TIME as f64))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+                FRAME_TIME,
+            )));
+
+        // same physics settings as the game
+        let mut rapier_config = app.world.resource_mut::<RapierConfiguration>();
+        rapier_config.timestep_mode = TimestepMode::Fixed {
+            dt: FRAME_TIME,
+            substeps: 1,
+        };
+        rapier_config.gravity = Vec2::ZERO;
+
+        app.insert_resource(ProjectileResource {
+            projectiles: from_bytes::<HashMap<ProjectileType, ProjectileData>>(include_bytes!(
+                "../../assets/data/projectiles.ron"
+            ))
+            .unwrap(),
+        })
+        .insert_resource(
+            from_bytes::<GameParametersResource>(include_bytes!(
+                "../../assets/data/game_parameters.ron"
+            ))
+            .unwrap(),
+        )
+        .insert_resource(ProjectileAssets {
+            ally_blast_layout: default(),
+            ally_blast_image: default(),
+            enemy_blast_layout: default(),
+            enemy_blast_image: default(),
+            neutral_blast_layout: default(),
+            neutral_blast_image: default(),
+            ally_bullet_layout: default(),
+            ally_bullet_image: default(),
+            enemy_bullet_layout: default(),
+            enemy_bullet_image: default(),
+        })
+        .insert_resource(GameOptions::default())
+        .insert_resource(GameRngResource::new(0))
+        .insert_resource(if use_pool {
+            SpawnablePoolResource::default()
+        } else {
+            SpawnablePoolResource::with_max_size(0)
+        })
+        .add_event::<FireWeaponEvent>()
+        .add_event::<PlaySoundEffectEvent>()
+        .add_event::<SortedCollisionEvent>()
+        .add_event::<SpawnEffectEvent>()
+        .add_event::<DamageDealtEvent>()
+        .add_event::<ApplyStatusEffectEvent>()
+        .add_systems(
+            Update,
+            (
+                fire_weapons_system,
+                spawn_projectile_system,
+                projectile_execute_behavior_system,
+            )
+                .chain(),
+        );
+
+        app.finish();
+        app.cleanup();
+        app
+    }
+
+    /// Average time to run a frame of a bullet-heavy scenario
+    fn average_frame_time(use_pool: bool) -> Duration {
+        let mut app = new_benchmark_app(use_pool);
+
+        // fill the arena with projectiles before measuring
+        for _ in 0..BULLET_LIFETIME * 2 {
+            app.update();
+        }
+
+        // projectiles time out during the last frame of their lifetime, leaving the ones fired
+        // during the frames before it. Recycled projectiles keep their components, so only the
+        // active ones are counted.
+        let active_count = app
+            .world
+            .query_filtered::<&PooledComponent, With<ProjectileComponent>>()
+            .iter(&app.world)
+            .filter(|pooled| pooled.is_active())
+            .count();
+        assert_eq!(
+            active_count,
+            BULLETS_PER_FRAME * (BULLET_LIFETIME as usize - 1)
+        );
+
+        let frames = 600;
+        let start = Instant::now();
+        for _ in 0..frames {
+            app.update();
+        }
+
+        start.elapsed() / frames
+    }
+
+    /// Compares frame times of firing and timing out projectiles with and without pooling, using
+    /// the projectile systems and physics of the game. Run with
+    /// `cargo test --release pool_benchmark -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn pool_benchmark() {
+        let despawned = average_frame_time(false);
+        let pooled = average_frame_time(true);
+
+        println!(
+            "{BULLETS_PER_FRAME} projectiles per frame: {despawned:?} per frame when despawning, \
+             {pooled:?} per frame when pooling"
+        );
+    }
+}
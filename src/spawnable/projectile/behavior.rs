@@ -1,6 +1,7 @@
 use crate::{
     collision::SortedCollisionEvent,
-    spawnable::{MobComponent, MobSegmentComponent, SpawnEffectEvent},
+    spawnable::{MobComponent, MobSegmentComponent, RecycleOrDespawnExt, SpawnEffectEvent},
+    status_effect::{ApplyStatusEffectEvent, StatusEffectData},
 };
 use bevy::prelude::*;
 use serde::Deserialize;
@@ -21,6 +22,8 @@ pub enum ProjectileBehavior {
     DealDamageOnIntersection,
     DealDamageOnContact,
     TimedDespawn { despawn_time: f32 },
+    ApplyStatusEffectsOnIntersection(Vec<StatusEffectData>),
+    ApplyStatusEffectsOnContact(Vec<StatusEffectData>),
 }
 
 /// Manages executing behaviors of all projectiles
@@ -36,6 +39,7 @@ pub fn projectile_execute_behavior_system(
     time: Res<Time>,
     mut sound_effect_event_writer: EventWriter<PlaySoundEffectEvent>,
     mut damage_dealt_event_writer: EventWriter<DamageDealtEvent>,
+    mut apply_status_effect_event_writer: EventWriter<ApplyStatusEffectEvent>,
 ) {
     // Put all collision events in a vec so they can be read more than once
     let collision_events_vec: Vec<_> = collision_events.read().collect();
@@ -80,6 +84,24 @@ pub fn projectile_execute_behavior_system(
                     &mut sound_effect_event_writer,
                     &mut damage_dealt_event_writer,
                 ),
+                ProjectileBehavior::ApplyStatusEffectsOnIntersection(status_effects) => {
+                    apply_status_effects_on_hit(
+                        projectile_entity,
+                        &collision_events_vec,
+                        false,
+                        &status_effects,
+                        &mut apply_status_effect_event_writer,
+                    )
+                }
+                ProjectileBehavior::ApplyStatusEffectsOnContact(status_effects) => {
+                    apply_status_effects_on_hit(
+                        projectile_entity,
+                        &collision_events_vec,
+                        true,
+                        &status_effects,
+                        &mut apply_status_effect_event_writer,
+                    )
+                }
                 ProjectileBehavior::TimedDespawn { despawn_time } => {
                     projectile_component.time_alive += time.delta_seconds();
                     if projectile_component.time_alive > despawn_time {
@@ -137,7 +159,7 @@ pub fn projectile_execute_behavior_system(
                             },
                         }
 
-                        commands.entity(projectile_entity).despawn_recursive();
+                        commands.recycle_or_despawn(projectile_entity);
                     }
                 }
             }
@@ -320,6 +342,82 @@ fn deal_damage_on_intersection(
     }
 }
 
+/// Apply status effects to the players, mobs and mob segments that a projectile hits, through
+/// contacts if `contact` or through intersections otherwise
+fn apply_status_effects_on_hit(
+    projectile: Entity,
+    collision_events: &[&SortedCollisionEvent],
+    contact: bool,
+    status_effects: &[StatusEffectData],
+    apply_status_effect_event_writer: &mut EventWriter<ApplyStatusEffectEvent>,
+) {
+    for collision_event in collision_events.iter() {
+        let target = match collision_event {
+            SortedCollisionEvent::PlayerToProjectileContact {
+                player_entity,
+                projectile_entity,
+                projectile_faction,
+                ..
+            } if contact => (*projectile_entity == projectile
+                && matches!(projectile_faction, Faction::Neutral | Faction::Enemy))
+            .then_some(*player_entity),
+            SortedCollisionEvent::PlayerToProjectileIntersection {
+                player_entity,
+                projectile_entity,
+                projectile_faction,
+                ..
+            } if !contact => (*projectile_entity == projectile
+                && matches!(projectile_faction, Faction::Neutral | Faction::Enemy))
+            .then_some(*player_entity),
+            SortedCollisionEvent::MobToProjectileContact {
+                mob_entity,
+                projectile_entity,
+                mob_faction,
+                projectile_faction,
+                ..
+            } if contact => (*projectile_entity == projectile && mob_faction != projectile_faction)
+                .then_some(*mob_entity),
+            SortedCollisionEvent::MobToProjectileIntersection {
+                mob_entity,
+                projectile_entity,
+                mob_faction,
+                projectile_faction,
+                ..
+            } if !contact => (*projectile_entity == projectile
+                && mob_faction != projectile_faction)
+                .then_some(*mob_entity),
+            SortedCollisionEvent::MobSegmentToProjectileContact {
+                mob_segment_entity,
+                projectile_entity,
+                mob_segment_faction,
+                projectile_faction,
+                ..
+            } if contact => (*projectile_entity == projectile
+                && mob_segment_faction != projectile_faction)
+                .then_some(*mob_segment_entity),
+            SortedCollisionEvent::MobSegmentToProjectileIntersection {
+                mob_segment_entity,
+                projectile_entity,
+                mob_segment_faction,
+                projectile_faction,
+                ..
+            } if !contact => (*projectile_entity == projectile
+                && mob_segment_faction != projectile_faction)
+                .then_some(*mob_segment_entity),
+            _ => None,
+        };
+
+        if let Some(target) = target {
+            for status_effect in status_effects.iter() {
+                apply_status_effect_event_writer.send(ApplyStatusEffectEvent {
+                    target,
+                    status_effect: status_effect.clone(),
+                });
+            }
+        }
+    }
+}
+
 /// Explode a specific projectile on impact
 fn explode_on_intersection(
     commands: &mut Commands,
@@ -355,7 +453,7 @@ fn explode_on_intersection(
                     });
 
                     // despawn blast
-                    commands.entity(projectile).despawn_recursive();
+                    commands.recycle_or_despawn(projectile);
                 }
             }
 
@@ -406,7 +504,7 @@ fn explode_on_intersection(
                     }
 
                     // despawn blast
-                    commands.entity(projectile).despawn_recursive();
+                    commands.recycle_or_despawn(projectile);
                 }
             }
             SortedCollisionEvent::MobSegmentToProjectileIntersection {
@@ -455,7 +553,7 @@ fn explode_on_intersection(
                     }
 
                     // despawn blast
-                    commands.entity(projectile).despawn_recursive();
+                    commands.recycle_or_despawn(projectile);
                 }
             }
             _ => {}
@@ -498,7 +596,7 @@ fn explode_on_contact(
                     });
 
                     // despawn blast
-                    commands.entity(projectile).despawn_recursive();
+                    commands.recycle_or_despawn(projectile);
 
                     continue;
                 }
@@ -545,7 +643,7 @@ fn explode_on_contact(
                     }
 
                     // despawn blast
-                    commands.entity(projectile).despawn_recursive();
+                    commands.recycle_or_despawn(projectile);
                     continue;
                 }
             }
@@ -590,7 +688,7 @@ fn explode_on_contact(
                     }
 
                     // despawn blast
-                    commands.entity(projectile).despawn_recursive();
+                    commands.recycle_or_despawn(projectile);
                     continue;
                 }
             }
@@ -630,7 +728,7 @@ fn explode_on_contact(
                     }
 
                     // despawn blast
-                    commands.entity(projectile).despawn_recursive();
+                    commands.recycle_or_despawn(projectile);
                     continue;
                 }
             }
@@ -1,19 +1,19 @@
 use bevy::{
     prelude::{
-        Commands, Component, Entity, Event, EventReader, EventWriter, Name, Quat, Res, Resource,
-        Sprite, SpriteSheetBundle, Timer, TimerMode, Transform, Vec2, Vec3Swizzles,
+        Commands, Component, Entity, Event, EventReader, EventWriter, Name, Quat, Res, ResMut,
+        Resource, Sprite, SpriteSheetBundle, Timer, TimerMode, Transform, Vec2, Vec3Swizzles,
     },
     render::color::Color,
 };
 use bevy_rapier2d::prelude::{
-    ActiveEvents, Collider, CollisionGroups, Group, LockedAxes, RigidBody, Sensor, Velocity,
+    ActiveEvents, Collider, CollisionGroups, Group, LockedAxes, RigidBody, Sensor,
 };
 use serde::Deserialize;
 use std::collections::HashMap;
 use thetawave_interface::{
     audio::PlaySoundEffectEvent,
     game::options::GameOptions,
-    spawnable::{Faction, ProjectileType, SpawnableType},
+    spawnable::{Faction, ProjectileType, SpawnableType, TargetingStrategy},
     states::GameCleanup,
     weapon::WeaponProjectileData,
 };
@@ -25,8 +25,11 @@ use crate::collision::{
 use crate::{
     animation::{AnimationComponent, AnimationData},
     assets::ProjectileAssets,
-    game::GameParametersResource,
-    spawnable::{SpawnableBehavior, SpawnableComponent},
+    game::{GameParametersResource, GameRngResource, RngStream},
+    spawnable::{
+        PoolType, PooledComponent, SpawnableBehavior, SpawnableComponent, SpawnableParentComponent,
+        SpawnablePoolResource, TargetingComponent,
+    },
     weapon::WeaponProjectileInitialVelocitiesExt,
 };
 
@@ -66,6 +69,9 @@ pub struct ProjectileData {
     pub projectile_type: ProjectileType,
     /// List of spawnable behaviors that are performed
     pub spawnable_behaviors: Vec<SpawnableBehavior>,
+    /// How the projectile picks what to rotate or dash toward
+    #[serde(default)]
+    pub targeting: TargetingStrategy,
     /// List of projectile behaviors that are performed
     pub projectile_behaviors: Vec<ProjectileBehavior>,
     /// Animation (currently loops single animation in specified direction)
@@ -98,6 +104,7 @@ pub struct ProjectileResource {
 }
 
 /// Spawns projectiles from events
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_projectile_system(
     mut commands: Commands,
     mut fire_weapon_event_reader: EventReader<FireWeaponEvent>,
@@ -106,6 +113,8 @@ pub fn spawn_projectile_system(
     projectile_assets: Res<ProjectileAssets>,
     game_parameters: Res<GameParametersResource>,
     game_options: Res<GameOptions>,
+    mut game_rng: ResMut<GameRngResource>,
+    mut spawnable_pool: ResMut<SpawnablePoolResource>,
 ) {
     for event in fire_weapon_event_reader.read() {
         spawn_projectile_from_weapon(
@@ -119,10 +128,13 @@ pub fn spawn_projectile_system(
             &projectile_assets,
             &game_parameters,
             &game_options,
+            &mut game_rng,
+            &mut spawnable_pool,
         );
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_projectile_from_weapon(
     commands: &mut Commands,
     sound_effect_event_writer: &mut EventWriter<PlaySoundEffectEvent>,
@@ -134,6 +146,8 @@ pub fn spawn_projectile_from_weapon(
     projectile_assets: &ProjectileAssets,
     game_parameters: &GameParametersResource,
     game_options: &GameOptions,
+    game_rng: &mut GameRngResource,
+    spawnable_pool: &mut SpawnablePoolResource,
 ) {
     // Play the sound effect for the projectiles firing
     sound_effect_event_writer.send(PlaySoundEffectEvent {
@@ -167,7 +181,10 @@ pub fn spawn_projectile_from_weapon(
         get_projectile_collider_group(weapon_projectile_data.ammunition.get_faction());
 
     // Get a vec of linvels to create the spread pattern
-    let spread_linvels = weapon_projectile_data.get_linvels(game_parameters.max_player_projectiles);
+    let spread_linvels = weapon_projectile_data.get_linvels(
+        game_parameters.max_player_projectiles,
+        game_rng.stream(RngStream::Weapons),
+    );
 
     for linvel in spread_linvels {
         let new_initial_motion =
@@ -183,8 +200,8 @@ pub fn spawn_projectile_from_weapon(
                 initial_motion.clone()
             };
 
-        // create projectile entity
-        let mut projectile = commands.spawn_empty();
+        // create projectile entity, reusing a recycled one if there is one
+        let mut projectile = spawnable_pool.spawn_pooled(commands, PoolType::Projectile);
 
         projectile
             .insert(LockedAxes::ROTATION_LOCKED)
@@ -208,7 +225,7 @@ pub fn spawn_projectile_from_weapon(
                 direction: projectile_data.animation.direction.clone(),
             })
             .insert(RigidBody::Dynamic)
-            .insert(Velocity::from(new_initial_motion))
+            .insert(new_initial_motion.get_velocity(game_rng.stream(RngStream::Motion)))
             .insert(projectile_transform)
             .insert(Collider::cuboid(
                 projectile_data.collider.dimensions.x,
@@ -221,6 +238,7 @@ pub fn spawn_projectile_from_weapon(
                 time_alive: 0.0,
                 source: source_entity,
             })
+            .insert(SpawnableParentComponent(source_entity))
             .insert(SpawnableComponent {
                 spawnable_type: SpawnableType::Projectile(projectile_data.projectile_type.clone()),
                 acceleration: Vec2::ZERO,
@@ -239,11 +257,21 @@ pub fn spawn_projectile_from_weapon(
                         | projectile_colider_group),
             })
             .insert(GameCleanup)
+            .insert(PooledComponent::from(PoolType::Projectile))
             .insert(Name::new(projectile_data.projectile_type.to_string()));
 
-        if !projectile_data.is_solid {
+        // recycled projectiles may have been fired with different ammunition
+        if projectile_data.is_solid {
+            projectile.remove::<Sensor>();
+        } else {
             projectile.insert(Sensor);
         }
+
+        if SpawnableBehavior::needs_target(&projectile_data.spawnable_behaviors) {
+            projectile.insert(TargetingComponent::from(projectile_data.targeting.clone()));
+        } else {
+            projectile.remove::<TargetingComponent>();
+        }
     }
 }
 
@@ -0,0 +1,145 @@
+//! Picks what spawnables aim at using the targeting strategies declared in their data.
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+use thetawave_interface::spawnable::TargetingStrategy;
+
+/// What a spawnable aims at. Only added to spawnables that rotate toward a target or fire aimed
+/// weapons.
+#[derive(Component, Debug, Clone)]
+pub struct TargetingComponent {
+    pub strategy: TargetingStrategy,
+    /// Entity being targeted, kept so that strategies that don't depend on distance stick with
+    /// the same target
+    target_entity: Option<Entity>,
+    /// Position of the target, None if there is nothing to target
+    pub target_position: Option<Vec2>,
+}
+
+impl From<TargetingStrategy> for TargetingComponent {
+    fn from(strategy: TargetingStrategy) -> Self {
+        TargetingComponent {
+            strategy,
+            target_entity: None,
+            target_position: None,
+        }
+    }
+}
+
+/// Something that can be targeted, with what targeting strategies need to know about it
+#[derive(Debug, Clone, Copy)]
+pub struct TargetCandidate {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub health: usize,
+}
+
+/// Approximation of a distance to compare with, since floats are not totally ordered
+fn distance_key(position: Vec2, candidate: &TargetCandidate) -> i32 {
+    (position.distance(candidate.position) * 100.0) as i32
+}
+
+impl TargetingComponent {
+    /// Pick the target of a spawnable at `position` from the players and mobs allied with them
+    pub fn update_target(
+        &mut self,
+        position: Vec2,
+        players: &[TargetCandidate],
+        ally_mobs: &[TargetCandidate],
+        rng: &mut impl Rng,
+    ) {
+        let target = match &self.strategy {
+            TargetingStrategy::ClosestPlayer => players
+                .iter()
+                .min_by_key(|candidate| distance_key(position, candidate)),
+            TargetingStrategy::LowestHealthPlayer => players
+                .iter()
+                .min_by_key(|candidate| (candidate.health, distance_key(position, candidate))),
+            TargetingStrategy::RandomPlayer => players
+                .iter()
+                .find(|candidate| Some(candidate.entity) == self.target_entity)
+                .or_else(|| players.choose(rng)),
+            TargetingStrategy::ClosestAllyMob => ally_mobs
+                .iter()
+                .min_by_key(|candidate| distance_key(position, candidate)),
+            TargetingStrategy::FixedPoint(point) => {
+                self.target_entity = None;
+                self.target_position = Some(*point);
+                return;
+            }
+        };
+
+        self.target_entity = target.map(|candidate| candidate.entity);
+        self.target_position = target.map(|candidate| candidate.position);
+    }
+
+    /// Angle in radians from `position` to the target, if there is one
+    pub fn get_angle_to_target(&self, position: Vec2) -> Option<f32> {
+        self.target_position.map(|target_position| {
+            let offset = target_position - position;
+            offset.y.atan2(offset.x)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::{Entity, Vec2};
+    use rand::{rngs::StdRng, SeedableRng};
+    use thetawave_interface::spawnable::TargetingStrategy;
+
+    use super::{TargetCandidate, TargetingComponent};
+
+    fn candidate(index: u32, position: Vec2, health: usize) -> TargetCandidate {
+        TargetCandidate {
+            entity: Entity::from_raw(index),
+            position,
+            health,
+        }
+    }
+
+    #[test]
+    fn test_targeting_strategies_pick_targets() {
+        let players = [
+            candidate(0, Vec2::new(0.0, -100.0), 50),
+            candidate(1, Vec2::new(0.0, -300.0), 20),
+        ];
+        let ally_mobs = [candidate(2, Vec2::new(200.0, 0.0), 100)];
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let target_of = |strategy: TargetingStrategy, rng: &mut StdRng| {
+            let mut targeting = TargetingComponent::from(strategy);
+            targeting.update_target(Vec2::ZERO, &players, &ally_mobs, rng);
+            targeting.target_position
+        };
+
+        assert_eq!(
+            target_of(TargetingStrategy::ClosestPlayer, &mut rng),
+            Some(players[0].position)
+        );
+        assert_eq!(
+            target_of(TargetingStrategy::LowestHealthPlayer, &mut rng),
+            Some(players[1].position)
+        );
+        assert_eq!(
+            target_of(TargetingStrategy::ClosestAllyMob, &mut rng),
+            Some(ally_mobs[0].position)
+        );
+        assert_eq!(
+            target_of(TargetingStrategy::FixedPoint(Vec2::X), &mut rng),
+            Some(Vec2::X)
+        );
+
+        // random targets are kept until they are gone
+        let mut targeting = TargetingComponent::from(TargetingStrategy::RandomPlayer);
+        targeting.update_target(Vec2::ZERO, &players, &ally_mobs, &mut rng);
+        let first_target = targeting.target_position;
+        for _ in 0..10 {
+            targeting.update_target(Vec2::ZERO, &players, &ally_mobs, &mut rng);
+            assert_eq!(targeting.target_position, first_target);
+        }
+
+        // there's nothing to target without players
+        targeting.update_target(Vec2::ZERO, &[], &ally_mobs, &mut rng);
+        assert_eq!(targeting.target_position, None);
+    }
+}
@@ -0,0 +1,654 @@
+//! Checks the references between the mob, mob segment, behavior sequence, loot, projectile and
+//! consumable data files, the movement behaviors of spawnables and the status effects
+//! they apply.
+use std::collections::{HashMap, HashSet};
+use thetawave_interface::spawnable::{
+    ConsumableType, MobSegmentType, MobType, ProjectileType, SpawnableType, TargetingStrategy,
+};
+
+use crate::{
+    loot::{DropListType, LootDrop, LootDropsResource},
+    status_effect::{StatusEffect, StatusEffectData},
+    validation::DataValidator,
+};
+
+use super::{
+    BehaviorSequenceResource, ConsumableData, ConsumableEffect, MobBehavior, MobData,
+    MobSegmentBehavior, MobSegmentControlBehavior, MobSegmentData, MobSegmentsResource,
+    MobSpawnerData, ProjectileBehavior, ProjectileData, SpawnableBehavior,
+};
+
+const MOBS_FILE: &str = "mobs.ron";
+const MOB_SEGMENTS_FILE: &str = "mob_segments.ron";
+const BEHAVIOR_SEQUENCES_FILE: &str = "behavior_sequences.ron";
+const LOOT_DROPS_FILE: &str = "loot_drops.ron";
+const PROJECTILES_FILE: &str = "projectiles.ron";
+const CONSUMABLES_FILE: &str = "consumables.ron";
+
+/// Validate the spawnable data files. Returns the types of mobs that are defined, so that other
+/// data files can be checked against them.
+pub(crate) fn validate_data(validator: &mut DataValidator) -> HashSet<MobType> {
+    let mobs = validator
+        .load::<HashMap<MobType, MobData>>(MOBS_FILE)
+        .unwrap_or_default();
+    let mob_segments = validator
+        .load::<MobSegmentsResource>(MOB_SEGMENTS_FILE)
+        .map(|res| res.mob_segments)
+        .unwrap_or_default();
+    let behavior_sequences = validator.load::<BehaviorSequenceResource>(BEHAVIOR_SEQUENCES_FILE);
+    let loot_drops = validator.load::<LootDropsResource>(LOOT_DROPS_FILE);
+    let projectiles = validator
+        .load::<HashMap<ProjectileType, ProjectileData>>(PROJECTILES_FILE)
+        .unwrap_or_default();
+    let consumables = validator
+        .load::<HashMap<ConsumableType, ConsumableData>>(CONSUMABLES_FILE)
+        .unwrap_or_default();
+
+    let mob_types: HashSet<MobType> = mobs.keys().cloned().collect();
+
+    for (mob_type, mob_data) in mobs.iter() {
+        let key = format!("{mob_type:?}");
+
+        check_mob_spawners(
+            validator,
+            MOBS_FILE,
+            &key,
+            &mob_data.mob_spawners,
+            &mob_types,
+        );
+
+        check_spawnable_behaviors(validator, MOBS_FILE, &key, &mob_data.spawnable_behaviors);
+
+        check_control_behaviors(
+            validator,
+            MOBS_FILE,
+            &key,
+            &mob_data.mob_segment_behaviors,
+            &mob_data.control_behaviors,
+        );
+
+        for behavior in mob_data.mob_behaviors.iter() {
+            if let MobBehavior::SpawnMob(spawner_key) = behavior {
+                check_spawner_key(
+                    validator,
+                    MOBS_FILE,
+                    &key,
+                    &mob_data.mob_spawners,
+                    spawner_key,
+                );
+            }
+        }
+
+        if let Some(sequence_type) = &mob_data.behavior_sequence_type {
+            match behavior_sequences
+                .as_ref()
+                .and_then(|res| res.sequences.get(sequence_type))
+            {
+                Some(sequence) => {
+                    // behaviors in the sequence are performed by this mob, so spawn keys must be
+                    // in this mob's spawners and control behaviors in its segment behaviors
+                    for element in sequence.behaviors.iter() {
+                        check_control_behaviors(
+                            validator,
+                            MOBS_FILE,
+                            &key,
+                            &mob_data.mob_segment_behaviors,
+                            &element.control_behaviors,
+                        );
+
+                        for behavior in element.mob_behaviors.iter() {
+                            if let MobBehavior::SpawnMob(spawner_key) = behavior {
+                                check_spawner_key(
+                                    validator,
+                                    MOBS_FILE,
+                                    &key,
+                                    &mob_data.mob_spawners,
+                                    spawner_key,
+                                );
+                            }
+                        }
+                    }
+                }
+                None if behavior_sequences.is_some() => validator.report(
+                    MOBS_FILE,
+                    &key,
+                    format!("unknown behavior sequence {sequence_type:?}"),
+                ),
+                None => {}
+            }
+        }
+
+        for boss_stage in mob_data.boss_stages.iter() {
+            if !(0.0..=1.0).contains(&boss_stage.health_threshold) {
+                validator.report(
+                    MOBS_FILE,
+                    &key,
+                    format!(
+                        "boss stage health threshold {} is not between 0.0 and 1.0",
+                        boss_stage.health_threshold
+                    ),
+                );
+            }
+
+            if let (Some(sequence_type), Some(behavior_sequences)) =
+                (&boss_stage.behavior_sequence, &behavior_sequences)
+            {
+                match behavior_sequences.sequences.get(sequence_type) {
+                    Some(sequence) => {
+                        for element in sequence.behaviors.iter() {
+                            check_control_behaviors(
+                                validator,
+                                MOBS_FILE,
+                                &key,
+                                &mob_data.mob_segment_behaviors,
+                                &element.control_behaviors,
+                            );
+                        }
+                    }
+                    None => validator.report(
+                        MOBS_FILE,
+                        &key,
+                        format!("unknown behavior sequence {sequence_type:?} in boss stage"),
+                    ),
+                }
+            }
+
+            if let Some(behaviors) = &boss_stage.behaviors {
+                check_spawnable_behaviors(
+                    validator,
+                    MOBS_FILE,
+                    &key,
+                    &behaviors.spawnable_behaviors,
+                );
+
+                check_control_behaviors(
+                    validator,
+                    MOBS_FILE,
+                    &key,
+                    &mob_data.mob_segment_behaviors,
+                    &behaviors.control_behaviors,
+                );
+            }
+
+            for behavior in boss_stage
+                .behaviors
+                .iter()
+                .flat_map(|behaviors| behaviors.mob_behaviors.iter())
+            {
+                if let MobBehavior::SpawnMob(spawner_key) = behavior {
+                    check_spawner_key(
+                        validator,
+                        MOBS_FILE,
+                        &key,
+                        &mob_data.mob_spawners,
+                        spawner_key,
+                    );
+                }
+            }
+
+            for reinforcement in boss_stage.reinforcements.iter() {
+                if !mob_types.contains(&reinforcement.mob_type) {
+                    validator.report(
+                        MOBS_FILE,
+                        &key,
+                        format!(
+                            "unknown mob type {:?} in boss stage reinforcements",
+                            reinforcement.mob_type
+                        ),
+                    );
+                }
+            }
+        }
+
+        // mobs are given a target once any of their steps or stages needs one, so a targeting
+        // strategy is only wasted if none of them ever does
+        if mob_data.targeting != TargetingStrategy::default()
+            && !mob_ever_needs_target(mob_data, behavior_sequences.as_ref())
+        {
+            validator.report(
+                MOBS_FILE,
+                &key,
+                format!(
+                    "targeting strategy {:?} is never used, no behavior, behavior sequence step, \
+                     boss stage or aimed weapon of the mob needs a target",
+                    mob_data.targeting
+                ),
+            );
+        }
+
+        check_drop_list(
+            validator,
+            MOBS_FILE,
+            &key,
+            &loot_drops,
+            &mob_data.consumable_drops,
+        );
+
+        for anchor_point in mob_data.mob_segment_anchor_points.iter() {
+            check_mob_segment_type(
+                validator,
+                MOBS_FILE,
+                &key,
+                &mob_segments,
+                &anchor_point.mob_segment_type,
+            );
+        }
+
+        for segment_behaviors in mob_data
+            .mob_segment_behaviors
+            .iter()
+            .flat_map(|m| m.values())
+        {
+            for (mob_segment_type, behaviors) in segment_behaviors.iter() {
+                check_mob_segment_type(validator, MOBS_FILE, &key, &mob_segments, mob_segment_type);
+
+                // these behaviors are performed by the segment, so spawn keys must be in the
+                // segment's spawners
+                let mob_spawners = mob_segments
+                    .get(mob_segment_type)
+                    .and_then(|mob_segment_data| mob_segment_data.mob_spawners.clone())
+                    .unwrap_or_default();
+                for behavior in behaviors.iter() {
+                    if let MobSegmentBehavior::SpawnMob(spawner_key) = behavior {
+                        check_spawner_key(validator, MOBS_FILE, &key, &mob_spawners, spawner_key);
+                    }
+                }
+
+                check_joint_behaviors(validator, MOBS_FILE, &key, behaviors);
+            }
+        }
+
+        if let Some(weapon) = &mob_data.weapon {
+            let ammunition = &weapon.projectile_data.ammunition;
+            if !projectiles.contains_key(ammunition) {
+                validator.report(
+                    MOBS_FILE,
+                    &key,
+                    format!("unknown projectile type {ammunition:?} in weapon"),
+                );
+            }
+        }
+    }
+
+    for (mob_segment_type, mob_segment_data) in mob_segments.iter() {
+        let key = format!("{mob_segment_type:?}");
+        let mob_spawners = mob_segment_data.mob_spawners.clone().unwrap_or_default();
+
+        check_mob_spawners(
+            validator,
+            MOB_SEGMENTS_FILE,
+            &key,
+            &mob_spawners,
+            &mob_types,
+        );
+
+        for behavior in mob_segment_data
+            .behaviors
+            .iter()
+            .chain(mob_segment_data.disconnected_behaviors.iter().flatten())
+        {
+            if let MobSegmentBehavior::SpawnMob(spawner_key) = behavior {
+                check_spawner_key(
+                    validator,
+                    MOB_SEGMENTS_FILE,
+                    &key,
+                    &mob_spawners,
+                    spawner_key,
+                );
+            }
+        }
+
+        check_joint_behaviors(
+            validator,
+            MOB_SEGMENTS_FILE,
+            &key,
+            &mob_segment_data.behaviors,
+        );
+        if let Some(disconnected_behaviors) = &mob_segment_data.disconnected_behaviors {
+            check_joint_behaviors(validator, MOB_SEGMENTS_FILE, &key, disconnected_behaviors);
+        }
+
+        check_drop_list(
+            validator,
+            MOB_SEGMENTS_FILE,
+            &key,
+            &loot_drops,
+            &mob_segment_data.consumable_drops,
+        );
+
+        for anchor_point in mob_segment_data.mob_segment_anchor_points.iter().flatten() {
+            check_mob_segment_type(
+                validator,
+                MOB_SEGMENTS_FILE,
+                &key,
+                &mob_segments,
+                &anchor_point.mob_segment_type,
+            );
+        }
+    }
+
+    for (sequence_type, sequence) in behavior_sequences
+        .iter()
+        .flat_map(|res| res.sequences.iter())
+    {
+        if sequence.behaviors.is_empty() {
+            validator.report(
+                BEHAVIOR_SEQUENCES_FILE,
+                sequence_type,
+                "sequence has no steps",
+            );
+        }
+
+        for element in sequence.behaviors.iter() {
+            check_spawnable_behaviors(
+                validator,
+                BEHAVIOR_SEQUENCES_FILE,
+                sequence_type,
+                &element.spawnable_behaviors,
+            );
+        }
+
+        for transition in sequence
+            .behaviors
+            .iter()
+            .flat_map(|element| element.transitions.iter())
+        {
+            if transition.step >= sequence.behaviors.len() {
+                validator.report(
+                    BEHAVIOR_SEQUENCES_FILE,
+                    sequence_type,
+                    format!("transition to unknown step {}", transition.step),
+                );
+            }
+        }
+    }
+
+    for (projectile_type, projectile_data) in projectiles.iter() {
+        check_spawnable_behaviors(
+            validator,
+            PROJECTILES_FILE,
+            &format!("{projectile_type:?}"),
+            &projectile_data.spawnable_behaviors,
+        );
+
+        for behavior in projectile_data.projectile_behaviors.iter() {
+            if let ProjectileBehavior::ApplyStatusEffectsOnIntersection(status_effects)
+            | ProjectileBehavior::ApplyStatusEffectsOnContact(status_effects) = behavior
+            {
+                check_status_effects(
+                    validator,
+                    PROJECTILES_FILE,
+                    &format!("{projectile_type:?}"),
+                    status_effects,
+                );
+            }
+        }
+    }
+
+    for (consumable_type, consumable_data) in consumables.iter() {
+        check_spawnable_behaviors(
+            validator,
+            CONSUMABLES_FILE,
+            &format!("{consumable_type:?}"),
+            &consumable_data.spawnable_behaviors,
+        );
+
+        for effect in consumable_data.consumable_effects.iter() {
+            if let ConsumableEffect::ApplyStatusEffect(status_effect) = effect {
+                check_status_effects(
+                    validator,
+                    CONSUMABLES_FILE,
+                    &format!("{consumable_type:?}"),
+                    std::slice::from_ref(status_effect),
+                );
+            }
+        }
+    }
+
+    if let Some(loot_drops) = &loot_drops {
+        for (drop_list_type, drop_list) in loot_drops.drops.iter() {
+            for loot_drop in drop_list.iter() {
+                if let LootDrop::Consumable(consumable_loot_drop) = loot_drop {
+                    if !consumables.contains_key(&consumable_loot_drop.consumable) {
+                        validator.report(
+                            LOOT_DROPS_FILE,
+                            drop_list_type,
+                            format!(
+                                "unknown consumable type {:?}",
+                                consumable_loot_drop.consumable
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    mob_types
+}
+
+/// Check that the mobs spawned by mob spawners are defined
+fn check_mob_spawners(
+    validator: &mut DataValidator,
+    file: &str,
+    key: &str,
+    mob_spawners: &HashMap<String, Vec<MobSpawnerData>>,
+    mob_types: &HashSet<MobType>,
+) {
+    for (spawner_key, spawners) in mob_spawners.iter() {
+        for spawner in spawners.iter() {
+            if !mob_types.contains(&spawner.mob_type) {
+                validator.report(
+                    file,
+                    key,
+                    format!(
+                        "unknown mob type {:?} in mob spawner \"{spawner_key}\"",
+                        spawner.mob_type
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Check that the movement behaviors of a spawnable describe movements that can be performed
+fn check_spawnable_behaviors(
+    validator: &mut DataValidator,
+    file: &str,
+    key: &str,
+    behaviors: &[SpawnableBehavior],
+) {
+    for behavior in behaviors.iter() {
+        let error = match behavior {
+            SpawnableBehavior::SineWave(sine_wave) if sine_wave.frequency <= 0.0 => {
+                Some("SineWave behavior frequency is not positive")
+            }
+            SpawnableBehavior::WaypointPath(waypoint_path)
+                if waypoint_path.waypoints.is_empty() =>
+            {
+                Some("WaypointPath behavior has no waypoints")
+            }
+            SpawnableBehavior::Orbit(orbit) if orbit.radius <= 0.0 => {
+                Some("Orbit behavior radius is not positive")
+            }
+            SpawnableBehavior::Dash(dash) if dash.period <= 0.0 => {
+                Some("Dash behavior period is not positive")
+            }
+            _ => None,
+        };
+
+        if let Some(error) = error {
+            validator.report(file, key, error);
+        }
+    }
+}
+
+/// Whether the mob needs a target when spawned, in a step of one of its behavior sequences or in
+/// one of its boss stages
+fn mob_ever_needs_target(
+    mob_data: &MobData,
+    behavior_sequences: Option<&BehaviorSequenceResource>,
+) -> bool {
+    let sequence_steps = mob_data
+        .behavior_sequence_type
+        .iter()
+        .chain(
+            mob_data
+                .boss_stages
+                .iter()
+                .filter_map(|boss_stage| boss_stage.behavior_sequence.as_ref()),
+        )
+        .filter_map(|sequence_type| behavior_sequences?.sequences.get(sequence_type))
+        .flat_map(|sequence| sequence.behaviors.iter())
+        .map(|element| element.spawnable_behaviors.as_slice());
+
+    let boss_stage_behaviors = mob_data
+        .boss_stages
+        .iter()
+        .filter_map(|boss_stage| boss_stage.behaviors.as_ref())
+        .map(|behaviors| behaviors.spawnable_behaviors.as_slice());
+
+    mob_data.needs_target()
+        || sequence_steps
+            .chain(boss_stage_behaviors)
+            .any(SpawnableBehavior::needs_target)
+}
+
+/// Check that status effects last for some time, and that burns deal damage at a positive interval
+fn check_status_effects(
+    validator: &mut DataValidator,
+    file: &str,
+    key: &str,
+    status_effects: &[StatusEffectData],
+) {
+    for status_effect in status_effects.iter() {
+        if status_effect.duration <= 0.0 {
+            validator.report(
+                file,
+                key,
+                format!(
+                    "{:?} status effect duration is not positive",
+                    status_effect.effect
+                ),
+            );
+        }
+
+        if let StatusEffect::Burn { interval, .. } = status_effect.effect {
+            if interval <= 0.0 {
+                validator.report(file, key, "Burn status effect interval is not positive");
+            }
+        }
+    }
+}
+
+/// Check that the control behaviors a mob uses are ones it has mob segment behaviors for
+fn check_control_behaviors(
+    validator: &mut DataValidator,
+    file: &str,
+    key: &str,
+    mob_segment_behaviors: &Option<
+        HashMap<MobSegmentControlBehavior, HashMap<MobSegmentType, Vec<MobSegmentBehavior>>>,
+    >,
+    control_behaviors: &[MobSegmentControlBehavior],
+) {
+    for control_behavior in control_behaviors.iter() {
+        if !mob_segment_behaviors
+            .as_ref()
+            .is_some_and(|behaviors| behaviors.contains_key(control_behavior))
+        {
+            validator.report(
+                file,
+                key,
+                format!("no mob segment behaviors for control behavior {control_behavior:?}"),
+            );
+        }
+    }
+}
+
+/// Check that the joint motor behaviors of a mob segment move at a rate that can be followed
+fn check_joint_behaviors(
+    validator: &mut DataValidator,
+    file: &str,
+    key: &str,
+    behaviors: &[MobSegmentBehavior],
+) {
+    for behavior in behaviors.iter() {
+        let error = match behavior {
+            MobSegmentBehavior::SweepJointMotor(sweep) if sweep.sweep_time <= 0.0 => {
+                Some("SweepJointMotor behavior sweep time is not positive")
+            }
+            MobSegmentBehavior::OscillateJointMotor(oscillate) if oscillate.frequency <= 0.0 => {
+                Some("OscillateJointMotor behavior frequency is not positive")
+            }
+            _ => None,
+        };
+
+        if let Some(error) = error {
+            validator.report(file, key, error);
+        }
+    }
+}
+
+/// Check that a `SpawnMob` behavior uses a mob spawner that is defined
+fn check_spawner_key(
+    validator: &mut DataValidator,
+    file: &str,
+    key: &str,
+    mob_spawners: &HashMap<String, Vec<MobSpawnerData>>,
+    spawner_key: &str,
+) {
+    if !mob_spawners.contains_key(spawner_key) {
+        validator.report(
+            file,
+            key,
+            format!("SpawnMob behavior uses unknown mob spawner \"{spawner_key}\""),
+        );
+    }
+}
+
+/// Check that a drop list is defined in the loot drops file
+fn check_drop_list(
+    validator: &mut DataValidator,
+    file: &str,
+    key: &str,
+    loot_drops: &Option<LootDropsResource>,
+    drop_list_type: &DropListType,
+) {
+    if let Some(loot_drops) = loot_drops {
+        if !loot_drops.drops.contains_key(drop_list_type) {
+            validator.report(file, key, format!("unknown drop list {drop_list_type:?}"));
+        }
+    }
+}
+
+/// Check that a mob segment is defined in the mob segments file
+fn check_mob_segment_type(
+    validator: &mut DataValidator,
+    file: &str,
+    key: &str,
+    mob_segments: &HashMap<MobSegmentType, MobSegmentData>,
+    mob_segment_type: &MobSegmentType,
+) {
+    if !mob_segments.contains_key(mob_segment_type) {
+        validator.report(
+            file,
+            key,
+            format!("unknown mob segment type {mob_segment_type:?}"),
+        );
+    }
+}
+
+/// Check that a spawnable in a formation is defined
+pub(crate) fn check_spawnable_type(
+    validator: &mut DataValidator,
+    file: &str,
+    key: &str,
+    mob_types: &HashSet<MobType>,
+    spawnable_type: &SpawnableType,
+) {
+    if let SpawnableType::Mob(mob_type) = spawnable_type {
+        if !mob_types.contains(mob_type) {
+            validator.report(file, key, format!("unknown mob type {mob_type:?}"));
+        }
+    }
+}
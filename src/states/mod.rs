@@ -12,6 +12,7 @@ use leafwing_input_manager::prelude::ActionState;
 use thetawave_interface::input::MenuAction;
 use thetawave_interface::input::MenuExplorer;
 use thetawave_interface::states::CharacterSelectionCleanup;
+use thetawave_interface::states::DailyLeaderboardCleanup;
 use thetawave_interface::states::GameCleanup;
 use thetawave_interface::states::GameOverCleanup;
 use thetawave_interface::states::InstructionsCleanup;
@@ -157,6 +158,11 @@ impl Plugin for StatesPlugin {
             clear_state_system::<InstructionsCleanup>,
         );
 
+        app.add_systems(
+            OnExit(AppStates::DailyLeaderboard),
+            clear_state_system::<DailyLeaderboardCleanup>,
+        );
+
         app.add_systems(
             Update,
             close_pause_menu_system.run_if(in_state(GameStates::Paused)),
@@ -0,0 +1,333 @@
+//! Exposes a plugin for status effects: lasting effects like slows, burns, stuns and vulnerability
+//! that projectiles and consumables apply to mobs, mob segments and players.
+use std::{collections::HashMap, mem::discriminant, time::Duration};
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::Velocity;
+use serde::Deserialize;
+use thetawave_interface::{
+    health::{DamageDealtEvent, HealthComponent},
+    player::PlayerMovementComponent,
+    states,
+};
+
+use crate::{spawnable::SpawnableComponent, GameUpdateSet};
+
+/// Includes systems that apply status effects, tick them down, and show them on the affected
+/// entities.
+pub(super) struct StatusEffectPlugin;
+
+impl Plugin for StatusEffectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ApplyStatusEffectEvent>().add_systems(
+            Update,
+            (
+                apply_status_effects_system,
+                tick_status_effects_system.after(apply_status_effects_system),
+                slow_status_effect_system
+                    .after(GameUpdateSet::Movement)
+                    .after(GameUpdateSet::ExecuteBehavior),
+                status_effect_tint_system,
+            )
+                .run_if(in_state(states::AppStates::Game))
+                .run_if(in_state(states::GameStates::Playing)),
+        );
+    }
+}
+
+/// Kinds of status effects, with their magnitudes
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub enum StatusEffect {
+    /// Maximum speed is multiplied by the factor
+    Slow(f32),
+    /// Damage is dealt every `interval` seconds
+    Burn { damage: usize, interval: f32 },
+    /// Weapons and behaviors are disabled, except for taking damage and dying
+    Stun,
+    /// Damage taken is multiplied by the factor
+    Vulnerable(f32),
+}
+
+impl StatusEffect {
+    /// Color of the sprites of entities affected by the effect
+    fn get_tint(&self) -> Color {
+        match self {
+            StatusEffect::Slow(_) => Color::rgb(0.5, 0.7, 1.0),
+            StatusEffect::Burn { .. } => Color::rgb(1.0, 0.55, 0.3),
+            StatusEffect::Stun => Color::rgb(1.0, 1.0, 0.4),
+            StatusEffect::Vulnerable(_) => Color::rgb(0.85, 0.45, 1.0),
+        }
+    }
+}
+
+/// A status effect and how many seconds it lasts
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct StatusEffectData {
+    pub effect: StatusEffect,
+    pub duration: f32,
+}
+
+/// Event for applying a status effect to a mob, mob segment or player
+#[derive(Event)]
+pub struct ApplyStatusEffectEvent {
+    pub target: Entity,
+    pub status_effect: StatusEffectData,
+}
+
+/// A status effect that is affecting an entity
+struct ActiveStatusEffect {
+    effect: StatusEffect,
+    /// Time left before the effect wears off
+    timer: Timer,
+    /// Time until the next damage of a burn
+    damage_timer: Option<Timer>,
+}
+
+/// Status effects affecting an entity
+#[derive(Component, Default)]
+pub struct StatusEffectsComponent {
+    effects: Vec<ActiveStatusEffect>,
+    /// Color of the sprite of the entity before it was tinted by an effect
+    base_color: Option<Color>,
+}
+
+impl StatusEffectsComponent {
+    /// Start a status effect. An active effect of the same kind is replaced, so reapplying an
+    /// effect refreshes it.
+    pub fn apply(&mut self, status_effect: &StatusEffectData) {
+        self.effects
+            .retain(|active| discriminant(&active.effect) != discriminant(&status_effect.effect));
+
+        let damage_timer = match status_effect.effect {
+            StatusEffect::Burn { interval, .. } => {
+                Some(Timer::from_seconds(interval, TimerMode::Repeating))
+            }
+            _ => None,
+        };
+
+        self.effects.push(ActiveStatusEffect {
+            effect: status_effect.effect.clone(),
+            timer: Timer::from_seconds(status_effect.duration, TimerMode::Once),
+            damage_timer,
+        });
+    }
+
+    /// Advance the effects by `delta`, removing the ones that wore off. Returns the damage dealt
+    /// by burns in that time.
+    pub fn tick(&mut self, delta: Duration) -> usize {
+        let mut damage = 0;
+
+        for active in self.effects.iter_mut() {
+            // burns stop dealing damage when they wear off, even in the middle of a tick
+            let active_time = delta.min(active.timer.remaining());
+            active.timer.tick(delta);
+
+            if let (
+                StatusEffect::Burn {
+                    damage: burn_damage,
+                    ..
+                },
+                Some(damage_timer),
+            ) = (&active.effect, &mut active.damage_timer)
+            {
+                damage_timer.tick(active_time);
+                damage += burn_damage * damage_timer.times_finished_this_tick() as usize;
+            }
+        }
+
+        self.effects.retain(|active| !active.timer.finished());
+
+        damage
+    }
+
+    pub fn is_stunned(&self) -> bool {
+        self.effects
+            .iter()
+            .any(|active| active.effect == StatusEffect::Stun)
+    }
+
+    /// Factor that the maximum speed of the entity is multiplied by
+    pub fn get_speed_multiplier(&self) -> f32 {
+        self.effects
+            .iter()
+            .filter_map(|active| match active.effect {
+                StatusEffect::Slow(multiplier) => Some(multiplier),
+                _ => None,
+            })
+            .product()
+    }
+
+    /// Damage taken by the entity from a hit of `damage`
+    pub fn scale_damage(&self, damage: usize) -> usize {
+        let multiplier: f32 = self
+            .effects
+            .iter()
+            .filter_map(|active| match active.effect {
+                StatusEffect::Vulnerable(multiplier) => Some(multiplier),
+                _ => None,
+            })
+            .product();
+
+        (damage as f32 * multiplier).round() as usize
+    }
+
+    /// Tint of the most recently applied effect, if any
+    fn get_tint(&self) -> Option<Color> {
+        self.effects.last().map(|active| active.effect.get_tint())
+    }
+}
+
+/// Returns true if the entity is stunned, for systems that skip stunned entities
+pub fn is_stunned(status_effects: Option<&StatusEffectsComponent>) -> bool {
+    status_effects.is_some_and(|status_effects| status_effects.is_stunned())
+}
+
+/// Apply status effects to the mobs, mob segments and players they were sent to
+fn apply_status_effects_system(
+    mut commands: Commands,
+    mut apply_status_effect_events: EventReader<ApplyStatusEffectEvent>,
+    mut status_effects_query: Query<&mut StatusEffectsComponent>,
+    health_query: Query<(), With<HealthComponent>>,
+) {
+    // entities without status effects yet get a new component once all events are read, in case
+    // they are the target of several events
+    let mut new_status_effects: HashMap<Entity, StatusEffectsComponent> = HashMap::new();
+
+    for event in apply_status_effect_events.read() {
+        if let Ok(mut status_effects) = status_effects_query.get_mut(event.target) {
+            status_effects.apply(&event.status_effect);
+        } else if health_query.contains(event.target) {
+            new_status_effects
+                .entry(event.target)
+                .or_default()
+                .apply(&event.status_effect);
+        }
+    }
+
+    for (entity, status_effects) in new_status_effects {
+        if let Some(mut entity_commands) = commands.get_entity(entity) {
+            entity_commands.insert(status_effects);
+        }
+    }
+}
+
+/// Wear off status effects over time, and deal the damage of burns
+fn tick_status_effects_system(
+    time: Res<Time>,
+    mut status_effects_query: Query<(Entity, &mut StatusEffectsComponent)>,
+    mut damage_dealt_event_writer: EventWriter<DamageDealtEvent>,
+) {
+    for (entity, mut status_effects) in status_effects_query.iter_mut() {
+        let damage = status_effects.tick(time.delta());
+
+        if damage > 0 {
+            damage_dealt_event_writer.send(DamageDealtEvent {
+                damage,
+                target: entity,
+            });
+        }
+    }
+}
+
+/// Keep slowed spawnables and players under their slowed maximum speed
+fn slow_status_effect_system(
+    mut status_effects_query: Query<(
+        &StatusEffectsComponent,
+        &mut Velocity,
+        Option<&SpawnableComponent>,
+        Option<&PlayerMovementComponent>,
+    )>,
+) {
+    for (status_effects, mut velocity, spawnable, player_movement) in
+        status_effects_query.iter_mut()
+    {
+        let speed_multiplier = status_effects.get_speed_multiplier();
+        if speed_multiplier >= 1.0 {
+            continue;
+        }
+
+        let Some(max_speed) = spawnable
+            .map(|spawnable| spawnable.speed)
+            .or(player_movement.map(|player_movement| player_movement.speed))
+        else {
+            continue;
+        };
+
+        let slowed_speed = max_speed * speed_multiplier.max(0.0);
+        velocity.linvel = velocity.linvel.clamp(-slowed_speed, slowed_speed);
+    }
+}
+
+/// Tint the sprites of entities while they have status effects, and restore their color after
+fn status_effect_tint_system(
+    mut status_effects_query: Query<(&mut StatusEffectsComponent, &mut Sprite)>,
+) {
+    for (mut status_effects, mut sprite) in status_effects_query.iter_mut() {
+        if let Some(tint) = status_effects.get_tint() {
+            if status_effects.base_color.is_none() {
+                status_effects.base_color = Some(sprite.color);
+            }
+            sprite.color = tint;
+        } else if let Some(base_color) = status_effects.base_color.take() {
+            sprite.color = base_color;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{StatusEffect, StatusEffectData, StatusEffectsComponent};
+
+    #[test]
+    fn test_status_effects_wear_off() {
+        let mut status_effects = StatusEffectsComponent::default();
+        status_effects.apply(&StatusEffectData {
+            effect: StatusEffect::Burn {
+                damage: 2,
+                interval: 0.5,
+            },
+            duration: 2.0,
+        });
+        status_effects.apply(&StatusEffectData {
+            effect: StatusEffect::Stun,
+            duration: 1.0,
+        });
+
+        assert!(status_effects.is_stunned());
+        assert_eq!(status_effects.tick(Duration::from_secs_f32(1.0)), 4);
+        assert!(!status_effects.is_stunned());
+
+        // reapplying an effect refreshes it instead of stacking it
+        status_effects.apply(&StatusEffectData {
+            effect: StatusEffect::Burn {
+                damage: 2,
+                interval: 0.5,
+            },
+            duration: 2.0,
+        });
+        assert_eq!(status_effects.tick(Duration::from_secs_f32(1.5)), 6);
+        assert_eq!(status_effects.tick(Duration::from_secs_f32(1.0)), 2);
+        assert_eq!(status_effects.tick(Duration::from_secs_f32(1.0)), 0);
+        assert!(status_effects.get_tint().is_none());
+    }
+
+    #[test]
+    fn test_status_effect_multipliers() {
+        let mut status_effects = StatusEffectsComponent::default();
+        assert_eq!(status_effects.scale_damage(10), 10);
+
+        status_effects.apply(&StatusEffectData {
+            effect: StatusEffect::Slow(0.5),
+            duration: 3.0,
+        });
+        status_effects.apply(&StatusEffectData {
+            effect: StatusEffect::Vulnerable(1.5),
+            duration: 3.0,
+        });
+
+        assert_eq!(status_effects.scale_damage(10), 15);
+        assert!((status_effects.get_speed_multiplier() - 0.5).abs() < f32::EPSILON);
+    }
+}
@@ -1,11 +1,11 @@
 //! Helper functions
-use rand::{thread_rng, Rng};
+use rand::Rng;
 
 /// Randomly picks index of vector using weights
 /// Takes in a vector of weights
-pub fn weighted_rng(probs: Vec<f32>) -> usize {
+pub fn weighted_rng(probs: Vec<f32>, rng: &mut impl Rng) -> usize {
     let prob_space = probs.iter().fold(0.0, |sum, prob| sum + prob);
-    let pos = thread_rng().gen::<f32>() * prob_space;
+    let pos = rng.gen::<f32>() * prob_space;
     let mut sum = 0.0;
     for (idx, prob) in probs.iter().enumerate() {
         sum += prob;
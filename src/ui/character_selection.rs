@@ -1,6 +1,11 @@
 //! Systems to spawn and style the character selection screen, where each player picks a character
-//! from one of a few options, and possibly enables/diables the tutorial.
-use crate::{options::PlayingOnArcadeResource, run::CurrentRunProgressResource};
+//! from one of a few options, possibly enables/diables the tutorial, picks the difficulty and
+//! toggles run modifiers.
+use crate::{
+    game::RunModifiersResource,
+    options::PlayingOnArcadeResource,
+    run::{CurrentRunProgressResource, DailyChallengeResource},
+};
 
 use super::BouncingPromptComponent;
 use bevy::{
@@ -14,7 +19,7 @@ use bevy::{
     hierarchy::{BuildChildren, Children},
     input::gamepad::GamepadButtonChangedEvent,
     render::{color::Color, view::Visibility},
-    text::{JustifyText, Text, TextStyle},
+    text::{JustifyText, Text, TextSection, TextStyle},
     time::{Timer, TimerMode},
     ui::{
         node_bundles::{ImageBundle, NodeBundle, TextBundle},
@@ -29,6 +34,7 @@ use thetawave_interface::{
     audio::{PlaySoundEffectEvent, SoundEffectType},
     character::CharacterType,
     character_selection::PlayerJoinEvent,
+    game::{difficulty::SelectedDifficultyResource, run_modifiers::SelectedRunModifiersResource},
     player::{PlayerData, PlayerInput, PlayersResource},
     states::CharacterSelectionCleanup,
 };
@@ -71,11 +77,24 @@ pub(super) struct StartGamePrompt;
 #[derive(Component)]
 pub(super) struct ToggleTutorialUI;
 
+#[derive(Component)]
+pub(super) struct DifficultyUI;
+
+/// Lists the run modifiers, marking the one that toggling applies to
+#[derive(Component)]
+pub(super) struct RunModifiersUI {
+    /// Index of the selected modifier in `RunModifiersResource`
+    selected: usize,
+}
+
 /// Setup the character selection UI
 pub(super) fn setup_character_selection_system(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     playing_on_arcade: Res<PlayingOnArcadeResource>,
+    selected_difficulty: Res<SelectedDifficultyResource>,
+    run_modifiers: Res<RunModifiersResource>,
+    selected_run_modifiers: Res<SelectedRunModifiersResource>,
 ) {
     let font = asset_server.load("fonts/wibletown-regular.otf");
 
@@ -544,7 +563,7 @@ pub(super) fn setup_character_selection_system(
                                     text: Text::from_section(
                                         "Tutorials On",
                                         TextStyle {
-                                            font,
+                                            font: font.clone(),
                                             font_size: 30.0,
                                             color: Color::WHITE,
                                         },
@@ -553,6 +572,49 @@ pub(super) fn setup_character_selection_system(
                                     ..default()
                                 })
                                 .insert(ToggleTutorialUI);
+
+                            parent
+                                .spawn(TextBundle {
+                                    style: Style {
+                                        justify_self: JustifySelf::Center,
+                                        align_self: AlignSelf::Center,
+                                        ..default()
+                                    },
+                                    text: Text::from_section(
+                                        format!("Difficulty: {}", selected_difficulty.level),
+                                        TextStyle {
+                                            font: font.clone(),
+                                            font_size: 30.0,
+                                            color: Color::WHITE,
+                                        },
+                                    )
+                                    .with_justify(JustifyText::Center),
+                                    ..default()
+                                })
+                                .insert(DifficultyUI);
+
+                            parent
+                                .spawn(TextBundle {
+                                    style: Style {
+                                        justify_self: JustifySelf::Center,
+                                        align_self: AlignSelf::Center,
+                                        margin: UiRect::top(Val::Percent(1.0)),
+                                        ..default()
+                                    },
+                                    text: Text::from_sections(get_run_modifier_sections(
+                                        &run_modifiers,
+                                        &selected_run_modifiers,
+                                        0,
+                                        &TextStyle {
+                                            font,
+                                            font_size: 24.0,
+                                            color: Color::WHITE,
+                                        },
+                                    ))
+                                    .with_justify(JustifyText::Center),
+                                    ..default()
+                                })
+                                .insert(RunModifiersUI { selected: 0 });
                         });
                 });
         });
@@ -690,6 +752,118 @@ pub(super) fn toggle_tutorial_system(
     }
 }
 
+/// Cycle through the difficulties for the run. Daily challenges are always played on the same
+/// difficulty, so it can't be changed then.
+pub(super) fn change_difficulty_system(
+    menu_input_query: Query<&ActionState<MenuAction>, With<MenuExplorer>>,
+    mut selected_difficulty: ResMut<SelectedDifficultyResource>,
+    daily_challenge: Res<DailyChallengeResource>,
+    mut sound_effect_pub: EventWriter<PlaySoundEffectEvent>,
+    mut difficulty_text_query: Query<&mut Text, With<DifficultyUI>>,
+) {
+    // read menu input action
+    let action_state = menu_input_query.single();
+
+    if daily_challenge.is_active() {
+        return;
+    }
+
+    if action_state.just_released(&MenuAction::ChangeDifficulty) {
+        selected_difficulty.level = selected_difficulty.level.next();
+
+        if let Ok(mut text) = difficulty_text_query.get_single_mut() {
+            text.sections[0].value = format!("Difficulty: {}", selected_difficulty.level);
+        }
+
+        // play sound effect
+        sound_effect_pub.send(PlaySoundEffectEvent {
+            sound_effect_type: SoundEffectType::MenuInputSuccess,
+        });
+    }
+}
+
+/// Move the selection through the run modifiers and toggle the selected one. Daily challenges
+/// pick their own modifiers, so they can't be changed then.
+pub(super) fn toggle_run_modifiers_system(
+    menu_input_query: Query<&ActionState<MenuAction>, With<MenuExplorer>>,
+    run_modifiers: Res<RunModifiersResource>,
+    mut selected_run_modifiers: ResMut<SelectedRunModifiersResource>,
+    daily_challenge: Res<DailyChallengeResource>,
+    mut sound_effect_pub: EventWriter<PlaySoundEffectEvent>,
+    mut run_modifiers_ui_query: Query<(&mut Text, &mut RunModifiersUI)>,
+) {
+    // read menu input action
+    let action_state = menu_input_query.single();
+
+    if run_modifiers.modifiers.is_empty() || daily_challenge.is_active() {
+        return;
+    }
+
+    let Ok((mut text, mut run_modifiers_ui)) = run_modifiers_ui_query.get_single_mut() else {
+        return;
+    };
+
+    if action_state.just_released(&MenuAction::SelectRunModifier) {
+        run_modifiers_ui.selected = (run_modifiers_ui.selected + 1) % run_modifiers.modifiers.len();
+
+        sound_effect_pub.send(PlaySoundEffectEvent {
+            sound_effect_type: SoundEffectType::ButtonSelect,
+        });
+    } else if action_state.just_released(&MenuAction::ToggleRunModifier) {
+        if let Some(modifier) = run_modifiers.modifiers.get(run_modifiers_ui.selected) {
+            selected_run_modifiers.toggle(&modifier.key);
+        }
+
+        sound_effect_pub.send(PlaySoundEffectEvent {
+            sound_effect_type: SoundEffectType::MenuInputSuccess,
+        });
+    } else {
+        return;
+    }
+
+    let style = text.sections[0].style.clone();
+    text.sections = get_run_modifier_sections(
+        &run_modifiers,
+        &selected_run_modifiers,
+        run_modifiers_ui.selected,
+        &style,
+    );
+}
+
+/// One line for each run modifier, white if it is on and gray if it is off. The selected modifier
+/// is marked with arrows.
+fn get_run_modifier_sections(
+    run_modifiers: &RunModifiersResource,
+    selected_run_modifiers: &SelectedRunModifiersResource,
+    selected: usize,
+    style: &TextStyle,
+) -> Vec<TextSection> {
+    run_modifiers
+        .modifiers
+        .iter()
+        .enumerate()
+        .map(|(i, modifier)| {
+            let value = if i == selected {
+                format!("> {} <\n", modifier.name)
+            } else {
+                format!("{}\n", modifier.name)
+            };
+
+            TextSection::new(
+                value,
+                TextStyle {
+                    color: if selected_run_modifiers.is_active(&modifier.key) {
+                        Color::WHITE
+                    } else {
+                        Color::GRAY
+                    },
+                    ..style.clone()
+                },
+            )
+        })
+        .collect()
+}
+
 // handle the character selection for each player
 pub(super) fn select_character_system(
     menu_input_query: Query<&ActionState<MenuAction>, With<MenuExplorer>>,
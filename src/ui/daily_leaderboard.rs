@@ -0,0 +1,205 @@
+//! Systems to draw the daily leaderboard screen, which shows the results of the daily challenges of
+//! each day and starts today's daily challenge.
+use bevy::{
+    asset::AssetServer,
+    ecs::{
+        component::Component,
+        event::EventWriter,
+        query::With,
+        schedule::NextState,
+        system::{Commands, Query, Res, ResMut},
+    },
+    hierarchy::BuildChildren,
+    render::color::Color,
+    text::{JustifyText, Text, TextStyle},
+    ui::{
+        node_bundles::{NodeBundle, TextBundle},
+        AlignItems, FlexDirection, JustifyContent, Style, UiRect, Val,
+    },
+    utils::default,
+};
+use chrono::{Days, NaiveDate};
+use leafwing_input_manager::prelude::ActionState;
+use thetawave_interface::{
+    audio::{PlaySoundEffectEvent, SoundEffectType},
+    game::daily_challenge::DailyResultsResource,
+    input::{MenuAction, MenuExplorer},
+    states::{AppStates, DailyLeaderboardCleanup},
+};
+
+use crate::{
+    game::RunModifiersResource,
+    run::{
+        format_date, get_daily_run_modifiers, get_daily_seed, get_today, StartDailyChallengeEvent,
+    },
+};
+
+const FONT_SIZE: f32 = 32.0;
+const TITLE_FONT_SIZE: f32 = 48.0;
+/// Number of results shown for a day
+const LEADERBOARD_LENGTH: usize = 10;
+
+/// The text listing the results of the day that is shown
+#[derive(Component)]
+pub(super) struct DailyLeaderboardUI {
+    date: NaiveDate,
+}
+
+/// Spawn the daily leaderboard, starting with today's results
+pub(super) fn setup_daily_leaderboard_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    daily_results: Res<DailyResultsResource>,
+    run_modifiers: Res<RunModifiersResource>,
+) {
+    let font = asset_server.load("fonts/wibletown-regular.otf");
+    let today = get_today();
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::BLACK.with_a(0.9).into(),
+            ..default()
+        })
+        .insert(DailyLeaderboardCleanup)
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Daily Challenge",
+                TextStyle {
+                    font: font.clone(),
+                    font_size: TITLE_FONT_SIZE,
+                    color: Color::WHITE,
+                },
+            ));
+
+            parent
+                .spawn(TextBundle {
+                    style: Style {
+                        margin: UiRect::vertical(Val::Percent(3.0)),
+                        ..default()
+                    },
+                    text: Text::from_section(
+                        get_leaderboard_text(&daily_results, &run_modifiers, today),
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: FONT_SIZE,
+                            color: Color::WHITE,
+                        },
+                    )
+                    .with_justify(JustifyText::Center),
+                    ..default()
+                })
+                .insert(DailyLeaderboardUI { date: today });
+
+            parent.spawn(
+                TextBundle::from_section(
+                    "Confirm: play today's challenge\nUp/Down: change day\nBack: main menu",
+                    TextStyle {
+                        font,
+                        font_size: FONT_SIZE,
+                        color: Color::GRAY,
+                    },
+                )
+                .with_justify(JustifyText::Center),
+            );
+        });
+}
+
+/// Browse the days of the leaderboard, start today's challenge or go back to the main menu
+pub(super) fn daily_leaderboard_input_system(
+    menu_input_query: Query<&ActionState<MenuAction>, With<MenuExplorer>>,
+    mut leaderboard_query: Query<(&mut Text, &mut DailyLeaderboardUI)>,
+    daily_results: Res<DailyResultsResource>,
+    run_modifiers: Res<RunModifiersResource>,
+    mut next_app_state: ResMut<NextState<AppStates>>,
+    mut start_daily_challenge_event_writer: EventWriter<StartDailyChallengeEvent>,
+    mut sound_effect_event_writer: EventWriter<PlaySoundEffectEvent>,
+) {
+    let action_state = menu_input_query.single();
+
+    if action_state.just_released(&MenuAction::Confirm) {
+        start_daily_challenge_event_writer.send(StartDailyChallengeEvent);
+        sound_effect_event_writer.send(PlaySoundEffectEvent {
+            sound_effect_type: SoundEffectType::MenuInputSuccess,
+        });
+        return;
+    }
+
+    if action_state.just_released(&MenuAction::Back) {
+        next_app_state.set(AppStates::MainMenu);
+        return;
+    }
+
+    let Ok((mut text, mut leaderboard_ui)) = leaderboard_query.get_single_mut() else {
+        return;
+    };
+
+    // days can't be browsed past today
+    let date = if action_state.just_released(&MenuAction::NavigateUp) {
+        leaderboard_ui
+            .date
+            .checked_add_days(Days::new(1))
+            .filter(|date| *date <= get_today())
+    } else if action_state.just_released(&MenuAction::NavigateDown) {
+        leaderboard_ui.date.checked_sub_days(Days::new(1))
+    } else {
+        None
+    };
+
+    if let Some(date) = date {
+        leaderboard_ui.date = date;
+        text.sections[0].value = get_leaderboard_text(&daily_results, &run_modifiers, date);
+        sound_effect_event_writer.send(PlaySoundEffectEvent {
+            sound_effect_type: SoundEffectType::ButtonSelect,
+        });
+    }
+}
+
+fn get_leaderboard_text(
+    daily_results: &DailyResultsResource,
+    run_modifiers: &RunModifiersResource,
+    date: NaiveDate,
+) -> String {
+    let run_modifier_names: Vec<&str> =
+        get_daily_run_modifiers(get_daily_seed(date), run_modifiers)
+            .iter()
+            .filter_map(|key| run_modifiers.get(key))
+            .map(|modifier| modifier.name.as_str())
+            .collect();
+    let date = format_date(date);
+    let leaderboard = daily_results.get_leaderboard(&date);
+
+    let results = if leaderboard.is_empty() {
+        "No results yet".to_string()
+    } else {
+        leaderboard
+            .iter()
+            .take(LEADERBOARD_LENGTH)
+            .enumerate()
+            .map(|(rank, result)| {
+                format!(
+                    "{}. {}  {}:{:02}  {}  {}",
+                    rank + 1,
+                    result.score,
+                    result.time_secs / 60,
+                    result.time_secs % 60,
+                    result.outcome,
+                    result.character
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+
+    format!(
+        "{date}\nModifiers: {}\n\n{results}",
+        run_modifier_names.join(", ")
+    )
+}
@@ -114,9 +114,13 @@ pub(super) fn border_gradient_on_gate_interaction_system(
 ) {
     for event in gate_events.read() {
         match event.defense_interaction {
-            DefenseInteraction::Heal(_) => bg_event_writer.send(BorderGradientEvent::Defense),
+            Some(DefenseInteraction::Heal(_)) => bg_event_writer.send(BorderGradientEvent::Defense),
 
-            DefenseInteraction::Damage(_) => bg_event_writer.send(BorderGradientEvent::Warning),
+            Some(DefenseInteraction::Damage(_)) => {
+                bg_event_writer.send(BorderGradientEvent::Warning)
+            }
+
+            None => continue,
         };
     }
 }
@@ -16,7 +16,7 @@ use bevy::{
     utils::default,
 };
 use std::time::Duration;
-use thetawave_interface::run::CyclePhaseEvent;
+use thetawave_interface::run::{CyclePhaseEvent, IntroTextEvent};
 
 use super::parent::GameCenterUiChildBuilderExt;
 
@@ -68,28 +68,35 @@ impl GameCenterUiChildBuilderExt for ChildBuilder<'_> {
 /// Updates the alpha color of the background color for the text ui
 pub(super) fn update_center_text_ui_system(
     mut cycle_phase_event_reader: EventReader<CyclePhaseEvent>,
+    mut intro_text_event_reader: EventReader<IntroTextEvent>,
     run_resource: Res<CurrentRunProgressResource>,
     mut center_text_query: Query<
         (&mut Text, &mut BackgroundColor, &mut FadeOutUiComponent),
         With<CenterTextUi>,
     >,
 ) {
-    // if phase has been cycled update the text
+    // if phase has been cycled use the intro text of the new phase
+    let mut intro_text = None;
     if cycle_phase_event_reader.read().next().is_some() {
         if let Some(level) = &run_resource.current_level {
             if let Some(phase) = &level.current_phase {
-                if let Ok((mut text, mut bg_color, mut fade_out)) =
-                    center_text_query.get_single_mut()
-                {
-                    if let Some(intro_text) = phase.intro_text.clone() {
-                        text.sections[0].value = intro_text;
-                        *bg_color = BACKGROUND_COLOR.with_a(BASE_BACKGROUND_ALPHA).into();
-                        fade_out.timer.reset();
-                    }
-                }
+                intro_text = phase.intro_text.clone();
             }
         }
     }
+
+    // text sent in the middle of a phase is shown over the phase's intro text
+    if let Some(event) = intro_text_event_reader.read().last() {
+        intro_text = Some(event.text.clone());
+    }
+
+    if let Some(intro_text) = intro_text {
+        if let Ok((mut text, mut bg_color, mut fade_out)) = center_text_query.get_single_mut() {
+            text.sections[0].value = intro_text;
+            *bg_color = BACKGROUND_COLOR.with_a(BASE_BACKGROUND_ALPHA).into();
+            fade_out.timer.reset();
+        }
+    }
 }
 
 /// Gradually fade out text entities with a `FadeOutUIComponent`. This should be run every frame.
@@ -1,7 +1,7 @@
 //! Systems to draw and update UI elements related to the player's level progression and objectives
 //! (e.x. health). The user should know that certain behaviors bring them closer to defeat and know
 //! how far away they are from losing.
-use crate::run::CurrentRunProgressResource;
+use crate::{assets::UiAssets, run::CurrentRunProgressResource};
 use bevy::{
     asset::Handle,
     ecs::{
@@ -15,10 +15,11 @@ use bevy::{
     text::{Font, Text, TextStyle},
     ui::{
         node_bundles::{NodeBundle, TextBundle},
-        AlignItems, FlexDirection, JustifyContent, Style, UiRect, Val,
+        AlignItems, FlexDirection, JustifyContent, PositionType, Style, UiRect, Val,
     },
     utils::default,
 };
+use thetawave_interface::objective::Objective;
 
 use super::parent::LevelUiChildBuilderExt;
 
@@ -28,10 +29,15 @@ const FONT_SIZE: f32 = 48.0;
 const LEVEL_DATA_PADDING: UiRect =
     UiRect::new(Val::Vw(1.0), Val::Vw(1.0), Val::Vh(2.0), Val::Vh(2.0));
 const DEFENSE_COLOR: Color = Color::BLUE;
-const DEFENSE_COLOR_EMPTY_ALPHA: f32 = 0.05;
-const DEFENSE_COLOR_FILLED_ALPHA: f32 = 0.75;
-const DEFENSE_WIDTH: Val = Val::Percent(80.0);
-const DEFENSE_HEIGHT: Val = Val::Percent(60.0);
+const ESCORT_COLOR: Color = Color::GREEN;
+const SURVIVE_COLOR: Color = Color::PURPLE;
+const DESTROY_COLOR: Color = Color::RED;
+const COLLECT_COLOR: Color = Color::GOLD;
+const OBJECTIVE_COLOR_EMPTY_ALPHA: f32 = 0.05;
+const OBJECTIVE_COLOR_FILLED_ALPHA: f32 = 0.75;
+const OBJECTIVE_WIDTH: Val = Val::Percent(80.0);
+const OBJECTIVE_HEIGHT: Val = Val::Percent(60.0);
+const OBJECTIVE_FONT_SIZE: f32 = 32.0;
 
 /// Used for querying UI for displaying name
 #[derive(Component)]
@@ -95,12 +101,24 @@ impl LevelUiChildBuilderExt for ChildBuilder<'_> {
     }
 }
 
+/// Color of the progress bar of an objective
+fn get_objective_color(objective: &Objective) -> Color {
+    match objective {
+        Objective::Defense(_) => DEFENSE_COLOR,
+        Objective::Escort(_) => ESCORT_COLOR,
+        Objective::Survive(_) => SURVIVE_COLOR,
+        Objective::Destroy(_) => DESTROY_COLOR,
+        Objective::Collect(_) => COLLECT_COLOR,
+    }
+}
+
 /// Updates the all of the level ui at the bottom of the window
 pub(super) fn update_level_ui_system(
     mut commands: Commands,
     level_data_ui_query: Query<Entity, With<LevelDataUi>>,
     mut level_name_ui_query: Query<&mut Text, With<LevelNameUi>>,
     run_resource: Res<CurrentRunProgressResource>,
+    ui_assets: Res<UiAssets>,
 ) {
     if let Some(current_level) = &run_resource.current_level {
         if let Ok(mut text) = level_name_ui_query.get_single_mut() {
@@ -111,40 +129,60 @@ pub(super) fn update_level_ui_system(
             commands.entity(entity).despawn_descendants();
 
             if let Some(objective) = &current_level.objective {
-                match objective {
-                    thetawave_interface::objective::Objective::Defense(defense_data) => {
-                        commands.entity(entity).with_children(|level_data_ui| {
-                            level_data_ui
+                let objective_color = get_objective_color(objective);
+
+                commands.entity(entity).with_children(|level_data_ui| {
+                    level_data_ui
+                        .spawn(NodeBundle {
+                            style: Style {
+                                width: OBJECTIVE_WIDTH,
+                                height: OBJECTIVE_HEIGHT,
+                                flex_direction: FlexDirection::Row,
+                                ..default()
+                            },
+                            background_color: objective_color
+                                .with_a(OBJECTIVE_COLOR_EMPTY_ALPHA)
+                                .into(),
+                            ..default()
+                        })
+                        .with_children(|objective_ui| {
+                            objective_ui.spawn(NodeBundle {
+                                style: Style {
+                                    width: Val::Percent(100.0 * objective.get_percentage()),
+                                    height: Val::Percent(100.0),
+                                    ..default()
+                                },
+                                background_color: objective_color
+                                    .with_a(OBJECTIVE_COLOR_FILLED_ALPHA)
+                                    .into(),
+                                ..default()
+                            });
+
+                            // progress text is drawn over the bar
+                            objective_ui
                                 .spawn(NodeBundle {
                                     style: Style {
-                                        width: DEFENSE_WIDTH,
-                                        height: DEFENSE_HEIGHT,
-                                        flex_direction: FlexDirection::Row,
+                                        position_type: PositionType::Absolute,
+                                        width: Val::Percent(100.0),
+                                        height: Val::Percent(100.0),
+                                        align_items: AlignItems::Center,
+                                        justify_content: JustifyContent::Center,
                                         ..default()
                                     },
-                                    background_color: DEFENSE_COLOR
-                                        .with_a(DEFENSE_COLOR_EMPTY_ALPHA)
-                                        .into(),
                                     ..default()
                                 })
-                                .with_children(|defense_ui| {
-                                    defense_ui.spawn(NodeBundle {
-                                        style: Style {
-                                            width: Val::Percent(
-                                                100.0 * defense_data.get_percentage(),
-                                            ),
-                                            height: Val::Percent(100.0),
-                                            ..default()
+                                .with_children(|objective_text_ui| {
+                                    objective_text_ui.spawn(TextBundle::from_section(
+                                        objective.get_progress_text(),
+                                        TextStyle {
+                                            font: ui_assets.wibletown_font.clone(),
+                                            font_size: OBJECTIVE_FONT_SIZE,
+                                            color: TEXT_COLOR,
                                         },
-                                        background_color: DEFENSE_COLOR
-                                            .with_a(DEFENSE_COLOR_FILLED_ALPHA)
-                                            .into(),
-                                        ..default()
-                                    });
+                                    ));
                                 });
                         });
-                    }
-                }
+                });
             }
         }
     }
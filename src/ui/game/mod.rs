@@ -12,6 +12,8 @@ mod level;
 mod parent;
 mod phase;
 mod player;
+mod run_modifiers;
+mod shop;
 
 pub(super) struct GameUiPlugin;
 
@@ -38,6 +40,7 @@ impl Plugin for GameUiPlugin {
                 border_gradient::border_gradient_start_system,
                 border_gradient::border_gradient_update_system,
                 border_gradient::border_gradient_on_gate_interaction_system,
+                shop::update_shop_ui_system,
             )
                 .run_if(in_state(states::AppStates::Game))
                 .run_if(in_state(states::GameStates::Playing)),
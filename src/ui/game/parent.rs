@@ -17,7 +17,7 @@ use thetawave_interface::{
     states::GameCleanup,
 };
 
-use crate::{assets::UiAssets, player::CharactersResource};
+use crate::{assets::UiAssets, game::RunModifierParams, player::CharactersResource};
 
 use super::border_gradient::BorderGradientType;
 
@@ -44,6 +44,10 @@ pub(super) trait LevelUiChildBuilderExt {
     fn spawn_level_ui(&mut self, font: Handle<Font>);
 }
 
+pub(super) trait RunModifiersUiChildBuilderExt {
+    fn spawn_run_modifiers_ui(&mut self, font: Handle<Font>, run_modifier_names: &[&str]);
+}
+
 pub(super) trait GameCenterUiChildBuilderExt {
     fn spawn_game_center_ui(&mut self, font: Handle<Font>);
 }
@@ -86,6 +90,7 @@ pub(super) fn setup_game_ui_system(
     ui_assets: Res<UiAssets>,
     players_resource: Res<PlayersResource>,
     characters_resource: Res<CharactersResource>,
+    run_modifiers: RunModifierParams,
 ) {
     let font: Handle<Font> = ui_assets.wibletown_font.clone();
 
@@ -239,16 +244,24 @@ pub(super) fn setup_game_ui_system(
                 ..default()
             })
             .with_children(|bottom_row| {
-                // Top left corner
-                bottom_row.spawn(NodeBundle {
-                    style: Style {
-                        width: BOTTOM_CORNER_WIDTH,
-                        height: Val::Percent(100.0),
+                // Bottom left corner
+                bottom_row
+                    .spawn(NodeBundle {
+                        style: Style {
+                            width: BOTTOM_CORNER_WIDTH,
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        background_color: OUTSIDE_BORDER_BG_COLOR,
                         ..default()
-                    },
-                    background_color: OUTSIDE_BORDER_BG_COLOR,
-                    ..default()
-                });
+                    })
+                    .with_children(|bottom_left| {
+                        // spawn the list of active run modifiers
+                        bottom_left.spawn_run_modifiers_ui(
+                            font.clone(),
+                            &run_modifiers.get_active_names(),
+                        );
+                    });
 
                 // Bottom middle
                 bottom_row
@@ -11,7 +11,7 @@ use bevy::{
     text::{Font, JustifyText, Text, TextStyle},
     ui::{
         node_bundles::{NodeBundle, TextBundle},
-        AlignItems, FlexDirection, FlexWrap, JustifyContent, Style, UiRect, Val,
+        AlignItems, FlexDirection, FlexWrap, JustifyContent, PositionType, Style, UiRect, Val,
     },
     utils::default,
 };
@@ -19,7 +19,10 @@ use thetawave_interface::health::HealthComponent;
 
 use crate::run::level_phase::LevelPhaseType;
 use crate::run::tutorial::TutorialLesson;
-use crate::{run::CurrentRunProgressResource, spawnable::BossComponent};
+use crate::{
+    run::CurrentRunProgressResource,
+    spawnable::{BossComponent, BossStagesComponent},
+};
 
 use super::parent::PhaseUiChildBuilderExt;
 
@@ -35,6 +38,8 @@ const BOSS_HEALTH_HEIGHT: Val = Val::Percent(60.0);
 const BOSS_HEALTH_COLOR: Color = Color::RED;
 const BOSS_HEALTH_EMPTY_ALPHA: f32 = 0.05;
 const BOSS_HEALTH_FILLED_ALPHA: f32 = 0.75;
+const BOSS_STAGE_MARKER_WIDTH: Val = Val::Px(3.0);
+const BOSS_STAGE_MARKER_COLOR: Color = Color::WHITE;
 const TUTORIAL_TEXT_SECTION_HEIGHT: Val = Val::Px(30.0);
 
 /// Used for querying UI for displaying name
@@ -107,7 +112,7 @@ pub(super) fn update_phase_ui_system(
     mut phase_name_ui_query: Query<&mut Text, With<PhaseNameUi>>,
     phase_data_ui_query: Query<Entity, With<PhaseDataUi>>,
     run_resource: Res<CurrentRunProgressResource>,
-    boss_mobs_query: Query<&HealthComponent, With<BossComponent>>,
+    boss_mobs_query: Query<(&HealthComponent, Option<&BossStagesComponent>), With<BossComponent>>,
 ) {
     if let Some(current_level) = &run_resource.current_level {
         if let Some(current_phase) = &current_level.current_phase {
@@ -137,7 +142,8 @@ pub(super) fn update_phase_ui_system(
                             });
                         });
                     }
-                    LevelPhaseType::Break { phase_timer, .. } => {
+                    LevelPhaseType::Break { phase_timer, .. }
+                    | LevelPhaseType::Shop { phase_timer } => {
                         let font = asset_server.load("fonts/wibletown-regular.otf");
 
                         commands.entity(entity).with_children(|phase_data_ui| {
@@ -155,8 +161,44 @@ pub(super) fn update_phase_ui_system(
                             });
                         });
                     }
+                    LevelPhaseType::Endless(endless_phase) => {
+                        let font = asset_server.load("fonts/wibletown-regular.otf");
+
+                        commands.entity(entity).with_children(|phase_data_ui| {
+                            phase_data_ui.spawn(TextBundle {
+                                style: Style::default(),
+                                text: Text::from_section(
+                                    format!("{:.0}", endless_phase.get_wave_remaining_secs()),
+                                    TextStyle {
+                                        font,
+                                        font_size: FONT_SIZE,
+                                        color: NORMAL_TEXT_COLOR,
+                                    },
+                                ),
+                                ..default()
+                            });
+                        });
+                    }
+                    LevelPhaseType::Timeline(timeline_phase) => {
+                        let font = asset_server.load("fonts/wibletown-regular.otf");
+
+                        commands.entity(entity).with_children(|phase_data_ui| {
+                            phase_data_ui.spawn(TextBundle {
+                                style: Style::default(),
+                                text: Text::from_section(
+                                    format!("{:.0}", timeline_phase.get_remaining_secs()),
+                                    TextStyle {
+                                        font,
+                                        font_size: FONT_SIZE,
+                                        color: NORMAL_TEXT_COLOR,
+                                    },
+                                ),
+                                ..default()
+                            });
+                        });
+                    }
                     LevelPhaseType::Boss { .. } => {
-                        if let Ok(health) = boss_mobs_query.get_single() {
+                        if let Ok((health, boss_stages)) = boss_mobs_query.get_single() {
                             commands.entity(entity).with_children(|phase_data_ui| {
                                 phase_data_ui
                                     .spawn(NodeBundle {
@@ -185,6 +227,25 @@ pub(super) fn update_phase_ui_system(
                                                 .into(),
                                             ..default()
                                         });
+
+                                        // mark the health thresholds of the boss's stages to
+                                        // split the bar into segments
+                                        for threshold in boss_stages
+                                            .iter()
+                                            .flat_map(|boss_stages| boss_stages.get_thresholds())
+                                        {
+                                            boss_health_ui.spawn(NodeBundle {
+                                                style: Style {
+                                                    position_type: PositionType::Absolute,
+                                                    left: Val::Percent(100.0 * threshold),
+                                                    width: BOSS_STAGE_MARKER_WIDTH,
+                                                    height: Val::Percent(100.0),
+                                                    ..default()
+                                                },
+                                                background_color: BOSS_STAGE_MARKER_COLOR.into(),
+                                                ..default()
+                                            });
+                                        }
                                     });
                             });
                         }
@@ -0,0 +1,39 @@
+//! Lists the run modifiers the run is played with, so that the players don't forget about them.
+use bevy::{
+    asset::Handle,
+    hierarchy::ChildBuilder,
+    render::color::Color,
+    text::{Font, JustifyText, Text, TextStyle},
+    ui::{node_bundles::TextBundle, Style, UiRect, Val},
+    utils::default,
+};
+
+use super::parent::RunModifiersUiChildBuilderExt;
+
+const TEXT_COLOR: Color = Color::WHITE;
+const FONT_SIZE: f32 = 20.0;
+
+impl RunModifiersUiChildBuilderExt for ChildBuilder<'_> {
+    fn spawn_run_modifiers_ui(&mut self, font: Handle<Font>, run_modifier_names: &[&str]) {
+        if run_modifier_names.is_empty() {
+            return;
+        }
+
+        self.spawn(TextBundle {
+            style: Style {
+                margin: UiRect::all(Val::Auto),
+                ..default()
+            },
+            text: Text::from_section(
+                format!("Modifiers:\n{}", run_modifier_names.join("\n")),
+                TextStyle {
+                    font,
+                    font_size: FONT_SIZE,
+                    color: TEXT_COLOR,
+                },
+            )
+            .with_justify(JustifyText::Center),
+            ..default()
+        });
+    }
+}
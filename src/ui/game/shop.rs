@@ -0,0 +1,167 @@
+//! Shows the offers of the shop during shop phases, along with the offer each player has selected
+//! and how much money they have to spend.
+use bevy::{
+    asset::{AssetServer, Handle},
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::With,
+        system::{Commands, Query, Res},
+    },
+    hierarchy::{BuildChildren, DespawnRecursiveExt},
+    render::color::Color,
+    text::{Font, JustifyText, Text, TextSection, TextStyle},
+    ui::{
+        node_bundles::{NodeBundle, TextBundle},
+        AlignItems, JustifyContent, PositionType, Style, UiRect, Val,
+    },
+    utils::default,
+};
+use thetawave_interface::{
+    player::{PlayerComponent, PlayerIDComponent, PlayerInventoryComponent},
+    states::GameCleanup,
+};
+
+use crate::run::ShopResource;
+
+const TITLE_FONT_SIZE: f32 = 36.0;
+const FONT_SIZE: f32 = 24.0;
+const TEXT_COLOR: Color = Color::WHITE;
+const SOLD_TEXT_COLOR: Color = Color::GRAY;
+const CONTROLS_TEXT: &str = "\nKeyboard: Q select, F buy\nGamepad: Y select, X buy";
+
+/// Root of the shop overlay, spawned while the shop is open
+#[derive(Component)]
+pub(super) struct ShopUI;
+
+/// Text listing the offers of the shop
+#[derive(Component)]
+pub(super) struct ShopTextUI;
+
+/// Spawn the shop overlay when the shop opens, keep it up to date with the selections and money of
+/// the players, and despawn it when the shop closes
+pub(super) fn update_shop_ui_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    shop: Res<ShopResource>,
+    player_query: Query<(&PlayerIDComponent, &PlayerInventoryComponent), With<PlayerComponent>>,
+    shop_ui_query: Query<Entity, With<ShopUI>>,
+    mut shop_text_query: Query<&mut Text, With<ShopTextUI>>,
+) {
+    if !shop.is_open() {
+        for entity in shop_ui_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+
+    let font = asset_server.load("fonts/wibletown-regular.otf");
+
+    let mut player_money: Vec<(usize, usize)> = player_query
+        .iter()
+        .map(|(player_id, player_inventory)| (usize::from(*player_id), player_inventory.money))
+        .collect();
+    player_money.sort_unstable();
+
+    let sections = get_shop_sections(&shop, &player_money, font);
+
+    if let Ok(mut text) = shop_text_query.get_single_mut() {
+        text.sections = sections;
+        return;
+    }
+
+    if !shop_ui_query.is_empty() {
+        return;
+    }
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(ShopUI)
+        .insert(GameCleanup)
+        .with_children(|parent| {
+            parent
+                .spawn(TextBundle {
+                    style: Style {
+                        padding: UiRect::all(Val::Vh(2.0)),
+                        ..default()
+                    },
+                    text: Text::from_sections(sections).with_justify(JustifyText::Center),
+                    background_color: Color::BLACK.with_a(0.7).into(),
+                    ..default()
+                })
+                .insert(ShopTextUI);
+        });
+}
+
+/// Text of the shop overlay. Offers are marked with the players that have them selected, and
+/// offers that were sold are grayed out.
+fn get_shop_sections(
+    shop: &ShopResource,
+    player_money: &[(usize, usize)],
+    font: Handle<Font>,
+) -> Vec<TextSection> {
+    let style = |font_size, color| TextStyle {
+        font: font.clone(),
+        font_size,
+        color,
+    };
+
+    let money_text = player_money
+        .iter()
+        .map(|(player_idx, money)| format!("P{}: {}", player_idx + 1, money))
+        .collect::<Vec<String>>()
+        .join("   ");
+
+    let mut sections = vec![
+        TextSection::new("Shop\n", style(TITLE_FONT_SIZE, TEXT_COLOR)),
+        TextSection::new(
+            format!("Money  {money_text}\n\n"),
+            style(FONT_SIZE, TEXT_COLOR),
+        ),
+    ];
+
+    for (offer_idx, offer) in shop.offers.iter().enumerate() {
+        let selected_by = player_money
+            .iter()
+            .filter(|(player_idx, _)| shop.selections.get(*player_idx) == Some(&offer_idx))
+            .map(|(player_idx, _)| format!("P{}", player_idx + 1))
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        let (price_text, color) = if offer.sold {
+            ("Sold".to_string(), SOLD_TEXT_COLOR)
+        } else {
+            (offer.price.to_string(), TEXT_COLOR)
+        };
+
+        let line = if selected_by.is_empty() {
+            format!("{}  {}\n", offer.item.get_name(), price_text)
+        } else {
+            format!(
+                "{} > {}  {} <\n",
+                selected_by,
+                offer.item.get_name(),
+                price_text
+            )
+        };
+
+        sections.push(TextSection::new(line, style(FONT_SIZE, color)));
+    }
+
+    sections.push(TextSection::new(
+        CONTROLS_TEXT,
+        style(FONT_SIZE, SOLD_TEXT_COLOR),
+    ));
+
+    sections
+}
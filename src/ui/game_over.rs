@@ -18,15 +18,24 @@ use bevy::{
 use std::time::Duration;
 use thetawave_interface::{
     audio::ChangeBackgroundMusicEvent,
-    game::historical_metrics::{
-        MobKillsByPlayerForCurrentGame, UserStatsByPlayerForCurrentGameCache, DEFAULT_USER_ID,
+    game::{
+        difficulty::SelectedDifficultyResource,
+        historical_metrics::{
+            MobKillsByPlayerForCurrentGame, UserStatsByPlayerForCurrentGameCache, DEFAULT_USER_ID,
+        },
     },
     states::GameOverCleanup,
 };
 
-use crate::{options::PlayingOnArcadeResource, ui::BouncingPromptComponent};
+use crate::{
+    game::RunModifierParams,
+    options::PlayingOnArcadeResource,
+    run::{ContinuesResource, CurrentRunProgressResource},
+    ui::BouncingPromptComponent,
+};
 
 /// Spawn the styled UI elements for the game over screen. It should tell the player how they did.
+#[allow(clippy::too_many_arguments)]
 pub(super) fn setup_game_over_system(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -34,6 +43,10 @@ pub(super) fn setup_game_over_system(
     current_game_shot_counts: Res<UserStatsByPlayerForCurrentGameCache>,
     current_game_enemy_mob_kill_counts: Res<MobKillsByPlayerForCurrentGame>,
     playing_on_arcade: Res<PlayingOnArcadeResource>,
+    run_resource: Res<CurrentRunProgressResource>,
+    selected_difficulty: Res<SelectedDifficultyResource>,
+    run_modifiers: RunModifierParams,
+    continues: Res<ContinuesResource>,
 ) {
     let maybe_current_game_stats = (**current_game_shot_counts).get(&DEFAULT_USER_ID);
     let (accuracy_rate, total_shots_fired): (f32, usize) = match maybe_current_game_stats {
@@ -46,6 +59,51 @@ pub(super) fn setup_game_over_system(
         }
     };
 
+    // report how long the players lasted in endless mode
+    let endless_summary = match run_resource.get_endless_progress() {
+        Some((wave, survival_time)) => {
+            let survival_secs = survival_time.as_secs();
+            format!(
+                "Survived: {}:{:02}\nWave reached: {}\n\n",
+                survival_secs / 60,
+                survival_secs % 60,
+                wave
+            )
+        }
+        None => String::new(),
+    };
+
+    // tell the players if they can restart the level they failed
+    let continues_used = run_resource.continues_used;
+    let continue_summary = if continues.can_continue(continues_used) {
+        let remaining = match continues.get_remaining(continues_used) {
+            Some(remaining) => format!(" ({remaining} left)"),
+            None => String::new(),
+        };
+        format!(
+            "\n\nPress {} to continue{}",
+            if **playing_on_arcade {
+                "start"
+            } else {
+                "enter"
+            },
+            remaining
+        )
+    } else {
+        String::new()
+    };
+    let run_modifier_names = run_modifiers.get_active_names();
+    let run_modifiers_summary = if run_modifier_names.is_empty() {
+        String::new()
+    } else {
+        format!("Modifiers: {}\n", run_modifier_names.join(", "))
+    };
+    let continues_used_summary = if continues_used > 0 {
+        format!("Continues used: {continues_used}\n")
+    } else {
+        String::new()
+    };
+
     // fade music out
     change_bg_music_event_writer.send(ChangeBackgroundMusicEvent {
         fade_out: Some(Duration::from_secs(5)),
@@ -110,12 +168,17 @@ pub(super) fn setup_game_over_system(
 
                                 text: Text::from_section(
                                     format!(
-                                        "Projectiles fired: {}\nAccuracy: {:.2}%\n\nEnemies destroyed:\n{}",
+                                        "Difficulty: {}\n{}{}{}Projectiles fired: {}\nAccuracy: {:.2}%\n\nEnemies destroyed:\n{}{}",
+                                        selected_difficulty.level,
+                                        run_modifiers_summary,
+                                        continues_used_summary,
+                                        endless_summary,
                                         total_shots_fired,
                                         accuracy_rate,
                                         super::pprint_mob_kills_from_data(
                                             &current_game_enemy_mob_kill_counts
                                         ),
+                                        continue_summary,
                                     ),
                                     TextStyle {
                                         font,
@@ -1,4 +1,4 @@
-//! Provides the layout (trait on `bevy::hierarchy::ChildBUilder`) and behavior (systems) to put 4
+//! Provides the layout (trait on `bevy::hierarchy::ChildBUilder`) and behavior (systems) to put 6
 //! vertically layed out on the main menu, and change the state from
 //! `thetawave_interface::states::AppStates::MainMenu` to
 //! `thetawave_interface::states::AppStates::Instructions`
@@ -11,7 +11,7 @@ use bevy::{
         event::{Event, EventReader, EventWriter},
         query::{Changed, With},
         schedule::NextState,
-        system::{Local, Query, ResMut},
+        system::{Local, Query, Res, ResMut},
     },
     hierarchy::{BuildChildren, ChildBuilder, Children},
     log::{error, info},
@@ -28,10 +28,13 @@ use bevy::{
 use leafwing_input_manager::prelude::ActionState;
 use thetawave_interface::{
     audio::{PlaySoundEffectEvent, SoundEffectType},
+    game::saved_runs::{SavedRunsResource, DEFAULT_SAVE_SLOT},
     input::{MenuAction, MenuExplorer},
     states::AppStates,
 };
 
+use crate::save::ResumeRunEvent;
+
 const BUTTON_WIDTH: Val = Val::Percent(25.0);
 const BUTTON_MAX_WIDTH: Val = Val::Px(500.0);
 const BUTTON_MIN_WIDTH: Val = Val::Px(200.0);
@@ -49,6 +52,8 @@ const BUTTON_TEXTURE_PADDING_HOVERED: UiRect =
 #[derive(Component, Event, Clone, PartialEq, Eq, Copy, Debug)]
 pub(super) enum MainMenuButtonActionComponent {
     EnterInstructions,
+    ResumeRun,
+    EnterDailyChallenge,
     EnterOptions,
     EnterCompendium,
     QuitGame,
@@ -60,15 +65,27 @@ impl MainMenuButtonActionComponent {
     fn in_game_text(&self) -> &'static str {
         match self {
             Self::EnterInstructions => "Start Game",
+            Self::ResumeRun => "Resume Run",
+            Self::EnterDailyChallenge => "Daily Challenge",
             Self::EnterOptions => "Options",
             Self::EnterCompendium => "Compendium",
             Self::QuitGame => "Exit Game",
         }
     }
+
+    /// Whether the button can be clicked. A run can only be resumed if one was saved.
+    fn is_enabled(&self, saved_runs: &SavedRunsResource) -> bool {
+        match self {
+            Self::ResumeRun => saved_runs.contains_key(&DEFAULT_SAVE_SLOT),
+            _ => true,
+        }
+    }
 }
 /// This is the order (vertical, going down) of the buttons shown on the main menu UI.
-const MAIN_MENU_BUTTON_ORDER: [MainMenuButtonActionComponent; 4] = [
+const MAIN_MENU_BUTTON_ORDER: [MainMenuButtonActionComponent; 6] = [
     MainMenuButtonActionComponent::EnterInstructions,
+    MainMenuButtonActionComponent::ResumeRun,
+    MainMenuButtonActionComponent::EnterDailyChallenge,
     MainMenuButtonActionComponent::EnterOptions,
     MainMenuButtonActionComponent::EnterCompendium,
     MainMenuButtonActionComponent::QuitGame,
@@ -78,26 +95,38 @@ pub(super) type MainMenuButtonActionEvent = MainMenuButtonActionComponent;
 
 /// Extension trait for spawning customized UI elements for Thetawave
 pub(super) trait UiChildBuilderExt {
-    /// Spawn a Thetawave-stylized menu button
+    /// Spawn a Thetawave-stylized menu button, with faded text if it can't be clicked
     fn spawn_main_menu_button(
         &mut self,
         ui_assets: &UiAssets,
         text: String,
         font: Handle<Font>,
         action: MainMenuButtonActionComponent,
+        enabled: bool,
     );
     // Spawn 1 menu button for each element of `MainMenuButtonActionComponent`
-    fn spawn_main_menu_buttons(&mut self, ui_assets: &UiAssets, font: Handle<Font>) -> &mut Self;
+    fn spawn_main_menu_buttons(
+        &mut self,
+        ui_assets: &UiAssets,
+        font: Handle<Font>,
+        saved_runs: &SavedRunsResource,
+    ) -> &mut Self;
 }
 
 impl UiChildBuilderExt for ChildBuilder<'_> {
-    fn spawn_main_menu_buttons(&mut self, ui_assets: &UiAssets, font: Handle<Font>) -> &mut Self {
+    fn spawn_main_menu_buttons(
+        &mut self,
+        ui_assets: &UiAssets,
+        font: Handle<Font>,
+        saved_runs: &SavedRunsResource,
+    ) -> &mut Self {
         for action in MAIN_MENU_BUTTON_ORDER.iter() {
             self.spawn_main_menu_button(
                 ui_assets,
                 action.in_game_text().into(),
                 font.clone(),
                 action.clone(),
+                action.is_enabled(saved_runs),
             )
         }
 
@@ -109,6 +138,7 @@ impl UiChildBuilderExt for ChildBuilder<'_> {
         text: String,
         font: Handle<Font>,
         action: MainMenuButtonActionComponent,
+        enabled: bool,
     ) {
         // Spawn button bundle entity, with a child entity containing the texture
         self.spawn(ButtonBundle {
@@ -145,7 +175,11 @@ impl UiChildBuilderExt for ChildBuilder<'_> {
                         TextStyle {
                             font: font.clone(),
                             font_size: 30.0,
-                            color: Color::BLACK,
+                            color: if enabled {
+                                Color::BLACK
+                            } else {
+                                Color::rgba(0.0, 0.0, 0.0, 0.4)
+                            },
                         },
                     ));
                 });
@@ -179,6 +213,7 @@ fn bool_to_plus_minus_1(val: bool) -> i8 {
 /// Selects a button on the main menu to click. Mainly sends a `MainMenuButtonActionEvent`.
 /// Selection happens from the mouse, keyboard and gamepad. We deal with all kinds of inputs in 1
 /// system to control the interactionsm between using, for example, arrows and hovers.
+#[allow(clippy::too_many_arguments)]
 pub(super) fn main_menu_button_selection_and_click_system(
     main_menu_buttons: Query<(&MainMenuButtonActionComponent, &Children), With<Button>>,
     main_menu_button_mouse_movements: Query<
@@ -196,6 +231,7 @@ pub(super) fn main_menu_button_selection_and_click_system(
     // The main side effects of this system/UI component/widget
     mut sound_effect: EventWriter<PlaySoundEffectEvent>,
     mut button_event_writer: EventWriter<MainMenuButtonActionEvent>,
+    saved_runs: Res<SavedRunsResource>,
 ) {
     // We do a fair number of linear traversals, but there should only be < 10 buttons, children,
     // etc. So all of those linear time operations should actually be fast.
@@ -319,7 +355,11 @@ pub(super) fn main_menu_button_selection_and_click_system(
         }
         _ => {}
     };
-    if let Some(action) = first_button_mouse_clicked.or(player_confirmed_button_selection) {
+    // disabled buttons can be selected, but not clicked
+    if let Some(action) = first_button_mouse_clicked
+        .or(player_confirmed_button_selection)
+        .filter(|action| action.is_enabled(&saved_runs))
+    {
         button_event_writer.send(action.clone());
         sound_effect.send(PlaySoundEffectEvent {
             sound_effect_type: SoundEffectType::ButtonConfirm,
@@ -364,6 +404,7 @@ pub(super) fn main_menu_button_selection_and_click_system(
 pub(super) fn main_menu_button_on_click_system(
     mut button_event_reader: EventReader<MainMenuButtonActionEvent>,
     mut next_app_state: ResMut<NextState<AppStates>>,
+    mut resume_run_event_writer: EventWriter<ResumeRunEvent>,
     mut exit: EventWriter<AppExit>,
 ) {
     for event in button_event_reader.read() {
@@ -371,6 +412,14 @@ pub(super) fn main_menu_button_on_click_system(
             MainMenuButtonActionComponent::EnterInstructions => {
                 next_app_state.set(AppStates::Instructions);
             }
+            MainMenuButtonActionComponent::ResumeRun => {
+                resume_run_event_writer.send(ResumeRunEvent {
+                    slot: DEFAULT_SAVE_SLOT,
+                });
+            }
+            MainMenuButtonActionComponent::EnterDailyChallenge => {
+                next_app_state.set(AppStates::DailyLeaderboard);
+            }
             MainMenuButtonActionComponent::EnterOptions => info!("Enter options menu."),
             MainMenuButtonActionComponent::EnterCompendium => info!("Enter compendium."),
             MainMenuButtonActionComponent::QuitGame => {
@@ -23,6 +23,7 @@ use bevy::{
 };
 use std::time::Duration;
 use thetawave_interface::audio::{BGMusicType, ChangeBackgroundMusicEvent};
+use thetawave_interface::game::saved_runs::SavedRunsResource;
 use thetawave_interface::states::{AppStates, MainMenuCleanup};
 mod button;
 use self::button::main_menu_button_on_click_system;
@@ -56,6 +57,7 @@ fn setup_main_menu_system(
     asset_server: Res<AssetServer>,
     mut change_bg_music_event_writer: EventWriter<ChangeBackgroundMusicEvent>,
     ui_assets: Res<UiAssets>,
+    saved_runs: Res<SavedRunsResource>,
 ) {
     let font: Handle<Font> = asset_server.load("fonts/Lunchds.ttf");
 
@@ -136,7 +138,7 @@ fn setup_main_menu_system(
                             ..default()
                         })
                         .with_children(|parent| {
-                            parent.spawn_main_menu_buttons(&ui_assets, font.clone());
+                            parent.spawn_main_menu_buttons(&ui_assets, font.clone(), &saved_runs);
                         });
                 });
         });
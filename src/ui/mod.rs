@@ -10,6 +10,7 @@ use thetawave_interface::game::historical_metrics::{MobsKilledByPlayerCacheT, DE
 use thetawave_interface::states;
 
 mod character_selection;
+mod daily_leaderboard;
 mod game;
 mod game_over;
 mod instructions;
@@ -19,9 +20,10 @@ mod victory;
 
 use self::{
     character_selection::{
-        player_join_system, select_character_system, setup_character_selection_system,
-        toggle_tutorial_system,
+        change_difficulty_system, player_join_system, select_character_system,
+        setup_character_selection_system, toggle_run_modifiers_system, toggle_tutorial_system,
     },
+    daily_leaderboard::{daily_leaderboard_input_system, setup_daily_leaderboard_system},
     game::GameUiPlugin,
     game_over::setup_game_over_system,
     instructions::setup_instructions_system,
@@ -41,6 +43,16 @@ impl Plugin for UiPlugin {
         app.add_plugins(MainMenuUIPlugin);
         app.add_systems(Update, bouncing_prompt_system);
 
+        app.add_systems(
+            OnEnter(states::AppStates::DailyLeaderboard),
+            setup_daily_leaderboard_system,
+        );
+
+        app.add_systems(
+            Update,
+            daily_leaderboard_input_system.run_if(in_state(states::AppStates::DailyLeaderboard)),
+        );
+
         app.add_systems(
             OnEnter(states::AppStates::Instructions),
             setup_instructions_system,
@@ -57,6 +69,8 @@ impl Plugin for UiPlugin {
                 player_join_system,
                 select_character_system,
                 toggle_tutorial_system,
+                change_difficulty_system,
+                toggle_run_modifiers_system,
             )
                 .run_if(in_state(states::AppStates::CharacterSelection)),
         );
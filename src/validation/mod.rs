@@ -0,0 +1,130 @@
+//! Exposes functions for checking the files in 'assets/data' for mistakes without starting the
+//! game. Many references between data files (formation pool keys, mob spawner keys, drop lists,
+//! etc) are otherwise only checked when they are used, often by panicking in the middle of a run.
+use ron::de::from_str;
+use serde::de::DeserializeOwned;
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    fs::{read_dir, read_to_string},
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+use crate::{game, run, spawnable};
+
+/// A mistake found in a data file
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("{file}: {key}: {message}")]
+pub(super) struct DataValidationError {
+    /// Name of the file in the data directory
+    pub file: String,
+    /// Key of the entry in the file that has the mistake
+    pub key: String,
+    pub message: String,
+}
+
+/// Loads data files and collects the mistakes found in them
+pub(crate) struct DataValidator {
+    data_dir: PathBuf,
+    /// Files that have already been loaded as their actual type
+    loaded_files: HashSet<String>,
+    errors: Vec<DataValidationError>,
+}
+
+impl DataValidator {
+    fn new(data_dir: &Path) -> Self {
+        DataValidator {
+            data_dir: data_dir.to_path_buf(),
+            loaded_files: HashSet::new(),
+            errors: vec![],
+        }
+    }
+
+    /// Read and parse a data file, reporting an error if it can't be read or parsed
+    pub fn load<T: DeserializeOwned>(&mut self, file: &str) -> Option<T> {
+        self.loaded_files.insert(file.to_string());
+
+        match read_to_string(self.data_dir.join(file)) {
+            Ok(contents) => match from_str::<T>(&contents) {
+                Ok(data) => Some(data),
+                Err(err) => {
+                    self.report(file, "-", format!("failed to parse: {err}"));
+                    None
+                }
+            },
+            Err(err) => {
+                self.report(file, "-", format!("failed to read: {err}"));
+                None
+            }
+        }
+    }
+
+    /// Record a mistake in the entry of a data file
+    pub fn report(&mut self, file: &str, key: impl Display, message: impl Into<String>) {
+        self.errors.push(DataValidationError {
+            file: file.to_string(),
+            key: key.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// Check that the files that no validation has loaded are at least valid RON
+    fn check_remaining_files(&mut self) {
+        let entries = match read_dir(&self.data_dir) {
+            Ok(entries) => entries,
+            Err(err) => {
+                let data_dir = self.data_dir.display().to_string();
+                self.report(&data_dir, "-", format!("failed to read directory: {err}"));
+                return;
+            }
+        };
+
+        let mut files: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|file| file.ends_with(".ron") && !self.loaded_files.contains(file))
+            .collect();
+        files.sort();
+
+        for file in files {
+            self.load::<ron::Value>(&file);
+        }
+    }
+}
+
+/// Load every file in the data directory and return every broken reference that was found
+pub(super) fn validate_data(data_dir: &Path) -> Vec<DataValidationError> {
+    let mut validator = DataValidator::new(data_dir);
+
+    let mob_types = spawnable::validate_data(&mut validator);
+    run::validate_data(&mut validator, &mob_types);
+    game::validate_data(&mut validator);
+    validator.check_remaining_files();
+
+    // data is mostly stored in hash maps, so sort to report errors in a consistent order
+    let mut errors = validator.errors;
+    errors.sort_by(|a, b| (&a.file, &a.key).cmp(&(&b.file, &b.key)));
+    errors
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::validate_data;
+
+    #[test]
+    fn test_assets_data_is_valid() {
+        let data_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/data");
+
+        assert_eq!(validate_data(&data_dir), vec![]);
+    }
+
+    #[test]
+    fn test_missing_data_dir_is_reported() {
+        let errors = validate_data(Path::new("not/a/data/dir"));
+
+        assert!(errors.iter().any(|err| err.file == "mobs.ron"));
+    }
+}
@@ -18,8 +18,11 @@ use thetawave_interface::{
     weapon::{FireMode, SpreadPattern, WeaponComponent, WeaponProjectileData},
 };
 
-use crate::spawnable::{FireWeaponEvent, InitialMotion};
-use rand::{thread_rng, Rng};
+use crate::{
+    spawnable::{FireWeaponEvent, InitialMotion, TargetingComponent},
+    status_effect::{is_stunned, StatusEffectsComponent},
+};
+use rand::Rng;
 
 /// Emits `FireWeaponEvent`s at the proper times.
 pub(super) struct WeaponPlugin;
@@ -62,14 +65,37 @@ impl WeaponExt for WeaponComponent {
     }
 }
 
-/// Update all weapons, and fire weapons with the automatic fire mode
+/// Update all weapons, and fire weapons with the automatic fire mode. Aimed weapons are fired
+/// toward the target of their spawnable.
 fn update_weapon_system(
-    mut weapon_query: Query<(Entity, &mut WeaponComponent, &Transform, &Velocity)>,
+    mut weapon_query: Query<(
+        Entity,
+        &mut WeaponComponent,
+        &Transform,
+        &Velocity,
+        Option<&TargetingComponent>,
+        Option<&StatusEffectsComponent>,
+    )>,
     time: Res<Time>,
     mut fire_weapon: EventWriter<FireWeaponEvent>,
 ) {
-    for (entity, mut weapon, transform, velocity) in weapon_query.iter_mut() {
-        if let Some(weapon_projectile_data) = weapon.update(time.delta()) {
+    for (entity, mut weapon, transform, velocity, targeting, status_effects) in
+        weapon_query.iter_mut()
+    {
+        // stunned entities can't fire, and their weapons don't reload
+        if is_stunned(status_effects) {
+            continue;
+        }
+
+        if let Some(mut weapon_projectile_data) = weapon.update(time.delta()) {
+            if weapon_projectile_data.aimed {
+                if let Some(angle) = targeting.and_then(|targeting| {
+                    targeting.get_angle_to_target(transform.translation.truncate())
+                }) {
+                    weapon_projectile_data.direction = angle;
+                }
+            }
+
             // pass velocity into the spawned blast
             let initial_motion = InitialMotion {
                 linvel: Some(velocity.linvel),
@@ -89,11 +115,11 @@ fn update_weapon_system(
 pub(crate) trait WeaponProjectileInitialVelocitiesExt {
     /// The initial velocities of `n` projectiles using existing/'partially evaluated' params.
     /// Could be evenly spaced, or something else based on the struct params. max_projectiles
-    /// should be greater than 0.
-    fn get_linvels(&self, max_projectiles: u16) -> Vec<Vec2>;
+    /// should be greater than 0. Random spread patterns draw from `rng`.
+    fn get_linvels(&self, max_projectiles: u16, rng: &mut impl Rng) -> Vec<Vec2>;
 }
 impl WeaponProjectileInitialVelocitiesExt for WeaponProjectileData {
-    fn get_linvels(&self, max_projectiles: u16) -> Vec<Vec2> {
+    fn get_linvels(&self, max_projectiles: u16, rng: &mut impl Rng) -> Vec<Vec2> {
         match &self.spread_pattern {
             SpreadPattern::Arc(arc_pattern) => {
                 // Get the segment of a spread angle
@@ -134,10 +160,9 @@ impl WeaponProjectileInitialVelocitiesExt for WeaponProjectileData {
                 for _ in 0..self.count {
                     linvels.push(
                         // multiply the speed the projectile by a random angle and velocity multiplier
-                        Vec2::from_angle(
-                            thread_rng().gen_range(random_pattern.angle_range.clone()),
-                        ) * self.speed
-                            * thread_rng().gen_range(random_pattern.speed_range.clone()),
+                        Vec2::from_angle(rng.gen_range(random_pattern.angle_range.clone()))
+                            * self.speed
+                            * rng.gen_range(random_pattern.speed_range.clone()),
                     );
                 }
 
@@ -7,3 +7,14 @@ fn test_cli_help() {
         .args(&["--help"])
         .unwrap();
 }
+
+#[test]
+#[cfg(all(not(target_arch = "wasm32"), feature = "cli"))]
+fn test_cli_validate_data() {
+    // The data shipped with the game should never have broken references.
+    assert_cmd::Command::cargo_bin("thetawave")
+        .unwrap()
+        .args(&["--validate-data"])
+        .assert()
+        .success();
+}